@@ -0,0 +1,62 @@
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+use std::hint::black_box;
+
+use advent_of_code_2025::runner::{self, Solution};
+use criterion::{Criterion, criterion_group, criterion_main};
+
+/// Benchmarks the generator and every part of one day, using its real input.
+fn bench_day(c: &mut Criterion, day: u8, solutions: &[Solution]) {
+    let Ok(input) = runner::read_input(&runner::input_path(runner::YEAR, day)) else {
+        eprintln!("Skipping day {day}: no input");
+        return;
+    };
+    let mut group = c.benchmark_group(format!("day{day:02}"));
+    if let Some(first) = solutions.first() {
+        group.bench_function("parse", |b| {
+            b.iter(|| first.generate(black_box(&input)));
+        });
+    }
+    for solution in solutions {
+        let runner = match solution.generate(&input) {
+            Ok(runner) => runner,
+            Err(err) => {
+                eprintln!("Skipping {solution}: {err}");
+                continue;
+            }
+        };
+        let id = solution.name.map_or_else(
+            || format!("part{}", solution.part),
+            |name| format!("part{}/{name}", solution.part),
+        );
+        group.bench_function(id, |b| b.iter(|| runner.try_run()));
+    }
+    group.finish();
+}
+
+fn bench_days(c: &mut Criterion) {
+    for solutions in runner::solutions_for(runner::YEAR).chunk_by(|a, b| a.day == b.day) {
+        bench_day(c, solutions[0].day, solutions);
+    }
+}
+
+/// Benchmarks the day 3 part 1 variants on a few banks hundreds of thousands of digits
+/// long, where the chunked scan pays off.
+fn bench_long_banks(c: &mut Criterion) {
+    let bank: String = (0..300_000_u32)
+        .map(|i| char::from(b'1' + u8::try_from(i * 7 % 8).unwrap()))
+        .collect();
+    let input = [bank.as_str(); 8].join("\n");
+    let mut group = c.benchmark_group("day03-long");
+    for name in [None, Some("Chunked")] {
+        let Some(solution) = runner::find(runner::YEAR, 3, 1, name) else {
+            continue;
+        };
+        let runner = solution.generate(&input).expect("valid input");
+        group.bench_function(name.unwrap_or("part1"), |b| b.iter(|| runner.try_run()));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_days, bench_long_banks);
+criterion_main!(benches);
@@ -0,0 +1,171 @@
+//! Discovers the `src/day_NN.rs` modules and the solutions registered in them
+//! with `#[aoc(dayN, partM)]`, so that adding a file is enough to have it run.
+//! Days whose `dayNN` feature is disabled are left out entirely.
+//!
+//! Earlier years live in `src/yYYYY/day_NN.rs`, enabled as a whole by their `yYYYY` feature.
+//! aoc-runner keeps a single registry for the crate, which is this year's, so their days are
+//! registered by their `DaySolution` alone.
+
+use std::fmt::Write as _;
+use std::path::Path;
+use std::{env, fs};
+
+struct Solution {
+    part: u8,
+    name: Option<String>,
+}
+
+struct Day {
+    module: String,
+    day: u8,
+    solutions: Vec<Solution>,
+}
+
+fn main() {
+    println!("cargo::rerun-if-changed=src");
+    println!("cargo::rerun-if-changed=templates");
+    let mut out = String::new();
+    out.push_str("/// Invokes `$callback!` with every day module, its `DaySolution` struct and its registered solutions,\n");
+    out.push_str(
+        "/// first for the current year, then the days of each earlier year in its `yYYYY` module.\n",
+    );
+    out.push_str("macro_rules! for_each_day {\n    ($callback:ident) => {\n        $callback! {\n");
+    out.push_str("            {\n");
+    write_days(&mut out, &find_days(Path::new("src"), false));
+    out.push_str("            }\n");
+    let mut years = Vec::new();
+    for entry in fs::read_dir("src").expect("src directory") {
+        let path = entry.expect("directory entry").path();
+        let Some(year) = path
+            .file_name()
+            .and_then(|name| name.to_str()?.strip_prefix('y')?.parse::<u16>().ok())
+        else {
+            continue;
+        };
+        if path.is_dir() && env::var_os(format!("CARGO_FEATURE_Y{year}")).is_some() {
+            years.push((year, path));
+        }
+    }
+    years.sort();
+    for (year, path) in years {
+        writeln!(out, "            y{year} {year} {{").unwrap();
+        for day in find_days(&path, true) {
+            writeln!(out, "                {} Day{:02}", day.module, day.day).unwrap();
+        }
+        out.push_str("            }\n");
+    }
+    out.push_str("        }\n    };\n}\n");
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR set by cargo");
+    fs::write(Path::new(&out_dir).join("days.rs"), out).expect("writable OUT_DIR");
+    write_scaffolded_day(Path::new(&out_dir));
+}
+
+/// Renders `templates/day.rs` like `aoc new` would, for the first day without a module, so
+/// the tests can compile it and catch a template that no longer fits the crate.
+fn write_scaffolded_day(out_dir: &Path) {
+    let template = fs::read_to_string("templates/day.rs").expect("readable day template");
+    let day = (1..=25_u8).find(|day| !Path::new(&format!("src/day_{day:02}.rs")).exists());
+    let source = day.map_or_else(String::new, |day| {
+        template
+            .replace("@DAY2@", &format!("{day:02}"))
+            .replace("@DAY@", &day.to_string())
+    });
+    let day = day.map_or_else(String::new, |day| day.to_string());
+    println!("cargo::rustc-env=SCAFFOLDED_DAY={day}");
+    fs::write(out_dir.join("scaffolded_day.rs"), source).expect("writable OUT_DIR");
+}
+
+/// Finds the `day_NN.rs` modules in `dir`. Days of the current year are left out when their
+/// `dayNN` feature is disabled; those of earlier years register no solutions.
+fn find_days(dir: &Path, earlier_year: bool) -> Vec<Day> {
+    let mut days = Vec::new();
+    for entry in fs::read_dir(dir).expect("source directory") {
+        let path = entry.expect("directory entry").path();
+        if path.extension().is_none_or(|ext| ext != "rs") {
+            continue;
+        }
+        let Some(module) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        let Some(day) = module.strip_prefix("day_").and_then(|d| d.parse().ok()) else {
+            continue;
+        };
+        if earlier_year {
+            days.push(Day {
+                module: module.to_string(),
+                day,
+                solutions: Vec::new(),
+            });
+            continue;
+        }
+        if env::var_os(format!("CARGO_FEATURE_DAY{day:02}")).is_none() {
+            continue;
+        }
+        let source = fs::read_to_string(&path).expect("readable day module");
+        days.push(Day {
+            module: module.to_string(),
+            day,
+            solutions: find_solutions(&source, day),
+        });
+    }
+    days.sort_by_key(|day| day.day);
+    days
+}
+
+fn write_days(out: &mut String, days: &[Day]) {
+    for day in days {
+        write!(
+            out,
+            "                {} Day{:02} {} {{",
+            day.module, day.day, day.day
+        )
+        .unwrap();
+        for solution in &day.solutions {
+            let (name, suffix_snake, suffix_camel) = match &solution.name {
+                Some(name) => (
+                    format!("Some({name:?})"),
+                    format!("_{}", name.to_lowercase()),
+                    name.to_uppercase(),
+                ),
+                None => ("None".to_string(), String::new(), String::new()),
+            };
+            write!(
+                out,
+                " ({part}, {name}, Day{day}Part{part}{suffix_camel}, day{day}_part{part}{suffix_snake})",
+                part = solution.part,
+                day = day.day,
+            )
+            .unwrap();
+        }
+        out.push_str(" }\n");
+    }
+}
+
+/// Finds the `#[aoc(dayN, partM)]` and `#[aoc(dayN, partM, Name)]` attributes for `day`.
+fn find_solutions(source: &str, day: u8) -> Vec<Solution> {
+    let mut solutions = Vec::new();
+    for line in source.lines() {
+        let Some(args) = line
+            .trim()
+            .strip_prefix("#[aoc(")
+            .and_then(|rest| rest.strip_suffix(")]"))
+        else {
+            continue;
+        };
+        let mut args = args.split(',').map(str::trim);
+        if args.next() != Some(format!("day{day}").as_str()) {
+            continue;
+        }
+        let Some(part) = args
+            .next()
+            .and_then(|p| p.strip_prefix("part")?.parse().ok())
+        else {
+            continue;
+        };
+        let name = args.next().map(str::to_string);
+        solutions.push(Solution { part, name });
+    }
+    solutions.sort_by(|a, b| (a.part, &a.name).cmp(&(b.part, &b.name)));
+    solutions
+}
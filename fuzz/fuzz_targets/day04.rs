@@ -0,0 +1,9 @@
+#![no_main]
+
+use advent_of_code_2025::DaySolution;
+use advent_of_code_2025::days::Day04;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = Day04::parse(input);
+});
@@ -0,0 +1,22 @@
+/* C interface to the Advent of Code 2025 solutions, built with
+ * `cargo rustc --lib --release --features ffi --crate-type cdylib`. */
+#ifndef AOC_H
+#define AOC_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#define AOC_OK 0
+#define AOC_NULL_POINTER -1
+#define AOC_INVALID_UTF8 -2
+#define AOC_NO_SOLUTION -3
+#define AOC_SOLVER_ERROR -4
+#define AOC_BUFFER_TOO_SMALL -5
+#define AOC_PANIC -6
+
+/* Solves `part` of `day` for the `len` bytes of UTF-8 at `input`, writing the
+ * NUL-terminated answer (or error message, for AOC_SOLVER_ERROR) to `out_buf`. */
+int32_t aoc_solve(uint8_t day, uint8_t part, const uint8_t *input, size_t len,
+                  uint8_t *out_buf, size_t out_len);
+
+#endif
@@ -0,0 +1,116 @@
+//! A counting global allocator, installed with the `alloc-stats` feature, that tracks the
+//! number of allocations and the peak heap usage.
+//!
+//! With `dhat-heap` also enabled, dhat's allocator is installed instead and nothing is counted.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(all(feature = "alloc-stats", not(feature = "dhat-heap")))]
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Wraps the system allocator, counting every allocation.
+pub struct CountingAlloc;
+
+impl CountingAlloc {
+    fn grow(size: usize) {
+        let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn shrink(size: usize) {
+        CURRENT.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            Self::grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            Self::grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        Self::shrink(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            if new_size > layout.size() {
+                Self::grow(new_size - layout.size());
+            } else {
+                Self::shrink(layout.size() - new_size);
+            }
+        }
+        new_ptr
+    }
+}
+
+/// Heap usage while running a closure.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Number of allocations and reallocations.
+    pub allocations: usize,
+    /// Highest heap usage, in bytes, above the usage when the closure started.
+    pub peak_bytes: usize,
+}
+
+/// Whether the counting allocator is installed.
+#[must_use]
+pub const fn enabled() -> bool {
+    cfg!(all(feature = "alloc-stats", not(feature = "dhat-heap")))
+}
+
+/// Runs `f`, returning its heap usage if the counting allocator is installed.
+///
+/// The counters are global, so allocations from other threads are included.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    if !enabled() {
+        return (f(), None);
+    }
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(start, Ordering::Relaxed);
+    let result = f();
+    let stats = AllocStats {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        peak_bytes: PEAK.load(Ordering::Relaxed).saturating_sub(start),
+    };
+    (result, Some(stats))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measure() {
+        let (len, stats) = measure(|| Vec::<u8>::with_capacity(1000).capacity());
+        assert_eq!(len, 1000);
+        if enabled() {
+            assert!(stats.unwrap().allocations >= 1);
+        } else {
+            assert_eq!(stats, None);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::convert::Infallible;
+use std::fmt::Display;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+/// The answer to one part of a puzzle.
+///
+/// Answers compare equal when they would be submitted as the same text, so `U64(3)`,
+/// `I64(3)` and `Text("3")` are all the same answer.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    U64(u64),
+    I64(i64),
+    U128(u128),
+    /// Anything that is not a number, such as letters read from ASCII art.
+    Text(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::U64(n) => write!(f, "{n}"),
+            Self::I64(n) => write!(f, "{n}"),
+            Self::U128(n) => write!(f, "{n}"),
+            Self::Text(text) => f.write_str(text),
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::U64(a), Self::U64(b)) => a == b,
+            (Self::I64(a), Self::I64(b)) => a == b,
+            (Self::U128(a), Self::U128(b)) => a == b,
+            (Self::Text(a), Self::Text(b)) => a == b,
+            _ => self.to_string() == other.to_string(),
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+impl Hash for Answer {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_string().hash(state);
+    }
+}
+
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    /// Reads an answer back from its text, as the narrowest variant that holds it.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(s.parse()
+            .map(Self::U64)
+            .or_else(|_| s.parse().map(Self::I64))
+            .or_else(|_| s.parse().map(Self::U128))
+            .unwrap_or_else(|_| Self::Text(s.to_string())))
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+macro_rules! impl_from_int {
+    ($variant:ident: $($ty:ty)*) => {
+        $(impl From<$ty> for Answer {
+            fn from(value: $ty) -> Self {
+                Self::$variant(value.into())
+            }
+        })*
+    };
+}
+
+impl_from_int!(U64: u8 u16 u32 u64);
+impl_from_int!(I64: i8 i16 i32 i64);
+impl_from_int!(U128: u128);
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        u64::try_from(value).map_or_else(|_| Self::U128(value as u128), Self::U64)
+    }
+}
+
+impl From<isize> for Answer {
+    fn from(value: isize) -> Self {
+        Self::I64(value as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from() {
+        assert_eq!(Answer::from(42_usize), Answer::U64(42));
+        assert_eq!(Answer::from(-7_i32), Answer::I64(-7));
+        assert_eq!(Answer::from(u128::MAX), Answer::U128(u128::MAX));
+        assert_eq!(Answer::from("ABC"), Answer::Text("ABC".to_string()));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::I64(-7).to_string(), "-7");
+        assert_eq!(
+            Answer::U128(1 << 100).to_string(),
+            "1267650600228229401496703205376"
+        );
+        assert_eq!(Answer::Text("ABC".into()).to_string(), "ABC");
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("3".parse(), Ok(Answer::U64(3)));
+        assert_eq!("-3".parse(), Ok(Answer::I64(-3)));
+        assert_eq!("18446744073709551616".parse(), Ok(Answer::U128(1 << 64)));
+        assert_eq!("EHZ".parse(), Ok(Answer::Text("EHZ".into())));
+    }
+
+    #[test]
+    fn test_eq_across_variants() {
+        assert_eq!(Answer::U64(3), Answer::I64(3));
+        assert_eq!(Answer::U128(3), Answer::Text("3".into()));
+        assert_ne!(Answer::U64(3), Answer::I64(-3));
+    }
+}
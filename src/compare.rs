@@ -0,0 +1,85 @@
+//! Runs every registered variant of a day's solutions on the same input, checking that they
+//! agree and how they perform relative to the default solution.
+
+use std::error::Error;
+
+use crate::runner::{self, Outcome, Solution};
+
+#[derive(Debug)]
+pub struct Variant {
+    pub solution: &'static Solution,
+    pub outcome: Outcome,
+}
+
+/// All variants of one part, the default solution first.
+#[derive(Debug)]
+pub struct Comparison {
+    pub part: u8,
+    pub variants: Vec<Variant>,
+}
+
+impl Comparison {
+    /// Whether every variant produced the same answer.
+    #[must_use]
+    pub fn agrees(&self) -> bool {
+        self.variants
+            .windows(2)
+            .all(|pair| pair[0].outcome.answer == pair[1].outcome.answer)
+    }
+
+    /// Solver time of `variant` relative to the first variant.
+    #[must_use]
+    pub fn relative_time(&self, variant: &Variant) -> f64 {
+        let base = self.variants[0].outcome.run_time.as_secs_f64();
+        if base == 0.0 {
+            1.0
+        } else {
+            variant.outcome.run_time.as_secs_f64() / base
+        }
+    }
+}
+
+/// Runs every variant of every part of `day` of `year` on `input`.
+///
+/// # Errors
+///
+/// Returns the first error from a generator or solver.
+pub fn compare_day(year: u16, day: u8, input: &str) -> Result<Vec<Comparison>, Box<dyn Error>> {
+    let mut comparisons = Vec::new();
+    let solutions = runner::solutions_for(year).iter().filter(|s| s.day == day);
+    for solution in solutions {
+        let outcome = solution
+            .run(input)
+            .map_err(|err| format!("{solution}: {err}"))?;
+        let variant = Variant { solution, outcome };
+        match comparisons.last_mut() {
+            Some(Comparison { part, variants }) if *part == solution.part => variants.push(variant),
+            _ => comparisons.push(Comparison {
+                part: solution.part,
+                variants: vec![variant],
+            }),
+        }
+    }
+    Ok(comparisons)
+}
+
+#[cfg(all(test, feature = "day10"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_day() {
+        let input = "\
+            [.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}\n\
+            [...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}\n\
+            [.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}";
+        let comparisons = compare_day(runner::YEAR, 10, input).unwrap();
+        assert_eq!(comparisons.len(), 2);
+        let part_1 = &comparisons[0];
+        assert_eq!(part_1.variants.len(), 2);
+        assert_eq!(part_1.variants[0].solution.name, None);
+        assert_eq!(part_1.variants[1].solution.name, Some("BruteForce"));
+        assert!(part_1.agrees());
+        assert!((part_1.relative_time(&part_1.variants[0]) - 1.0).abs() < f64::EPSILON);
+    }
+}
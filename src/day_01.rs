@@ -1,56 +1,236 @@
 use std::num::ParseIntError;
+use std::sync::Arc;
 
 use thiserror::Error;
 
+use crate::shared::input::{InputError, Locate};
+use crate::shared::parse::normalize_input;
+use crate::{Answer, AocError, DaySolution};
+
 #[derive(Debug, Error)]
 enum ParseError {
+    #[error("Empty line")]
+    EmptyLine,
     #[error("Syntax error")]
     SyntaxError,
     #[error(transparent)]
     InvalidNumber(#[from] ParseIntError),
 }
 
+impl Locate for ParseError {
+    fn offset(&self, _text: &str) -> Option<usize> {
+        None
+    }
+}
+
+/// Positions on the dial of the puzzle.
+const DIAL_SIZE: i64 = 100;
+
+/// Where the dial of the puzzle starts.
+const START: i64 = 50;
+
 #[aoc_generator(day1)]
-fn parse(input: &str) -> Result<Vec<i16>, ParseError> {
+fn parse(input: &str) -> Result<Vec<i64>, InputError<ParseError>> {
+    rotations(&normalize_input(input)).collect()
+}
+
+/// The rotations of `input`, with errors located at their line.
+fn rotations(input: &str) -> impl Iterator<Item = Result<i64, InputError<ParseError>>> {
     input
         .lines()
-        .map(|w| {
-            Ok(match w.as_bytes()[0] {
-                b'L' => -w[1..].parse::<i16>()?,
-                b'R' => w[1..].parse::<i16>()?,
-                _ => return Err(ParseError::SyntaxError),
-            })
-        })
-        .collect()
+        .enumerate()
+        .map(|(row, line)| parse_rotation(line).map_err(|err| InputError::in_line(row, line, err)))
+}
+
+/// Parses a rotation like `L68` or `r5`, ignoring surrounding whitespace.
+fn parse_rotation(line: &str) -> Result<i64, ParseError> {
+    let line = line.trim();
+    let mut chars = line.chars();
+    let dir = chars.next().ok_or(ParseError::EmptyLine)?;
+    let dist = chars.as_str();
+    if !dist.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ParseError::SyntaxError);
+    }
+    let dist = dist.parse::<i64>()?;
+    Ok(match dir {
+        'L' | 'l' => -dist,
+        'R' | 'r' => dist,
+        _ => return Err(ParseError::SyntaxError),
+    })
+}
+
+/// A dial being turned, counting the turns that end at zero and the times zero is passed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DialLock {
+    size: i64,
+    position: i64,
+    zeros: u64,
+    clicks: u64,
+    history: Option<Vec<i64>>,
+}
+
+impl DialLock {
+    /// A dial of `size` positions, pointing at `start`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is not positive.
+    #[must_use]
+    pub const fn new(size: i64, start: i64) -> Self {
+        assert!(size > 0, "dial size must be positive");
+        Self {
+            size,
+            position: start.rem_euclid(size),
+            zeros: 0,
+            clicks: 0,
+            history: None,
+        }
+    }
+
+    /// Records the position after every rotation from here on, for [`DialLock::history`].
+    #[must_use]
+    pub fn with_history(mut self) -> Self {
+        self.history = Some(vec![self.position]);
+        self
+    }
+
+    /// Turns the dial right by `delta` positions, or left if it is negative.
+    pub fn apply(&mut self, delta: i64) {
+        let rest = self.position + delta % self.size;
+        self.clicks += delta.unsigned_abs() / self.size.unsigned_abs()
+            + u64::from(self.position != 0 && (rest <= 0 || rest >= self.size));
+        self.position = rest.rem_euclid(self.size);
+        self.zeros += u64::from(self.position == 0);
+        if let Some(history) = &mut self.history {
+            history.push(self.position);
+        }
+    }
+
+    #[must_use]
+    pub const fn position(&self) -> i64 {
+        self.position
+    }
+
+    /// Rotations that ended at zero.
+    #[must_use]
+    pub const fn zeros(&self) -> u64 {
+        self.zeros
+    }
+
+    /// Times the dial has pointed at zero, during or at the end of a rotation.
+    #[must_use]
+    pub const fn clicks(&self) -> u64 {
+        self.clicks
+    }
+
+    /// The recorded positions, starting with the one history was enabled at; empty unless
+    /// enabled with [`DialLock::with_history`].
+    pub fn history(&self) -> impl Iterator<Item = i64> + '_ {
+        self.history.iter().flatten().copied()
+    }
 }
 
-fn unlock(input: &[i16]) -> (u64, u64) {
-    let mut position = 50;
-    let mut zeros = 0;
-    let mut clicks = 0;
-    for &delta in input {
-        clicks += u64::from(delta.unsigned_abs()) / 100
-            + u64::from(
-                position == 0 || position + delta % 100 < 0 || position + delta % 100 > 100,
-            );
-        position = (position + delta).rem_euclid(100);
-        zeros += u64::from(position == 0);
+/// Turns a dial of `dial_size` positions from `start` by each of `deltas`, returning the
+/// turns that end at zero and the times zero is passed.
+fn unlock_with(dial_size: i64, start: i64, deltas: &[i64]) -> (u64, u64) {
+    let mut dial = DialLock::new(dial_size, start);
+    for &delta in deltas {
+        dial.apply(delta);
     }
-    (zeros, clicks)
+    (dial.zeros, dial.clicks)
 }
 
 #[aoc(day1, part1)]
-fn part_1(input: &[i16]) -> u64 {
-    unlock(input).0
+fn part_1(input: &[i64]) -> u64 {
+    unlock_with(DIAL_SIZE, START, input).0
 }
 
 #[aoc(day1, part2)]
-fn part_2(input: &[i16]) -> u64 {
-    unlock(input).1
+fn part_2(input: &[i64]) -> u64 {
+    unlock_with(DIAL_SIZE, START, input).1
+}
+
+/// Hands the input over as is, for the streaming solvers to parse as they go.
+#[aoc_generator(day1, part1, Streaming)]
+#[aoc_generator(day1, part2, Streaming)]
+fn raw(input: &Arc<str>) -> Arc<str> {
+    Arc::clone(input)
+}
+
+/// Parses and turns the dial one line at a time, without collecting the rotations or
+/// copying the input to normalize it.
+fn unlock_streaming(input: &str) -> Result<(u64, u64), InputError<ParseError>> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let mut dial = DialLock::new(DIAL_SIZE, START);
+    // The first blank line since the last rotation: an error only if another one follows.
+    let mut blank = None;
+    for (row, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            blank = blank.or(Some((row, line)));
+            continue;
+        }
+        if let Some((row, line)) = blank {
+            return Err(InputError::in_line(row, line, ParseError::EmptyLine));
+        }
+        let rotation = parse_rotation(line).map_err(|err| InputError::in_line(row, line, err))?;
+        dial.apply(rotation);
+    }
+    Ok((dial.zeros, dial.clicks))
+}
+
+#[aoc(day1, part1, Streaming)]
+fn part_1_streaming(input: &str) -> Result<u64, InputError<ParseError>> {
+    Ok(unlock_streaming(input)?.0)
+}
+
+#[aoc(day1, part2, Streaming)]
+fn part_2_streaming(input: &str) -> Result<u64, InputError<ParseError>> {
+    Ok(unlock_streaming(input)?.1)
+}
+
+pub struct Day01;
+
+impl Day01 {
+    /// Turns a dial of `dial_size` positions from `start` by each of `deltas`, returning the
+    /// answers of both parts for that dial.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dial_size` is not positive.
+    #[must_use]
+    pub fn unlock_with(dial_size: i64, start: i64, deltas: &[i64]) -> (u64, u64) {
+        unlock_with(dial_size, start, deltas)
+    }
+
+    /// The dial of the puzzle, recording its history, to turn step by step.
+    #[must_use]
+    pub fn dial_lock() -> DialLock {
+        DialLock::new(DIAL_SIZE, START).with_history()
+    }
+}
+
+impl DaySolution for Day01 {
+    const DAY: u8 = 1;
+
+    type Parsed = Vec<i64>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        parse(input).map_err(AocError::invalid_input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part_1(parsed).into())
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part_2(parsed).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use test_case::test_case;
+
     use super::*;
 
     const EXAMPLE1: &str = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82";
@@ -61,6 +241,34 @@ mod tests {
         assert_eq!(result, [-68, -30, 48, -5, 60, -55, -1, -99, 14, -82]);
     }
 
+    #[test]
+    fn test_parse_tolerant() {
+        let result = parse(" l68\r\nR30 \r\n\tr5\r\n").unwrap();
+        assert_eq!(result, [-68, 30, 5]);
+    }
+
+    #[test_case("L-32768"; "negative distance")]
+    #[test_case("R+5"; "explicit sign")]
+    #[test_case("Ä5"; "multibyte direction")]
+    #[test_case("R 5"; "space before distance")]
+    fn test_parse_invalid(input: &str) {
+        assert!(matches!(
+            parse(input),
+            Err(InputError {
+                line: 1,
+                error: ParseError::SyntaxError,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_empty_line() {
+        let err = parse("L68\n  \nR5").unwrap_err();
+        assert!(matches!(err.error, ParseError::EmptyLine));
+        assert_eq!(err.to_string(), "Empty line at line 2");
+    }
+
     #[test]
     fn test_part_1() {
         let input = parse(EXAMPLE1).unwrap();
@@ -74,4 +282,83 @@ mod tests {
         let result = part_2(&input);
         assert_eq!(result, 6);
     }
+
+    #[test]
+    fn test_streaming() {
+        assert_eq!(part_1_streaming(EXAMPLE1).unwrap(), 3);
+        assert_eq!(part_2_streaming(EXAMPLE1).unwrap(), 6);
+        let err = part_1_streaming("L68\nX30").unwrap_err();
+        assert_eq!(err.line, 2);
+        assert!(matches!(err.error, ParseError::SyntaxError));
+        let messy = format!("\u{feff}{}\r\n\n \n", EXAMPLE1.replace('\n', "\r\n"));
+        assert_eq!(part_2_streaming(&messy).unwrap(), 6);
+        let err = part_1_streaming("L68\n\n \nR30").unwrap_err();
+        assert_eq!(err.line, 2);
+        assert!(matches!(err.error, ParseError::EmptyLine));
+    }
+
+    #[test]
+    fn test_dial_lock() {
+        let mut dial = Day01::dial_lock();
+        for delta in parse(EXAMPLE1).unwrap() {
+            dial.apply(delta);
+        }
+        assert_eq!((dial.position(), dial.zeros(), dial.clicks()), (32, 3, 6));
+        assert!(dial.history().eq([50, 82, 52, 0, 95, 55, 0, 99, 0, 14, 32]));
+        assert_eq!(DialLock::new(10, 3).history().count(), 0);
+    }
+
+    #[test_case(50, 50, 1, 1; "onto zero")]
+    #[test_case(50, -50, 1, 1; "onto zero from the left")]
+    #[test_case(0, 5, 0, 0; "away from zero")]
+    #[test_case(50, 150, 1, 2; "past and onto zero")]
+    #[test_case(0, -100, 1, 1; "full circle from zero")]
+    fn test_dial_lock_clicks(start: i64, delta: i64, zeros: u64, clicks: u64) {
+        let mut dial = DialLock::new(100, start);
+        dial.apply(delta);
+        assert_eq!((dial.zeros(), dial.clicks()), (zeros, clicks));
+    }
+
+    #[test]
+    fn test_large_rotation() {
+        let input = parse("R40050\nL100000").unwrap();
+        assert_eq!(input, [40_050, -100_000]);
+        assert!(matches!(
+            parse("R9223372036854775808").map_err(|err| err.error),
+            Err(ParseError::InvalidNumber(_))
+        ));
+        assert_eq!(unlock_with(DIAL_SIZE, START, &input), (2, 1401));
+        // Rotations this long would overflow if added to the position before reducing.
+        let input = parse("R9223372036854775807\nL9223372036854775807").unwrap();
+        let mut dial = DialLock::new(DIAL_SIZE, START);
+        dial.apply(input[0]);
+        assert_eq!(
+            (dial.position(), dial.clicks()),
+            (57, 92_233_720_368_547_758)
+        );
+        dial.apply(input[1]);
+        assert_eq!(
+            (dial.position(), dial.clicks()),
+            (50, 2 * 92_233_720_368_547_758)
+        );
+    }
+
+    #[test]
+    #[should_panic = "dial size must be positive"]
+    fn test_negative_dial_size() {
+        let _ = Day01::unlock_with(-10, 3, &[5]);
+    }
+
+    #[test]
+    fn test_unlock_with() {
+        let input = parse(
+            "L3
+R10
+L7",
+        )
+        .unwrap();
+        assert_eq!(unlock_with(10, 3, &input), (2, 2));
+        let example = parse(EXAMPLE1).unwrap();
+        assert_eq!(unlock_with(100, 50, &example), (3, 6));
+    }
 }
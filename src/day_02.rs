@@ -2,6 +2,11 @@ use std::num::ParseIntError;
 
 use thiserror::Error;
 
+use crate::shared::parse::normalize_input_trimmed;
+use crate::shared::ranges::RangeSet;
+use crate::shared::{math, par};
+use crate::{Answer, AocError, DaySolution};
+
 #[derive(Debug, Error)]
 enum ParseError {
     #[error("Syntax error")]
@@ -10,8 +15,14 @@ enum ParseError {
     InvalidNumber(#[from] ParseIntError),
 }
 
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("Sum does not fit in 128 bits")]
+struct Overflow;
+
+/// Parses the ranges, merging any that overlap so no ID is counted twice.
 #[aoc_generator(day2)]
-fn parse(input: &str) -> Result<Vec<(u64, u64)>, ParseError> {
+fn parse(input: &str) -> Result<RangeSet<u128>, ParseError> {
+    let input = normalize_input_trimmed(input);
     input
         .split(',')
         .map(|r| {
@@ -21,50 +32,181 @@ fn parse(input: &str) -> Result<Vec<(u64, u64)>, ParseError> {
         .collect()
 }
 
-#[aoc(day2, part1)]
-#[expect(clippy::unreadable_literal, reason = "More readable")]
-fn part_1(ranges: &[(u64, u64)]) -> u64 {
-    ranges
-        .iter()
-        .map(|&(start, end)| {
-            (start..=end)
-                .filter(|x| match x {
-                    10..=99 => x % 11 == 0,
-                    1000..=9999 => x % 101 == 0,
-                    100000..=999999 => x % 1001 == 0,
-                    10000000..=99999999 => x % 10001 == 0,
-                    1000000000..=9999999999 => x % 100001 == 0,
-                    _ => false,
-                })
-                .sum::<u64>()
+/// A divisor whose multiples of some width are the numbers made of a repeated block, and
+/// whether its multiples are added or, to undo counting them twice, subtracted.
+type Term = (u128, bool);
+
+/// Decimal digits of the widest numbers the ranges can hold.
+const MAX_WIDTH: u32 = u128::MAX.ilog10() + 1;
+
+/// The divisor whose `width`-digit multiples are the numbers made of a `block`-digit block
+/// repeated, like 10101 for width 6 and block 2.
+fn block_divisor(width: u32, block: u32) -> u128 {
+    (0..width / block).map(|i| 10_u128.pow(i * block)).sum()
+}
+
+/// Terms for the `width`-digit numbers made of a block repeated twice.
+fn twice_terms(width: u32) -> Vec<Term> {
+    if width.is_multiple_of(2) {
+        vec![(block_divisor(width, width / 2), true)]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Terms for the `width`-digit numbers made of a block repeated at least twice.
+///
+/// Any repeated block also repeats as a block of `width / p` digits for a prime `p` dividing
+/// the width, and numbers repeating blocks for several primes repeat the block for their
+/// product, so inclusion–exclusion over those primes counts each number once.
+fn repeated_terms(width: u32) -> Vec<Term> {
+    let primes = math::prime_factors(width.into());
+    (1_u32..1 << primes.len())
+        .map(|subset| {
+            let product: u64 = primes
+                .iter()
+                .enumerate()
+                .filter(|&(ix, _)| subset >> ix & 1 == 1)
+                .map(|(_, &p)| p)
+                .product();
+            let block = width / u32::try_from(product).expect("divides width");
+            (block_divisor(width, block), subset.count_ones() % 2 == 1)
         })
-        .sum()
+        .collect()
+}
+
+/// The terms of every width, starting with width 1.
+fn terms_by_width(terms: fn(u32) -> Vec<Term>) -> Vec<Vec<Term>> {
+    (1..=MAX_WIDTH).map(terms).collect()
+}
+
+/// Sum of the multiples of `divisor` in `start..=end`, or `None` if it does not fit.
+const fn sum_of_multiples(divisor: u128, start: u128, end: u128) -> Option<u128> {
+    let first = start.div_ceil(divisor);
+    let last = end / divisor;
+    if first > last {
+        return Some(0);
+    }
+    let count = last - first + 1;
+    // Of `count` and `first + last`, one is even.
+    let sum = if count.is_multiple_of(2) {
+        match first.checked_add(last) {
+            Some(ends) => (count / 2).checked_mul(ends),
+            None => None,
+        }
+    } else {
+        u128::midpoint(first, last).checked_mul(count)
+    };
+    match sum {
+        Some(sum) => divisor.checked_mul(sum),
+        None => None,
+    }
+}
+
+/// Sum of the numbers in `start..=end` that are multiples of the terms for their width.
+fn sum_patterns(start: u128, end: u128, terms: &[Vec<Term>]) -> Result<u128, Overflow> {
+    let mut total = 0_u128;
+    for (width, terms) in (1..).zip(terms) {
+        let lo = start.max(10_u128.pow(width - 1));
+        let hi = end.min(10_u128.checked_pow(width).map_or(u128::MAX, |p| p - 1));
+        if lo > hi {
+            continue;
+        }
+        let (mut added, mut subtracted) = (0, 0);
+        for &(divisor, add) in terms {
+            let sum = sum_of_multiples(divisor, lo, hi).ok_or(Overflow)?;
+            let side = if add { &mut added } else { &mut subtracted };
+            *side = sum.checked_add(*side).ok_or(Overflow)?;
+        }
+        total = total.checked_add(added - subtracted).ok_or(Overflow)?;
+    }
+    Ok(total)
+}
+
+/// Sum of the numbers in every range that are multiples of the terms for their width.
+fn sum_ranges(ranges: &[(u128, u128)], terms: &[Vec<Term>]) -> Result<u128, Overflow> {
+    par::map(ranges, |&(start, end)| sum_patterns(start, end, terms))
+        .into_iter()
+        .try_fold(0_u128, |total, sum| total.checked_add(sum?).ok_or(Overflow))
+}
+
+#[aoc(day2, part1)]
+fn part_1(ranges: &[(u128, u128)]) -> Result<u128, Overflow> {
+    sum_ranges(ranges, &terms_by_width(twice_terms))
 }
 
 #[aoc(day2, part2)]
-#[expect(clippy::unreadable_literal, reason = "More readable")]
-fn part_2(ranges: &[(u64, u64)]) -> u64 {
+fn part_2(ranges: &[(u128, u128)]) -> Result<u128, Overflow> {
+    sum_ranges(ranges, &terms_by_width(repeated_terms))
+}
+
+/// Whether the digits of `n` are a block repeated twice, or if not `exactly_twice`, at
+/// least twice.
+fn is_repeated(n: u128, exactly_twice: bool) -> bool {
+    let digits = n.to_string();
+    let width = digits.len();
+    (1..width)
+        .filter(|&block| width.is_multiple_of(block) && (!exactly_twice || block * 2 == width))
+        .any(|block| digits == digits[..block].repeat(width / block))
+}
+
+/// Numbers the brute force scans per unit of parallel work.
+const CHUNK_SIZE: u128 = 1 << 14;
+
+/// Splits `ranges` into pieces of at most `size` numbers, so a single wide range still
+/// spreads over every thread.
+fn split_ranges(ranges: &[(u128, u128)], size: u128) -> Vec<(u128, u128)> {
     ranges
         .iter()
-        .map(|&(start, end)| {
-            (start..=end)
-                .filter(|x| match x {
-                    10..=99 => x % 11 == 0,
-                    100..=999 => x % 111 == 0,
-                    1000..=9999 => x % 101 == 0 || x % 1111 == 0,
-                    10000..=99999 => x % 11111 == 0,
-                    100000..=999999 => x % 1001 == 0 || x % 10101 == 0 || x % 111111 == 0,
-                    1000000..=9999999 => x % 1111111 == 0,
-                    10000000..=99999999 => x % 10001 == 0 || x % 1010101 == 0 || x % 11111111 == 0,
-                    100000000..=999999999 => x % 1001001 == 0 || x % 111111111 == 0,
-                    1000000000..=9999999999 => {
-                        x % 100001 == 0 || x % 101010101 == 0 || x % 1111111111 == 0
-                    }
-                    _ => false,
-                })
-                .sum::<u64>()
+        .flat_map(|&(start, end)| {
+            (0..=(end - start) / size).map(move |ix| {
+                let lo = start + ix * size;
+                (lo, end.min(lo + (size - 1)))
+            })
         })
-        .sum()
+        .collect()
+}
+
+#[aoc(day2, part1, BruteForce)]
+fn part_1_brute_force(ranges: &[(u128, u128)]) -> u128 {
+    par::sum(&split_ranges(ranges, CHUNK_SIZE), |&(start, end)| {
+        (start..=end)
+            .filter(|&x| is_repeated(x, true))
+            .sum::<u128>()
+    })
+}
+
+#[aoc(day2, part2, BruteForce)]
+fn part_2_brute_force(ranges: &[(u128, u128)]) -> u128 {
+    par::sum(&split_ranges(ranges, CHUNK_SIZE), |&(start, end)| {
+        (start..=end)
+            .filter(|&x| is_repeated(x, false))
+            .sum::<u128>()
+    })
+}
+
+pub struct Day02;
+
+impl DaySolution for Day02 {
+    const DAY: u8 = 2;
+
+    type Parsed = RangeSet<u128>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        parse(input).map_err(AocError::invalid_input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        part_1(parsed)
+            .map(Answer::from)
+            .map_err(|err| AocError::SolverFailed(err.to_string()))
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        part_2(parsed)
+            .map(Answer::from)
+            .map_err(|err| AocError::SolverFailed(err.to_string()))
+    }
 }
 
 #[cfg(test)]
@@ -81,34 +223,105 @@ mod tests {
     fn test_parse() {
         let result = parse(EXAMPLE1).unwrap();
         assert_eq!(
-            result,
+            result.as_slice(),
             [
                 (11, 22),
                 (95, 115),
                 (998, 1_012),
-                (1_188_511_880, 1_188_511_890),
                 (222_220, 222_224),
-                (1_698_522, 1_698_528),
                 (446_443, 446_449),
-                (38_593_856, 38_593_862),
                 (565_653, 565_659),
+                (1_698_522, 1_698_528),
+                (38_593_856, 38_593_862),
                 (824_824_821, 824_824_827),
+                (1_188_511_880, 1_188_511_890),
                 (2_121_212_118, 2_121_212_124)
             ]
         );
     }
 
+    #[test]
+    fn test_overlapping_ranges() {
+        let ranges = parse("11-22,15-30,11-22,95-115,100-120").unwrap();
+        assert_eq!(ranges.as_slice(), [(11, 30), (95, 120)]);
+        assert_eq!(part_1(&ranges), Ok(11 + 22 + 99));
+        assert_eq!(part_2(&ranges), Ok(11 + 22 + 99 + 111));
+        assert_eq!(part_2(&ranges), Ok(part_2_brute_force(&ranges)));
+    }
+
     #[test]
     fn test_part_1() {
         let ranges = parse(EXAMPLE1).unwrap();
         let result = part_1(&ranges);
-        assert_eq!(result, 1_227_775_554);
+        assert_eq!(result, Ok(1_227_775_554));
     }
 
     #[test]
     fn test_part_2() {
         let ranges = parse(EXAMPLE1).unwrap();
         let result = part_2(&ranges);
-        assert_eq!(result, 4_174_379_265);
+        assert_eq!(result, Ok(4_174_379_265));
+    }
+
+    #[test]
+    fn test_sum_of_multiples() {
+        assert_eq!(sum_of_multiples(11, 11, 22), Some(33));
+        assert_eq!(sum_of_multiples(11, 12, 21), Some(0));
+        assert_eq!(
+            sum_of_multiples(7, 1, 100),
+            Some(7 * (1..=14).sum::<u128>())
+        );
+        assert_eq!(sum_of_multiples(1, 1, u128::MAX), None);
+    }
+
+    #[test]
+    fn test_brute_force() {
+        let mut ranges = parse(EXAMPLE1).unwrap();
+        ranges.extend([
+            (1, 120_000),
+            (999_000, 1_001_100),
+            (1_000_999_000, 1_001_010_000),
+        ]);
+        assert_eq!(part_1(&ranges), Ok(part_1_brute_force(&ranges)));
+        assert_eq!(part_2(&ranges), Ok(part_2_brute_force(&ranges)));
+    }
+
+    #[test]
+    fn test_split_ranges() {
+        assert_eq!(
+            split_ranges(&[(1, 10), (20, 22), (30, 30)], 4),
+            [(1, 4), (5, 8), (9, 10), (20, 22), (30, 30)]
+        );
+        assert_eq!(split_ranges(&[(5, 12)], 4), [(5, 8), (9, 12)]);
+    }
+
+    #[test]
+    fn test_terms() {
+        assert_eq!(twice_terms(6), [(1001, true)]);
+        assert!(twice_terms(5).is_empty());
+        assert_eq!(
+            repeated_terms(6),
+            [(1001, true), (10101, true), (111_111, false)]
+        );
+        assert_eq!(repeated_terms(8), [(10001, true)]);
+        assert!(repeated_terms(1).is_empty());
+    }
+
+    #[test]
+    fn test_wide_ids() {
+        let twelve = 123_456_123_456;
+        let thirty = 123_456_789_012_345_123_456_789_012_345;
+        let ranges = [(twelve - 5, twelve + 5), (thirty, thirty)];
+        assert_eq!(part_1(&ranges), Ok(twelve + thirty));
+        assert_eq!(part_1(&ranges), Ok(part_1_brute_force(&ranges)));
+        let repeated = 121_212_121_212;
+        assert_eq!(part_2(&[(repeated, repeated)]), Ok(repeated));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let ranges = parse(&format!("1-{}", u128::MAX)).unwrap();
+        assert_eq!(part_1(&ranges), Err(Overflow));
+        assert_eq!(part_2(&ranges), Err(Overflow));
     }
 }
@@ -1,30 +1,135 @@
-#[aoc(day3, part1)]
-fn part_1(input: &str) -> u64 {
-    let mut res = 0;
-    for line in input.lines() {
-        res += find_max_joltage(line.as_bytes(), 2);
+use memchr::memchr;
+use thiserror::Error;
+
+use crate::shared::input::{InputError, Locate};
+use crate::shared::parse::normalize_input_trimmed;
+use crate::{Answer, AocError, DaySolution};
+
+/// The most batteries turned on in a bank, in part 2.
+const MAX_BATTERIES: usize = 12;
+
+#[derive(Debug, Error)]
+enum ParseError {
+    #[error("Invalid battery {0:?}")]
+    InvalidBattery(char),
+}
+
+impl Locate for ParseError {
+    fn offset(&self, text: &str) -> Option<usize> {
+        match self {
+            // Every character before the first invalid one is a digit.
+            Self::InvalidBattery(ch) => text.find(*ch),
+        }
     }
-    res
+}
+
+#[aoc_generator(day3)]
+fn parse(input: &str) -> Result<Vec<Vec<u8>>, InputError<ParseError>> {
+    let input = normalize_input_trimmed(input);
+    input
+        .lines()
+        .enumerate()
+        .map(|(row, line)| parse_bank(line).map_err(|err| InputError::in_line(row, line, err)))
+        .collect()
+}
+
+/// Parses a bank of batteries, as the values of its digits.
+fn parse_bank(line: &str) -> Result<Vec<u8>, ParseError> {
+    line.chars()
+        .map(|ch| {
+            ch.to_digit(10)
+                .and_then(|d| u8::try_from(d).ok())
+                .ok_or(ParseError::InvalidBattery(ch))
+        })
+        .collect()
+}
+
+/// A bank with fewer batteries than a part turns on.
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("Bank {bank} has {found} batteries, at least {needed} required")]
+struct TooFewBatteries {
+    bank: usize,
+    found: usize,
+    needed: usize,
+}
+
+/// Sum of `joltage` over the banks, once every bank is known to have `k` batteries.
+fn sum_banks(
+    banks: &[Vec<u8>],
+    k: usize,
+    joltage: impl Fn(&[u8]) -> u64,
+) -> Result<u64, TooFewBatteries> {
+    if let Some((ix, bank)) = banks.iter().enumerate().find(|(_, bank)| bank.len() < k) {
+        return Err(TooFewBatteries {
+            bank: ix + 1,
+            found: bank.len(),
+            needed: k,
+        });
+    }
+    Ok(banks.iter().map(|bank| joltage(bank)).sum())
+}
+
+/// Batteries turned on in a bank, in part 1.
+const PAIR: usize = 2;
+
+#[aoc(day3, part1)]
+fn part_1(banks: &[Vec<u8>]) -> Result<u64, TooFewBatteries> {
+    sum_banks(banks, PAIR, |bank| max_number_keeping(bank, PAIR))
 }
 
 #[aoc(day3, part2)]
-fn part_2(input: &str) -> u64 {
-    let mut res = 0;
-    for line in input.lines() {
-        res += find_max_joltage(line.as_bytes(), 12);
+fn part_2(banks: &[Vec<u8>]) -> Result<u64, TooFewBatteries> {
+    sum_banks(banks, MAX_BATTERIES, |bank| {
+        max_number_keeping(bank, MAX_BATTERIES)
+    })
+}
+
+/// Digits scanned at a time by [`max_digit`], sized so the inner loop vectorizes.
+const CHUNK: usize = 64;
+
+/// The largest of `digits`, or 0 if there are none.
+///
+/// Reduces whole chunks without branching, so the compiler can use SIMD within a chunk, and
+/// only checks between chunks whether a 9 allows stopping early.
+fn max_digit(digits: &[u8]) -> u8 {
+    let mut max = 0;
+    for chunk in digits.chunks(CHUNK) {
+        max = chunk.iter().fold(max, |m, &d| m.max(d));
+        if max == 9 {
+            break;
+        }
     }
-    res
+    max
+}
+
+/// The largest two-digit number made by keeping two of `digits` in order.
+///
+/// The tens digit is the largest one before the last, taken at its first occurrence to
+/// leave the most room, and the ones digit the largest after it. Each step is a chunked
+/// scan instead of the per-byte loop of [`max_number_keeping`].
+fn max_pair_chunked(digits: &[u8]) -> u64 {
+    let head = &digits[..digits.len() - 1];
+    let tens = max_digit(head);
+    let pos = memchr(tens, head).expect("maximum is in the slice");
+    let ones = max_digit(&digits[pos + 1..]);
+    u64::from(tens * 10 + ones)
 }
 
-fn find_max_joltage(batteries: &[u8], count: usize) -> u64 {
+#[aoc(day3, part1, Chunked)]
+fn part_1_chunked(banks: &[Vec<u8>]) -> Result<u64, TooFewBatteries> {
+    sum_banks(banks, PAIR, max_pair_chunked)
+}
+
+/// The largest number made by keeping `k` of `digits` in order, greedily picking the
+/// largest digit that still leaves room for the rest.
+fn max_number_keeping(digits: &[u8], k: usize) -> u64 {
     let mut value = 0;
     let mut start = 0;
-    let len = batteries.len();
-    for end in len - count + 1..=len {
+    let len = digits.len();
+    for end in len + 1 - k..=len {
         let mut max_digit = 0;
-        let mut max_pos = 0;
-        for (i, &ch) in batteries[start..end].iter().enumerate() {
-            let dig = ch - b'0';
+        let mut max_pos = start;
+        for (i, &dig) in digits[start..end].iter().enumerate() {
             if dig > max_digit {
                 max_digit = dig;
                 max_pos = start + i;
@@ -39,6 +144,48 @@ fn find_max_joltage(batteries: &[u8], count: usize) -> u64 {
     value
 }
 
+pub struct Day03;
+
+impl Day03 {
+    /// The largest number made by keeping `k` of the digits of `line` in order.
+    ///
+    /// Returns `None` if `line` has anything but digits, fewer than `k` of them, or `k` is
+    /// too large for the result to fit.
+    #[must_use]
+    pub fn max_number_keeping(line: &str, k: usize) -> Option<u64> {
+        if k > line.len() || k > u64::MAX.ilog10() as usize {
+            return None;
+        }
+        let digits = line
+            .bytes()
+            .map(|b| b.is_ascii_digit().then(|| b - b'0'))
+            .collect::<Option<Vec<u8>>>()?;
+        Some(max_number_keeping(&digits, k))
+    }
+}
+
+impl DaySolution for Day03 {
+    const DAY: u8 = 3;
+
+    type Parsed = Vec<Vec<u8>>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        parse(input).map_err(AocError::invalid_input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        part_1(parsed)
+            .map(Answer::from)
+            .map_err(|err| AocError::SolverFailed(err.to_string()))
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        part_2(parsed)
+            .map(Answer::from)
+            .map_err(|err| AocError::SolverFailed(err.to_string()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -50,15 +197,89 @@ mod tests {
         818181911112111\
     ";
 
+    #[test]
+    fn test_parse() {
+        let banks = parse(EXAMPLE1).unwrap();
+        assert_eq!(banks.len(), 4);
+        assert_eq!(banks[1], [8, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 9]);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let err = parse("987654321111111\n9876 54321111").unwrap_err();
+        assert!(matches!(err.error, ParseError::InvalidBattery(' ')));
+        assert_eq!((err.line, err.col), (2, Some(5)));
+    }
+
+    #[test]
+    fn test_too_few_batteries() {
+        let banks = parse("987654321111111\n12345").unwrap();
+        assert_eq!(part_1(&banks), Ok(98 + 45));
+        assert_eq!(part_1_chunked(&banks), Ok(98 + 45));
+        assert_eq!(
+            part_2(&banks),
+            Err(TooFewBatteries {
+                bank: 2,
+                found: 5,
+                needed: 12
+            })
+        );
+        let banks = parse("987654321111111\n9").unwrap();
+        assert_eq!(
+            part_1(&banks),
+            Err(TooFewBatteries {
+                bank: 2,
+                found: 1,
+                needed: 2
+            })
+        );
+    }
+
     #[test]
     fn test_part_1() {
-        let res = part_1(EXAMPLE1);
-        assert_eq!(res, 357);
+        let banks = parse(EXAMPLE1).unwrap();
+        let res = part_1(&banks);
+        assert_eq!(res, Ok(357));
+    }
+
+    #[test]
+    fn test_max_number_keeping() {
+        assert_eq!(Day03::max_number_keeping("818181911112111", 2), Some(92));
+        assert_eq!(
+            Day03::max_number_keeping("818181911112111", 5),
+            Some(92_111)
+        );
+        assert_eq!(Day03::max_number_keeping("12345", 5), Some(12_345));
+        assert_eq!(Day03::max_number_keeping("12345", 0), Some(0));
+        assert_eq!(Day03::max_number_keeping("12345", 6), None);
+        assert_eq!(Day03::max_number_keeping("12a45", 2), None);
+        let long = "9".repeat(30);
+        assert_eq!(
+            Day03::max_number_keeping(&long, 19),
+            Some(9_999_999_999_999_999_999)
+        );
+        assert_eq!(Day03::max_number_keeping(&long, 20), None);
+    }
+
+    #[test]
+    fn test_part_1_chunked() {
+        let banks = parse(EXAMPLE1).unwrap();
+        assert_eq!(part_1_chunked(&banks), Ok(357));
+        let mut long = "1".repeat(1000);
+        long.replace_range(130..131, "7");
+        long.replace_range(700..701, "7");
+        long.replace_range(999..1000, "8");
+        let banks = parse(&long).unwrap();
+        assert_eq!(part_1_chunked(&banks), Ok(78));
+        assert_eq!(part_1_chunked(&banks), part_1(&banks));
+        let banks = parse(&"9".repeat(200)).unwrap();
+        assert_eq!(part_1_chunked(&banks), Ok(99));
     }
 
     #[test]
     fn test_part_2() {
-        let res = part_2(EXAMPLE1);
-        assert_eq!(res, 3_121_910_778_619);
+        let banks = parse(EXAMPLE1).unwrap();
+        let res = part_2(&banks);
+        assert_eq!(res, Ok(3_121_910_778_619));
     }
 }
@@ -1,174 +1,152 @@
-use std::collections::{HashSet, VecDeque};
-use std::ops::{Index, IndexMut};
-use std::str::FromStr;
+use crate::shared::automaton::{Automaton, Neighborhood, Neighbors};
+use crate::shared::erosion::Erosion;
+use crate::shared::parse::normalize_input;
+use crate::shared::viz::Animator;
+use crate::shared::{Grid, GridError};
+use crate::{Answer, AocError, DaySolution};
 
-use thiserror::Error;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Pos {
-    row: usize,
-    col: usize,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tile {
+    Empty,
+    Roll,
 }
 
-impl Pos {
-    const fn new(row: usize, col: usize) -> Self {
-        Self { row, col }
+impl Tile {
+    const fn glyph(self) -> char {
+        match self {
+            Self::Empty => '.',
+            Self::Roll => '@',
+        }
     }
 }
 
-#[derive(Debug, Clone)]
-struct Grid<T> {
-    data: Vec<T>,
-    width: usize,
-    height: usize,
+#[aoc_generator(day4)]
+fn parse(input: &str) -> Result<Grid<Tile>, GridError> {
+    let input = normalize_input(input);
+    Grid::parse_with_legend(&input, |ch| match ch {
+        b'.' => Some(Tile::Empty),
+        b'@' => Some(Tile::Roll),
+        _ => None,
+    })
 }
 
-impl<T> Grid<T> {
-    fn neighbors(&self, pos: Pos) -> impl Iterator<Item = Pos> {
-        (pos.row.saturating_sub(1)..self.height.min(pos.row + 2))
-            .flat_map(move |r1| {
-                (pos.col.saturating_sub(1)..self.width.min(pos.col + 2))
-                    .map(move |c1| Pos::new(r1, c1))
-            })
-            .filter(move |&neighbor| neighbor != pos)
-    }
+/// A roll can be reached by a forklift when fewer than this many of its neighbors are rolls.
+const MAX_NEIGHBORS: usize = 4;
+
+/// Rolls crumble away as forklifts take them.
+const RULES: Erosion<fn(&Tile) -> bool> =
+    Erosion::new(Neighborhood::Moore, MAX_NEIGHBORS, |&tile| {
+        tile == Tile::Roll
+    });
 
-    const fn convert_index(&self, pos: Pos) -> Option<usize> {
-        if pos.row < self.height && pos.col < self.width {
-            Some(pos.row * self.width + pos.col)
+/// The same rules as a cellular automaton, taking every reachable roll at once each round.
+const FORKLIFTS: Automaton<fn(&Tile, Neighbors<'_, Tile>) -> Tile> =
+    Automaton::new(Neighborhood::Moore, |&tile, neighbors| {
+        if tile == Tile::Roll && neighbors.count(|&n| n == Tile::Roll) < MAX_NEIGHBORS {
+            Tile::Empty
         } else {
-            None
+            tile
         }
-    }
-}
+    });
 
-impl<T> Index<Pos> for Grid<T> {
-    type Output = T;
-
-    fn index(&self, pos: Pos) -> &Self::Output {
-        let index = self.convert_index(pos).expect("Index out of range");
-        &self.data[index]
-    }
+fn rolls(grid: &Grid<Tile>) -> usize {
+    grid.all_positions()
+        .filter(|&pos| grid[pos] == Tile::Roll)
+        .count()
 }
 
-impl<T> IndexMut<Pos> for Grid<T> {
-    fn index_mut(&mut self, pos: Pos) -> &mut Self::Output {
-        let index = self.convert_index(pos).expect("Index out of range");
-        &mut self.data[index]
-    }
+#[aoc(day4, part1)]
+fn part_1(grid: &Grid<Tile>) -> usize {
+    RULES.exposed(grid).count()
 }
 
-#[derive(Debug, Error)]
-enum ParseError {
-    #[error("Invalid tile")]
-    InvalidTile,
+#[aoc(day4, part1, Automaton)]
+fn part_1_automaton(grid: &Grid<Tile>) -> usize {
+    rolls(grid) - rolls(&FORKLIFTS.step(grid))
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Tile {
-    Empty,
-    Roll,
+#[aoc(day4, part2)]
+fn part_2(grid: &Grid<Tile>) -> usize {
+    RULES.erode(grid, |_| ())
 }
 
-impl TryFrom<u8> for Tile {
-    type Error = ParseError;
+#[aoc(day4, part2, Automaton)]
+fn part_2_automaton(grid: &Grid<Tile>) -> usize {
+    let (stable, _) = FORKLIFTS.stabilize(grid.clone());
+    rolls(grid) - rolls(&stable)
+}
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
-        Ok(match value {
-            b'.' => Self::Empty,
-            b'@' => Self::Roll,
-            _ => return Err(ParseError::InvalidTile),
-        })
-    }
+/// One round of removing every roll a forklift can reach.
+#[derive(Debug, Clone)]
+pub struct Round {
+    /// Rolls removed this round.
+    pub removed: usize,
+    /// The grid after the round, if asked for.
+    pub grid: Option<Grid<Tile>>,
 }
 
-impl<T> FromStr for Grid<T>
-where
-    T: TryFrom<u8>,
-{
-    type Err = T::Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lines = s.lines();
-        let height = lines.clone().count();
-        let width = lines.clone().next().unwrap_or_default().len();
-        let mut data = Vec::with_capacity(width * height);
-        for line in lines {
-            for ch in line.bytes() {
-                data.push(ch.try_into()?);
+/// Removes accessible rolls round by round until none are left, keeping the grid after each
+/// round if `keep_grids` is set.
+fn rounds(grid: &Grid<Tile>, keep_grids: bool) -> Vec<Round> {
+    let mut current = keep_grids.then(|| grid.clone());
+    let mut rounds = Vec::new();
+    RULES.erode(grid, |wave| {
+        if let Some(current) = &mut current {
+            for &pos in wave {
+                current[pos] = Tile::Empty;
             }
         }
-        Ok(Self {
-            data,
-            width,
-            height,
-        })
-    }
+        rounds.push(Round {
+            removed: wave.len(),
+            grid: current.clone(),
+        });
+    });
+    rounds
 }
 
-#[aoc_generator(day4)]
-fn parse(input: &str) -> Result<Grid<Tile>, ParseError> {
-    input.parse()
-}
+pub struct Day04;
 
-#[aoc(day4, part1)]
-fn part_1(grid: &Grid<Tile>) -> usize {
-    let mut count = 0;
-    for r in 0..grid.height {
-        for c in 0..grid.width {
-            let pos = Pos::new(r, c);
-            if grid[pos] != Tile::Roll {
-                continue;
+impl Day04 {
+    /// The rounds of removing rolls in part 2, with the grid after each if `keep_grids` is
+    /// set. The removed rolls of all rounds add up to the answer.
+    #[must_use]
+    pub fn rounds(grid: &Grid<Tile>, keep_grids: bool) -> Vec<Round> {
+        rounds(grid, keep_grids)
+    }
+
+    /// Plays the erosion of part 2, one frame per round of removed rolls.
+    pub fn animate(grid: &Grid<Tile>, animator: &mut impl Animator<char>) {
+        animator.frame(|| grid.map(|&tile| tile.glyph()));
+        for round in rounds(grid, animator.enabled()) {
+            if let Some(after) = round.grid {
+                animator.frame(|| after.map(|&tile| tile.glyph()));
             }
-            let neighbors = grid
-                .neighbors(pos)
-                .filter(|&n| grid[n] == Tile::Roll)
-                .count();
-            count += usize::from(neighbors < 4);
         }
     }
-    count
 }
 
-#[aoc(day4, part2)]
-fn part_2(grid: &Grid<Tile>) -> usize {
-    let mut removed = HashSet::<Pos>::new();
-    let mut pending = VecDeque::<Pos>::new();
-    for r in 0..grid.height {
-        for c in 0..grid.width {
-            let pos = Pos::new(r, c);
-            if grid[pos] != Tile::Roll {
-                continue;
-            }
-            let neighbors = grid
-                .neighbors(pos)
-                .filter(|&n| grid[n] == Tile::Roll)
-                .count();
-            if neighbors < 4 && removed.insert(pos) {
-                pending.push_back(pos);
-            }
-        }
+impl DaySolution for Day04 {
+    const DAY: u8 = 4;
+
+    type Parsed = Grid<Tile>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        parse(input).map_err(AocError::invalid_input)
     }
-    while let Some(pos) = pending.pop_front() {
-        for next in grid.neighbors(pos) {
-            if grid[next] != Tile::Roll {
-                continue;
-            }
-            let neighbors = grid
-                .neighbors(next)
-                .filter(|&n| grid[n] == Tile::Roll && !removed.contains(&n))
-                .count();
-            if neighbors < 4 && removed.insert(next) {
-                pending.push_back(next);
-            }
-        }
+
+    fn part1(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part_1(parsed).into())
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part_2(parsed).into())
     }
-    removed.len()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::shared::Pos;
 
     const EXAMLE1: &str = "\
         ..@@.@@@@.\n\
@@ -183,11 +161,29 @@ mod tests {
         @.@.@@@.@.\
     ";
 
+    #[test]
+    fn test_parse_invalid() {
+        let result = parse("..@\n.x@\n@@.");
+        assert_eq!(
+            result.unwrap_err(),
+            GridError::InvalidTile {
+                ch: 'x',
+                pos: Pos::new(1, 1)
+            }
+        );
+        let result = parse("..@\n.@\n@@.");
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Line 2 has width 2, expected 3"
+        );
+    }
+
     #[test]
     fn test_part_1() {
         let grid = parse(EXAMLE1).unwrap();
         let res = part_1(&grid);
         assert_eq!(res, 13);
+        assert_eq!(part_1_automaton(&grid), 13);
     }
 
     #[test]
@@ -195,5 +191,33 @@ mod tests {
         let grid = parse(EXAMLE1).unwrap();
         let res = part_2(&grid);
         assert_eq!(res, 43);
+        assert_eq!(part_2_automaton(&grid), 43);
+        // Nine rounds take rolls, the tenth finds none left to take.
+        let (_, generations) = FORKLIFTS.stabilize(grid);
+        assert_eq!(generations, 9);
+    }
+
+    #[test]
+    fn test_rounds() {
+        let grid = parse(EXAMLE1).unwrap();
+        let rounds = Day04::rounds(&grid, false);
+        let removed: Vec<usize> = rounds.iter().map(|round| round.removed).collect();
+        assert_eq!(removed, [13, 12, 7, 5, 2, 1, 1, 1, 1]);
+        assert!(rounds.iter().all(|round| round.grid.is_none()));
+        let rounds = Day04::rounds(&grid, true);
+        let after = rounds[0].grid.as_ref().unwrap();
+        let rolls = after.all_positions().filter(|&p| after[p] == Tile::Roll);
+        assert_eq!(rolls.count(), 71 - 13);
+    }
+
+    #[test]
+    fn test_animate() {
+        let grid = parse(EXAMLE1).unwrap();
+        let mut frames = Vec::new();
+        Day04::animate(&grid, &mut frames);
+        assert_eq!(frames.first().unwrap().to_string(), format!("{EXAMLE1}\n"));
+        let last = frames.last().unwrap();
+        let rolls = last.all_positions().filter(|&p| last[p] == '@');
+        assert_eq!(rolls.count(), 71 - 43);
     }
 }
@@ -3,8 +3,12 @@ use std::str::FromStr;
 
 use thiserror::Error;
 
+use crate::shared::parse::{input_blocks, normalize_input_trimmed};
+use crate::shared::ranges::RangeSet;
+use crate::{Answer, AocError, DaySolution};
+
 #[derive(Debug, Error)]
-enum ParseError {
+pub enum ParseError {
     #[error("Syntax error")]
     SyntaxError,
     #[error(transparent)]
@@ -12,32 +16,86 @@ enum ParseError {
 }
 
 #[derive(Debug, Clone)]
-struct Input {
-    fresh_ranges: Vec<(u64, u64)>,
+pub struct Input {
+    fresh: RangeSet<u64>,
     ingredients: Vec<u64>,
 }
 
+impl Input {
+    /// The fresh ingredient IDs, with overlapping ranges merged.
+    #[must_use]
+    pub const fn fresh(&self) -> &RangeSet<u64> {
+        &self.fresh
+    }
+
+    /// The available ingredient IDs, in increasing order.
+    #[must_use]
+    pub fn ingredients(&self) -> &[u64] {
+        &self.ingredients
+    }
+
+    /// Whether `ingredient` is fresh, by binary search over the merged ranges.
+    #[must_use]
+    pub fn is_fresh(&self, ingredient: u64) -> bool {
+        self.fresh.contains(ingredient)
+    }
+
+    /// The IDs between the fresh ranges that are not fresh, as ranges in increasing order.
+    #[must_use]
+    pub fn gaps(&self) -> Vec<(u64, u64)> {
+        self.fresh.gaps().collect()
+    }
+
+    /// The longest run of consecutive fresh IDs, joining ranges that touch. The first one
+    /// wins a tie. `None` if no ID is fresh.
+    #[must_use]
+    pub fn largest_fresh_interval(&self) -> Option<(u64, u64)> {
+        let mut largest: Option<(u64, u64)> = None;
+        let mut current: Option<(u64, u64)> = None;
+        for &(start, end) in self.fresh.iter() {
+            current = match current {
+                Some((lo, hi)) if hi.checked_add(1) == Some(start) => Some((lo, end)),
+                _ => Some((start, end)),
+            };
+            let (lo, hi) = current.expect("just set");
+            if largest.is_none_or(|(l, h)| hi - lo > h - l) {
+                largest = current;
+            }
+        }
+        largest
+    }
+
+    /// How far each ingredient, in the order of [`Input::ingredients`], is from the closest
+    /// fresh range; 0 for fresh ones. Empty if no ID is fresh.
+    #[must_use]
+    pub fn nearest_fresh_distances(&self) -> Vec<u64> {
+        self.ingredients
+            .iter()
+            .filter_map(|&ingredient| self.fresh.distance(ingredient))
+            .collect()
+    }
+}
+
 impl FromStr for Input {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut fresh_ranges = Vec::new();
         let mut ingredients = Vec::new();
-        let mut lines = s.lines();
-        for line in lines.by_ref() {
-            if line.is_empty() {
-                break;
-            }
+        let mut blocks = input_blocks(s);
+        for line in blocks.next().unwrap_or_default().lines() {
             let (start, end) = line.split_once('-').ok_or(ParseError::SyntaxError)?;
             fresh_ranges.push((start.parse()?, end.parse()?));
         }
-        fresh_ranges.sort_unstable();
-        for line in lines {
+        for line in blocks.next().unwrap_or_default().lines() {
             ingredients.push(line.parse()?);
         }
+        if blocks.next().is_some() {
+            return Err(ParseError::SyntaxError);
+        }
         ingredients.sort_unstable();
         Ok(Self {
-            fresh_ranges,
+            fresh: fresh_ranges.into_iter().collect(),
             ingredients,
         })
     }
@@ -45,54 +103,59 @@ impl FromStr for Input {
 
 #[aoc_generator(day5)]
 fn parse(input: &str) -> Result<Input, ParseError> {
+    let input = normalize_input_trimmed(input);
     input.parse()
 }
 
+/// Counts the fresh ingredients in one pass over both lists, which relies on the generator
+/// keeping the ingredients sorted and the ranges merged.
 #[aoc(day5, part1)]
 fn part_1(input: &Input) -> usize {
-    let mut ranges_it = input.fresh_ranges.iter();
-    let mut exhausted = false;
-
-    let Some(&(mut fresh_start, mut fresh_end)) = ranges_it.next() else {
-        return 0;
-    };
+    debug_assert!(input.ingredients.is_sorted());
+    let mut ranges = input.fresh.iter().peekable();
     let mut count = 0;
     for &ingredient in &input.ingredients {
-        while !exhausted && ingredient > fresh_end {
-            if let Some(&(start, end)) = ranges_it.next() {
-                if start > fresh_end + 1 {
-                    fresh_start = start;
-                }
-                fresh_end = end.max(fresh_end);
-                if start > ingredient {
-                    break;
-                }
-            } else {
-                exhausted = true;
-            }
-        }
-        if (fresh_start..=fresh_end).contains(&ingredient) {
-            count += 1;
-        }
+        while ranges.next_if(|&&(_, end)| end < ingredient).is_some() {}
+        let Some(&&(start, _)) = ranges.peek() else {
+            break;
+        };
+        count += usize::from(start <= ingredient);
     }
     count
 }
 
+#[aoc(day5, part1, Lookup)]
+fn part_1_lookup(input: &Input) -> usize {
+    input
+        .ingredients
+        .iter()
+        .filter(|&&ingredient| input.is_fresh(ingredient))
+        .count()
+}
+
 #[aoc(day5, part2)]
 fn part_2(input: &Input) -> u64 {
-    if input.fresh_ranges.is_empty() {
-        return 0;
+    input.fresh.total_len()
+}
+
+pub struct Day05;
+
+impl DaySolution for Day05 {
+    const DAY: u8 = 5;
+
+    type Parsed = Input;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        parse(input).map_err(AocError::invalid_input)
     }
-    let mut count = 0;
-    let (mut fresh_start, mut fresh_end) = input.fresh_ranges[0];
-    for &(start, end) in &input.fresh_ranges[1..] {
-        if start > fresh_end + 1 {
-            count += fresh_end - fresh_start + 1;
-            fresh_start = start;
-        }
-        fresh_end = fresh_end.max(end);
+
+    fn part1(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part_1(parsed).into())
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part_2(parsed).into())
     }
-    count + fresh_end - fresh_start + 1
 }
 
 #[cfg(test)]
@@ -113,6 +176,13 @@ mod tests {
         32\
     ";
 
+    #[test]
+    fn test_parse_crlf() {
+        let input = parse(&EXAMPLE1.replace('\n', "\r\n")).unwrap();
+        assert_eq!(input.fresh().as_slice(), [(3, 5), (10, 20)]);
+        assert_eq!(input.ingredients, [1, 5, 8, 11, 17, 32]);
+    }
+
     #[test]
     fn test_part_1() {
         let input = parse(EXAMPLE1).unwrap();
@@ -120,6 +190,39 @@ mod tests {
         assert_eq!(result, 3);
     }
 
+    #[test]
+    fn test_analytics() {
+        let input = parse(EXAMPLE1).unwrap();
+        assert_eq!(input.gaps(), [(6, 9)]);
+        assert_eq!(input.largest_fresh_interval(), Some((10, 20)));
+        assert_eq!(input.nearest_fresh_distances(), [2, 0, 2, 0, 0, 12]);
+        let touching = parse("1-3\n4-5\n7-9\n\n1").unwrap();
+        assert_eq!(touching.gaps(), [(6, 6)]);
+        assert_eq!(touching.largest_fresh_interval(), Some((1, 5)));
+        let top = parse("1-1\n18446744073709551614-18446744073709551615\n\n1").unwrap();
+        assert_eq!(top.largest_fresh_interval(), Some((u64::MAX - 1, u64::MAX)));
+        let none = Input {
+            fresh: RangeSet::new(),
+            ingredients: vec![1],
+        };
+        assert_eq!(none.largest_fresh_interval(), None);
+        assert!(none.nearest_fresh_distances().is_empty());
+    }
+
+    #[test]
+    fn test_is_fresh() {
+        let input = parse(EXAMPLE1).unwrap();
+        let fresh: Vec<u64> = [32, 17, 1, 11, 5, 8, 20, 9]
+            .into_iter()
+            .filter(|&id| input.is_fresh(id))
+            .collect();
+        assert_eq!(fresh, [17, 11, 5, 20]);
+        assert_eq!(part_1_lookup(&input), 3);
+        let input = parse("5-5\n1-3\n2-4\n\n9\n0\n4\n3\n6\n5").unwrap();
+        assert_eq!(part_1(&input), 3);
+        assert_eq!(part_1_lookup(&input), 3);
+    }
+
     #[test]
     fn test_part_2() {
         let input = parse(EXAMPLE1).unwrap();
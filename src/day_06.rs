@@ -1,119 +1,316 @@
-use std::ops::Index;
+use std::borrow::Cow;
+use std::ops::Range;
 use std::str::FromStr;
 
-#[derive(Debug, Clone)]
-struct Grid {
-    data: Vec<u8>,
-    width: usize,
-    height: usize,
-}
+use thiserror::Error;
 
-impl Grid {
-    const fn get_index(&self, (row, col): (usize, usize)) -> Option<usize> {
-        if row < self.height || col < self.width {
-            Some(row * self.width + col)
-        } else {
-            None
-        }
-    }
+use crate::shared::parse::normalize_input;
+use crate::{Answer, AocError, DaySolution};
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ParseError {
+    #[error("Missing operator row")]
+    MissingOperators,
+    #[error("Invalid digit {ch:?} at line {line}, column {col}")]
+    InvalidDigit { ch: char, line: usize, col: usize },
+    #[error("Invalid operator {op:?} at column {col}")]
+    InvalidOperator { op: String, col: usize },
+    #[error("Operator at column {0} is not below any numbers")]
+    StrayOperator(usize),
+    #[error("Numbers in columns {start} to {end} have no operator")]
+    MissingOperator { start: usize, end: usize },
+    #[error("Second operator at column {0} for the same numbers")]
+    ExtraOperator(usize),
 }
 
-impl Index<(usize, usize)> for Grid {
-    type Output = u8;
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum EvalError {
+    #[error("Division by zero")]
+    DivisionByZero,
+    #[error("Negative result")]
+    Negative,
+    #[error("Result does not fit in 128 bits")]
+    Overflow,
+}
 
-    fn index(&self, index: (usize, usize)) -> &Self::Output {
-        &self.data[self.get_index(index).expect("index out of range")]
-    }
+/// What a problem does with its numbers, written below them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    /// Integer division, rounding down.
+    Divide,
+    Min,
+    Max,
 }
 
-impl FromStr for Grid {
-    type Err = std::convert::Infallible;
+impl FromStr for Operator {
+    type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let height = s.lines().count();
-        let width = s.lines().next().unwrap_or_default().len();
-        let mut data = Vec::with_capacity(width * height);
-        for line in s.lines() {
-            data.extend_from_slice(line.as_bytes());
-        }
-        Ok(Self {
-            data,
-            width,
-            height,
+        Ok(match s {
+            "+" => Self::Add,
+            "-" => Self::Subtract,
+            "*" => Self::Multiply,
+            "/" => Self::Divide,
+            "min" => Self::Min,
+            "max" => Self::Max,
+            _ => return Err(()),
         })
     }
 }
 
-#[aoc_generator(day6)]
-fn parse(input: &str) -> Grid {
-    input.parse().unwrap()
+impl Operator {
+    /// Combines `numbers` from left to right, so the first number is the one subtracted
+    /// from or divided.
+    fn apply(
+        self,
+        mut numbers: impl Iterator<Item = Result<u128, EvalError>>,
+    ) -> Result<u128, EvalError> {
+        let first = numbers.next().expect("problems have at least one number")?;
+        numbers.try_fold(first, |acc, num| {
+            let num = num?;
+            match self {
+                Self::Add => acc.checked_add(num).ok_or(EvalError::Overflow),
+                Self::Subtract => acc.checked_sub(num).ok_or(EvalError::Negative),
+                Self::Multiply => acc.checked_mul(num).ok_or(EvalError::Overflow),
+                Self::Divide => acc.checked_div(num).ok_or(EvalError::DivisionByZero),
+                Self::Min => Ok(acc.min(num)),
+                Self::Max => Ok(acc.max(num)),
+            }
+        })
+    }
 }
 
-#[aoc(day6, part1)]
-fn part_1(grid: &Grid) -> u64 {
-    let mut right = grid.width;
-    let bottom = grid.height - 1;
-    let mut total_sum = 0;
-    for left in (0..grid.width).rev() {
-        let mut res = match grid[(bottom, left)] {
-            b'*' => 1,
-            b'+' => 0,
-            _ => continue,
-        };
-        for r in 0..bottom {
-            let num = (left..right).fold(0, |val, c| {
-                let ch = grid[(r, c)];
-                if ch == b' ' {
-                    val
-                } else {
-                    val * 10 + u64::from(ch - b'0')
-                }
-            });
-            match grid[(bottom, left)] {
-                b'*' => res *= num,
-                b'+' => res += num,
-                _ => unreachable!(),
+/// Where the digits of each number sit in the columns of its problem, for reading the
+/// numbers down the columns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Alignment {
+    /// Where they are written in the input.
+    #[default]
+    AsWritten,
+    Left,
+    Right,
+    /// Centered, leaning left when the spaces don't split evenly.
+    Center,
+}
+
+/// One problem of the worksheet: the numbers written in a block of columns, and the
+/// operator below them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    op: Operator,
+    /// The block of each number row, padded with spaces to the width of the problem.
+    rows: Vec<Vec<u8>>,
+}
+
+impl Problem {
+    /// The number formed by `digits`, skipping spaces, or `None` if there are no digits.
+    fn number(digits: impl Iterator<Item = u8>) -> Option<Result<u128, EvalError>> {
+        let mut digits = digits.filter(|&ch| ch != b' ').peekable();
+        digits.peek()?;
+        Some(digits.try_fold(0_u128, |val, ch| {
+            val.checked_mul(10)
+                .and_then(|val| val.checked_add(u128::from(ch - b'0')))
+                .ok_or(EvalError::Overflow)
+        }))
+    }
+
+    /// The numbers read left to right along each row.
+    fn row_numbers(&self) -> impl Iterator<Item = Result<u128, EvalError>> + '_ {
+        self.rows
+            .iter()
+            .filter_map(|row| Self::number(row.iter().copied()))
+    }
+
+    /// The rows of the problem with their digits moved to `alignment`, keeping the width.
+    fn aligned_rows(&self, alignment: Alignment) -> Vec<Cow<'_, [u8]>> {
+        let width = self.rows.first().map_or(0, Vec::len);
+        self.rows
+            .iter()
+            .map(|row| {
+                let digits = row.trim_ascii();
+                let pad = width - digits.len();
+                let left = match alignment {
+                    Alignment::AsWritten => return Cow::Borrowed(row.as_slice()),
+                    Alignment::Left => 0,
+                    Alignment::Right => pad,
+                    Alignment::Center => pad / 2,
+                };
+                let mut aligned = vec![b' '; width];
+                aligned[left..left + digits.len()].copy_from_slice(digits);
+                Cow::Owned(aligned)
+            })
+            .collect()
+    }
+
+    /// The numbers read top to bottom down each column, from the left, after moving the
+    /// digits of every row to `alignment`.
+    fn column_numbers(
+        &self,
+        alignment: Alignment,
+    ) -> impl Iterator<Item = Result<u128, EvalError>> + '_ {
+        let rows = self.aligned_rows(alignment);
+        let width = self.rows.first().map_or(0, Vec::len);
+        (0..width).filter_map(move |col| Self::number(rows.iter().map(|row| row[col])))
+    }
+}
+
+/// The runs of non-space characters in `line`, with the column each starts at.
+fn tokens(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    line.split(' ')
+        .scan(0, |col, token| {
+            let start = *col;
+            *col += token.len() + 1;
+            Some((start, token))
+        })
+        .filter(|(_, token)| !token.is_empty())
+}
+
+/// The blocks of columns with a digit in any of `rows`.
+fn column_spans(rows: &[&[u8]]) -> Vec<Range<usize>> {
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let occupied = |col: usize| {
+        rows.iter()
+            .any(|row| row.get(col).is_some_and(|&ch| ch != b' '))
+    };
+    let mut spans = Vec::new();
+    let mut col = 0;
+    while col < width {
+        if occupied(col) {
+            let start = col;
+            while col < width && occupied(col) {
+                col += 1;
             }
+            spans.push(start..col);
+        } else {
+            col += 1;
         }
-        total_sum += res;
-        right = left;
     }
-    total_sum
+    spans
 }
 
-#[aoc(day6, part2)]
-fn part_2(grid: &Grid) -> u64 {
-    let mut right = grid.width;
-    let bottom = grid.height - 1;
-    let mut total_sum = 0;
-    for left in (0..grid.width).rev() {
-        let mut res = match grid[(bottom, left)] {
-            b'*' => 1,
-            b'+' => 0,
-            _ => continue,
-        };
-        for c in left..right {
-            let num = (0..bottom).fold(0, |val, r| {
-                let ch = grid[(r, c)];
-                if ch == b' ' {
-                    val
-                } else {
-                    val * 10 + u64::from(ch - b'0')
-                }
+/// Parses the worksheet, finding each problem as a block of columns holding digits above,
+/// and its operator within that block in the bottom row.
+///
+/// Lines may be of different lengths, as when trailing spaces were trimmed; the missing
+/// columns count as spaces.
+#[aoc_generator(day6)]
+fn parse(input: &str) -> Result<Vec<Problem>, ParseError> {
+    let input = normalize_input(input);
+    let lines: Vec<&str> = input.lines().collect();
+    let (&operators, rows) = lines.split_last().ok_or(ParseError::MissingOperators)?;
+    let rows: Vec<&[u8]> = rows.iter().map(|row| row.as_bytes()).collect();
+    for (line, row) in rows.iter().enumerate() {
+        if let Some(col) = row
+            .iter()
+            .position(|&ch| ch != b' ' && !ch.is_ascii_digit())
+        {
+            return Err(ParseError::InvalidDigit {
+                ch: char::from(row[col]),
+                line: line + 1,
+                col: col + 1,
             });
-            if num == 0 {
-                continue;
+        }
+    }
+    let mut operators = tokens(operators).peekable();
+    let mut problems = Vec::new();
+    for span in column_spans(&rows) {
+        let mut op = None;
+        while let Some((col, token)) = operators.next_if(|&(col, _)| col < span.end) {
+            if col < span.start {
+                return Err(ParseError::StrayOperator(col + 1));
             }
-            match grid[(bottom, left)] {
-                b'*' => res *= num,
-                b'+' => res += num,
-                _ => unreachable!(),
+            let parsed = token.parse().map_err(|()| ParseError::InvalidOperator {
+                op: token.to_string(),
+                col: col + 1,
+            })?;
+            if op.replace(parsed).is_some() {
+                return Err(ParseError::ExtraOperator(col + 1));
             }
         }
-        total_sum += res;
-        right = left;
+        let op = op.ok_or(ParseError::MissingOperator {
+            start: span.start + 1,
+            end: span.end,
+        })?;
+        let rows = rows
+            .iter()
+            .map(|row| {
+                span.clone()
+                    .map(|col| row.get(col).copied().unwrap_or(b' '))
+                    .collect()
+            })
+            .collect();
+        problems.push(Problem { op, rows });
+    }
+    if let Some((col, _)) = operators.next() {
+        return Err(ParseError::StrayOperator(col + 1));
+    }
+    Ok(problems)
+}
+
+/// Sums the results of the problems, each evaluated by `numbers`, failing on any overflow
+/// rather than wrapping around.
+fn grand_total<'a, I>(
+    problems: &'a [Problem],
+    numbers: impl Fn(&'a Problem) -> I,
+) -> Result<u128, EvalError>
+where
+    I: Iterator<Item = Result<u128, EvalError>>,
+{
+    problems.iter().try_fold(0_u128, |total, problem| {
+        let result = problem.op.apply(numbers(problem))?;
+        total.checked_add(result).ok_or(EvalError::Overflow)
+    })
+}
+
+#[aoc(day6, part1)]
+fn part_1(problems: &[Problem]) -> Result<u128, EvalError> {
+    grand_total(problems, Problem::row_numbers)
+}
+
+#[aoc(day6, part2)]
+fn part_2(problems: &[Problem]) -> Result<u128, EvalError> {
+    grand_total(problems, |problem| {
+        problem.column_numbers(Alignment::AsWritten)
+    })
+}
+
+pub struct Day06;
+
+impl Day06 {
+    /// The answer to part 2 with the digits of every number moved to `alignment` within
+    /// its problem before reading down the columns.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a problem has no result, or the total does not fit.
+    pub fn part_2_aligned(problems: &[Problem], alignment: Alignment) -> Result<u128, EvalError> {
+        grand_total(problems, |problem| problem.column_numbers(alignment))
+    }
+}
+
+impl DaySolution for Day06 {
+    const DAY: u8 = 6;
+
+    type Parsed = Vec<Problem>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        parse(input).map_err(AocError::invalid_input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        part_1(parsed)
+            .map(Answer::from)
+            .map_err(|err| AocError::SolverFailed(err.to_string()))
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        part_2(parsed)
+            .map(Answer::from)
+            .map_err(|err| AocError::SolverFailed(err.to_string()))
     }
-    total_sum
 }
 
 #[cfg(test)]
@@ -124,15 +321,115 @@ mod tests {
 
     #[test]
     fn test_part_1() {
-        let grid = parse(EXAMPLE1);
+        let grid = parse(EXAMPLE1).unwrap();
         let res = part_1(&grid);
-        assert_eq!(res, 4_277_556);
+        assert_eq!(res, Ok(4_277_556));
+    }
+
+    #[test]
+    fn test_parse_trimmed() {
+        let trimmed: Vec<&str> = EXAMPLE1.lines().map(str::trim_end).collect();
+        let problems = parse(&trimmed.join("\n")).unwrap();
+        assert_eq!(problems, parse(EXAMPLE1).unwrap());
+        assert_eq!(problems.len(), 4);
+        assert_eq!(problems[3].rows, [b"64 ", b"23 ", b"314"]);
+    }
+
+    #[test]
+    fn test_varying_widths() {
+        let problems = parse("12 3\n 4 56\n*  +").unwrap();
+        assert_eq!(problems.len(), 2);
+        assert_eq!(part_1(&problems), Ok(12 * 4 + 3 + 56));
+        assert_eq!(part_2(&problems), Ok(24 + 35 + 6));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse(""), Err(ParseError::MissingOperators));
+        assert_eq!(
+            parse("1 2\n3x4\n+ *"),
+            Err(ParseError::InvalidDigit {
+                ch: 'x',
+                line: 2,
+                col: 2
+            })
+        );
+        assert_eq!(
+            parse("1 2\n% *"),
+            Err(ParseError::InvalidOperator {
+                op: "%".to_string(),
+                col: 1
+            })
+        );
+        assert_eq!(parse("1  2\n+ **"), Err(ParseError::StrayOperator(3)));
+        assert_eq!(
+            parse("1 2\n+  *"),
+            Err(ParseError::MissingOperator { start: 3, end: 3 })
+        );
+        assert_eq!(parse("1 2\n+ * +"), Err(ParseError::StrayOperator(5)));
+        assert_eq!(
+            parse("12 3\n+"),
+            Err(ParseError::MissingOperator { start: 4, end: 4 })
+        );
+        assert_eq!(parse("123\n+ -"), Err(ParseError::ExtraOperator(3)));
+    }
+
+    #[test]
+    fn test_operators() {
+        use Operator::{Divide, Max, Min, Subtract};
+
+        let problems = parse("20 8 700  40\n16 2  51 9 3\n-  / min max").unwrap();
+        let ops: Vec<Operator> = problems.iter().map(|problem| problem.op).collect();
+        assert_eq!(ops, [Subtract, Divide, Min, Max]);
+        assert_eq!(part_1(&problems), Ok(4 + 4 + 51 + 93));
+        assert_eq!(part_2(&problems), Ok(15 + 82 + 1 + 9));
+    }
+
+    #[test]
+    fn test_eval_errors() {
+        let problems = parse("1 9\n3 0\n- /").unwrap();
+        assert_eq!(part_1(&problems), Err(EvalError::Negative));
+        let problems = parse("9\n0\n/").unwrap();
+        assert_eq!(part_1(&problems), Err(EvalError::DivisionByZero));
+        assert!(matches!(
+            Day06::part1(&problems),
+            Err(AocError::SolverFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let wide = "9".repeat(19);
+        let problems = parse(&format!("{wide}\n{wide}\n*")).unwrap();
+        let expected = wide.parse::<u128>().unwrap().pow(2);
+        assert_eq!(part_1(&problems), Ok(expected));
+        let problems = parse(&format!("{wide}\n{wide}\n{wide}\n*")).unwrap();
+        assert_eq!(part_1(&problems), Err(EvalError::Overflow));
+        let problems = parse(&format!("{}\n+", "9".repeat(40))).unwrap();
+        assert_eq!(part_1(&problems), Err(EvalError::Overflow));
+        assert_eq!(part_2(&problems), Ok(9 * 40));
+    }
+
+    #[test]
+    fn test_alignment() {
+        let problems = parse("123\n 45\n  6\n*").unwrap();
+        let total = |alignment| Day06::part_2_aligned(&problems, alignment);
+        assert_eq!(total(Alignment::AsWritten), Ok(356 * 24));
+        assert_eq!(total(Alignment::Right), Ok(356 * 24));
+        assert_eq!(total(Alignment::Left), Ok(146 * 25 * 3));
+        assert_eq!(total(Alignment::Center), Ok(14 * 256 * 3));
+        let problems = parse(EXAMPLE1).unwrap();
+        assert_eq!(
+            Day06::part_2_aligned(&problems, Alignment::AsWritten),
+            part_2(&problems)
+        );
+        assert_eq!(part_1(&problems), Ok(4_277_556));
     }
 
     #[test]
     fn test_part_2() {
-        let grid = parse(EXAMPLE1);
+        let grid = parse(EXAMPLE1).unwrap();
         let res = part_2(&grid);
-        assert_eq!(res, 3_263_827);
+        assert_eq!(res, Ok(3_263_827));
     }
 }
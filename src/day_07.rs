@@ -1,84 +1,333 @@
-use thiserror::Error;
+use std::fmt::Display;
 
-use crate::shared::{Grid, Pos};
+use thiserror::Error;
+use tracing::trace;
 
-#[derive(Debug, Error)]
-enum ParseError {
-    #[error("Invalid tile")]
-    InvalidTile,
-}
+#[cfg(feature = "bigint")]
+use crate::shared::bigint::BigUint;
+use crate::shared::parse::normalize_input;
+use crate::shared::viz::Animator;
+use crate::shared::{Grid, GridError, Pos};
+use crate::{Answer, AocError, DaySolution};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Tile {
+pub enum Tile {
     Empty,
     Splitter,
     Start,
+    /// Stops the beams that hit it, ending their timelines.
+    Absorber,
+    /// Moves the beams that hit it one column left, without splitting them.
+    DeflectLeft,
+    /// Moves the beams that hit it one column right, without splitting them.
+    DeflectRight,
 }
 
-impl TryFrom<u8> for Tile {
-    type Error = ParseError;
+impl Tile {
+    const fn glyph(self) -> char {
+        match self {
+            Self::Empty => '.',
+            Self::Splitter => '^',
+            Self::Start => 'S',
+            Self::Absorber => '#',
+            Self::DeflectLeft => '<',
+            Self::DeflectRight => '>',
+        }
+    }
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
-        Ok(match value {
+    const fn from_glyph(ch: u8) -> Option<Self> {
+        Some(match ch {
             b'.' => Self::Empty,
             b'^' => Self::Splitter,
             b'S' => Self::Start,
-            _ => return Err(ParseError::InvalidTile),
+            b'#' => Self::Absorber,
+            b'<' => Self::DeflectLeft,
+            b'>' => Self::DeflectRight,
+            _ => return None,
         })
     }
+
+    /// The column offsets at which a beam hitting the tile carries on into the next row.
+    const fn exits(self) -> &'static [isize] {
+        match self {
+            Self::Empty | Self::Start => &[0],
+            Self::Splitter => &[-1, 1],
+            Self::Absorber => &[],
+            Self::DeflectLeft => &[-1],
+            Self::DeflectRight => &[1],
+        }
+    }
 }
 
 #[aoc_generator(day7)]
-fn parse(input: &str) -> Result<Grid<Tile>, ParseError> {
-    input.parse()
+fn parse(input: &str) -> Result<Grid<Tile>, GridError> {
+    let input = normalize_input(input);
+    Grid::parse_with_legend(&input, Tile::from_glyph)
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SimulationError {
+    #[error("No 'S' in the manifold")]
+    NoStart,
+    #[error("Timeline count does not fit in 128 bits")]
+    Overflow,
+}
+
+/// A count of timelines sharing a beam.
+trait Multitude: Clone + Display {
+    fn zero() -> Self;
+
+    fn one() -> Self;
+
+    fn is_zero(&self) -> bool;
+
+    /// Adds `other`, or returns `None` if the sum does not fit.
+    fn checked_add_assign(&mut self, other: &Self) -> Option<()>;
+}
+
+impl Multitude for u128 {
+    fn zero() -> Self {
+        0
+    }
+
+    fn one() -> Self {
+        1
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == 0
+    }
+
+    fn checked_add_assign(&mut self, other: &Self) -> Option<()> {
+        *self = self.checked_add(*other)?;
+        Some(())
+    }
+}
+
+/// Whether any timeline takes a beam, for when only the beams' paths matter.
+impl Multitude for bool {
+    fn zero() -> Self {
+        false
+    }
+
+    fn one() -> Self {
+        true
+    }
+
+    fn is_zero(&self) -> bool {
+        !self
+    }
+
+    fn checked_add_assign(&mut self, other: &Self) -> Option<()> {
+        *self |= other;
+        Some(())
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl Multitude for BigUint {
+    fn zero() -> Self {
+        Self::default()
+    }
+
+    fn one() -> Self {
+        Self::from(1)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.is_zero()
+    }
+
+    fn checked_add_assign(&mut self, other: &Self) -> Option<()> {
+        *self += other;
+        Some(())
+    }
 }
 
 #[aoc(day7, part1)]
-fn part_1(grid: &Grid<Tile>) -> u64 {
-    simulate(grid).0
+fn part_1(grid: &Grid<Tile>) -> Result<u64, SimulationError> {
+    Ok(simulate::<bool>(grid, &starts(grid), |_, _, _| ())?.0)
 }
 
+/// Counts the timelines in 128 bits, or with the `bigint` feature, in as many as it takes.
 #[aoc(day7, part2)]
-fn part_2(grid: &Grid<Tile>) -> u64 {
-    simulate(grid).1
+fn part_2(grid: &Grid<Tile>) -> Result<Answer, SimulationError> {
+    match simulate::<u128>(grid, &starts(grid), |_, _, _| ()) {
+        Ok((_, timelines)) => Ok(timelines.into()),
+        #[cfg(feature = "bigint")]
+        Err(SimulationError::Overflow) => {
+            let (_, timelines) = simulate::<BigUint>(grid, &starts(grid), |_, _, _| ())?;
+            Ok(timelines.to_string().into())
+        }
+        Err(err) => Err(err),
+    }
 }
 
-fn simulate(grid: &Grid<Tile>) -> (u64, u64) {
-    let start = grid
-        .all_positions()
-        .take(grid.width()) // first row
-        .find(|&pos| grid[pos] == Tile::Start)
-        .expect("'S' in first row");
+/// The positions of the `S` tiles, in reading order.
+fn starts(grid: &Grid<Tile>) -> Vec<Pos> {
+    grid.all_positions()
+        .filter(|&pos| grid[pos] == Tile::Start)
+        .collect()
+}
 
-    let mut pending = vec![0; grid.width()];
-    pending[start.col] = 1;
+/// Follows the beams down the manifold one row at a time, starting one beam at each of
+/// `starts`, given in reading order. Calls `on_row` with each row, the timelines leaving it
+/// per column, and the columns of the splitters hit in it.
+///
+/// The beams entering a row are counted per column in one buffer, and those leaving it in
+/// another; the two swap roles each row. What becomes of the beams hitting a tile is up to
+/// [`Tile::exits`]. Returns the number of splits and of timelines, counting beams that are
+/// absorbed or leave the side of the manifold as ended timelines, or an error if
+/// the timelines outgrow `M`. A splitter hit by beams from several starts splits once.
+fn simulate<M: Multitude>(
+    grid: &Grid<Tile>,
+    starts: &[Pos],
+    mut on_row: impl FnMut(usize, &[M], &[usize]),
+) -> Result<(u64, M), SimulationError> {
+    if starts.is_empty() {
+        return Err(SimulationError::NoStart);
+    }
+    let mut starts = starts.iter().peekable();
 
-    let mut next = vec![0; grid.width()];
+    let mut pending = vec![M::zero(); grid.width()];
+    let mut next = vec![M::zero(); grid.width()];
 
     let mut num_splits = 0;
+    let mut ended = M::zero();
+
+    let mut hits = Vec::new();
 
-    for row in (0..grid.width()).step_by(2) {
-        for (col, &multitude) in pending.iter().enumerate() {
-            if multitude == 0 {
+    for row in 0..grid.height() {
+        while let Some(start) = starts.next_if(|start| start.row == row) {
+            pending[start.col]
+                .checked_add_assign(&M::one())
+                .ok_or(SimulationError::Overflow)?;
+        }
+        for (col, multitude) in pending.iter().enumerate() {
+            if multitude.is_zero() {
                 continue;
             }
-            let pos = Pos::new(row, col);
-            match grid[pos] {
-                Tile::Empty | Tile::Start => {
-                    next[pos.col] += multitude;
-                }
-                Tile::Splitter => {
-                    num_splits += 1;
-                    next[pos.col - 1] += multitude;
-                    next[pos.col + 1] += multitude;
-                }
+            let tile = grid[Pos::new(row, col)];
+            if tile == Tile::Splitter {
+                trace!(target: "replay", step = "split", row, col, timelines = %multitude);
+                num_splits += 1;
+                hits.push(col);
+            }
+            let exits = tile.exits();
+            if exits.is_empty() {
+                ended
+                    .checked_add_assign(multitude)
+                    .ok_or(SimulationError::Overflow)?;
+            }
+            for &offset in exits {
+                col.checked_add_signed(offset)
+                    .and_then(|exit| next.get_mut(exit))
+                    .unwrap_or(&mut ended)
+                    .checked_add_assign(multitude)
+                    .ok_or(SimulationError::Overflow)?;
             }
         }
+        on_row(row, &next, &hits);
+        hits.clear();
         (pending, next) = (next, pending);
-        next.fill(0);
+        next.fill(M::zero());
+    }
+    let num_timelines = pending
+        .iter()
+        .try_fold(ended, |mut total, multitude| {
+            total.checked_add_assign(multitude)?;
+            Some(total)
+        })
+        .ok_or(SimulationError::Overflow)?;
+    Ok((num_splits, num_timelines))
+}
+
+/// The beams after one row of the manifold.
+#[derive(Debug, Clone, Copy)]
+pub struct BeamRow<'a> {
+    pub row: usize,
+    /// Timelines leaving the row, per column.
+    pub timelines: &'a [u128],
+    /// Columns of the splitters hit in the row.
+    pub hits: &'a [usize],
+}
+
+pub struct Day07;
+
+impl Day07 {
+    /// Runs the beam cascade, handing `on_row` the beams after every row, and returns the
+    /// answers of both parts.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the timelines outgrow 128 bits.
+    pub fn cascade(
+        grid: &Grid<Tile>,
+        mut on_row: impl FnMut(&BeamRow<'_>),
+    ) -> Result<(u64, u128), SimulationError> {
+        simulate(grid, &starts(grid), |row, timelines, hits| {
+            on_row(&BeamRow {
+                row,
+                timelines,
+                hits,
+            });
+        })
+    }
+
+    /// The answers of both parts for each `S` on its own, in reading order.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the timelines of a start outgrow 128 bits.
+    pub fn per_start(grid: &Grid<Tile>) -> Result<Vec<(Pos, u64, u128)>, SimulationError> {
+        starts(grid)
+            .into_iter()
+            .map(|start| {
+                let (splits, timelines) = simulate(grid, &[start], |_, _, _| ())?;
+                Ok((start, splits, timelines))
+            })
+            .collect()
+    }
+
+    /// Plays the beam cascade, drawing the beams with `|`, one frame per row.
+    pub fn animate(grid: &Grid<Tile>, animator: &mut impl Animator<char>) {
+        if !animator.enabled() {
+            return;
+        }
+        let mut canvas = grid.map(|&tile| tile.glyph());
+        // Tracking only which beams are lit cannot overflow.
+        let _ = simulate::<bool>(grid, &starts(grid), |row, lit, _| {
+            for (col, _) in lit.iter().enumerate().filter(|&(_, &lit)| lit) {
+                for row in row..grid.height().min(row + 2) {
+                    let cell = &mut canvas[Pos::new(row, col)];
+                    if *cell == '.' {
+                        *cell = '|';
+                    }
+                }
+            }
+            animator.frame(|| canvas.clone());
+        });
+    }
+}
+
+impl DaySolution for Day07 {
+    const DAY: u8 = 7;
+
+    type Parsed = Grid<Tile>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        parse(input).map_err(AocError::invalid_input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        part_1(parsed)
+            .map(Answer::from)
+            .map_err(|err| AocError::SolverFailed(err.to_string()))
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        part_2(parsed).map_err(|err| AocError::SolverFailed(err.to_string()))
     }
-    let num_timelines = pending.into_iter().sum();
-    (num_splits, num_timelines)
 }
 
 #[cfg(test)]
@@ -108,13 +357,111 @@ mod tests {
     fn test_part_1() {
         let grid = parse(EXAMPLE1).unwrap();
         let result = part_1(&grid);
-        assert_eq!(result, 21);
+        assert_eq!(result, Ok(21));
     }
 
     #[test]
     fn test_part_2() {
         let grid = parse(EXAMPLE1).unwrap();
         let result = part_2(&grid);
-        assert_eq!(result, 40);
+        assert_eq!(result, Ok(Answer::from(40_u64)));
+    }
+
+    #[test]
+    fn test_uneven_grid() {
+        // Taller than wide, with splitters on odd rows and against both edges.
+        let grid = parse(".S.\n...\n.^.\n^..\n...\n..^\n...").unwrap();
+        assert_eq!(
+            simulate(&grid, &starts(&grid), |_, _, _| ()),
+            Ok((3, 4_u128))
+        );
+    }
+
+    /// A manifold three wide where every two rows double the timelines in the middle,
+    /// sending as many off the sides, for `3 * 2^doublings - 2` timelines in all.
+    fn doubling(doublings: usize) -> Grid<Tile> {
+        let rows = ".S.\n...\n".to_string() + &".^.\n^.^\n".repeat(doublings) + "...";
+        parse(&rows).unwrap()
+    }
+
+    #[test]
+    fn test_large_counts() {
+        let grid = doubling(100);
+        assert_eq!(part_2(&grid), Ok(Answer::from(3 * (1_u128 << 100) - 2)));
+        let grid = doubling(130);
+        assert_eq!(part_1(&grid), Ok(3 * 130));
+        #[cfg(feature = "bigint")]
+        assert_eq!(
+            part_2(&grid),
+            Ok(Answer::from("4083388403051261561560495289181218537470"))
+        );
+        #[cfg(not(feature = "bigint"))]
+        assert_eq!(part_2(&grid), Err(SimulationError::Overflow));
+    }
+
+    #[test]
+    fn test_multiple_starts() {
+        // The second start is below the first, and shares the splitter at row 4.
+        let grid = parse("..S..\n.....\n..^..\n.S...\n.^...\n.....").unwrap();
+        assert_eq!(part_1(&grid), Ok(2));
+        assert_eq!(part_2(&grid), Ok(Answer::from(5_u64)));
+        assert_eq!(
+            Day07::per_start(&grid),
+            Ok(vec![(Pos::new(0, 2), 2, 3), (Pos::new(3, 1), 1, 2)])
+        );
+        let grid = parse("...\n.^.").unwrap();
+        assert_eq!(part_1(&grid), Err(SimulationError::NoStart));
+    }
+
+    #[test]
+    fn test_absorbers_and_deflectors() {
+        let grid = parse("..S..\n..>..\n...^.\n..#.<\n.....").unwrap();
+        assert_eq!(part_1(&grid), Ok(1));
+        // One timeline ends in the absorber, the other is deflected back and carries on.
+        assert_eq!(part_2(&grid), Ok(Answer::from(2_u64)));
+        let mut bottom = Vec::new();
+        Day07::cascade(&grid, |beams| bottom = beams.timelines.to_vec()).unwrap();
+        assert_eq!(bottom, [0, 0, 0, 1, 0]);
+        let grid = parse("S\n<").unwrap();
+        assert_eq!(part_2(&grid), Ok(Answer::from(1_u64)));
+        assert_eq!(
+            parse("S\n@").unwrap_err(),
+            GridError::InvalidTile {
+                ch: '@',
+                pos: Pos::new(1, 0)
+            }
+        );
+    }
+
+    #[test]
+    fn test_cascade() {
+        let grid = parse(EXAMPLE1).unwrap();
+        let mut rows = Vec::new();
+        let answers = Day07::cascade(&grid, |beams| {
+            rows.push((beams.row, beams.timelines.to_vec(), beams.hits.to_vec()));
+        });
+        assert_eq!(answers, Ok((21, 40)));
+        assert_eq!(rows.len(), grid.height());
+        let (row, timelines, hits) = &rows[4];
+        assert_eq!(*row, 4);
+        assert_eq!(timelines[5..10], [1, 0, 2, 0, 1]);
+        assert_eq!(*hits, [6, 8]);
+        let splits: usize = rows.iter().map(|(_, _, hits)| hits.len()).sum();
+        assert_eq!(splits, 21);
+    }
+
+    #[test]
+    fn test_animate() {
+        let grid = parse(EXAMPLE1).unwrap();
+        let mut frames = Vec::new();
+        Day07::animate(&grid, &mut frames);
+        let first = frames.first().unwrap().to_string();
+        assert!(first.starts_with(".......S.......\n.......|.......\n.......^.......\n"));
+        let last = frames.last().unwrap().to_string();
+        assert!(last.starts_with(".......S.......\n.......|.......\n......|^|......\n"));
+        assert!(
+            last.ends_with("|^|^|^|^|^|||^|\n|.|.|.|.|.|||.|\n"),
+            "{last}"
+        );
     }
 }
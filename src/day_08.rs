@@ -1,23 +1,34 @@
 use std::cmp::Reverse;
-use std::collections::BinaryHeap;
+use std::collections::{BinaryHeap, HashMap};
 use std::fmt::Display;
+use std::io;
 use std::num::ParseIntError;
-use std::str::FromStr;
+use std::path::Path;
 
 use thiserror::Error;
+use tracing::{debug, trace};
 
-use crate::shared::UnionFind;
+use crate::shared::input::{InputError, Locate, ParseInput, ParseLine};
+use crate::shared::mst::{Kruskal, Merge};
+use crate::shared::parse::{ParseArrayError, normalize_input_trimmed, parse_array};
+use crate::shared::viz::{Rgb, category_color, export_points3d};
+use crate::shared::{FastMap, UnionFind};
+use crate::{Answer, AocError, DaySolution};
 
 #[derive(Debug, Error)]
-enum ParseError {
-    #[error("Syntax error")]
-    SyntaxError,
+pub enum ParseError {
     #[error(transparent)]
-    InvalidNumber(#[from] ParseIntError),
+    InvalidPoint(#[from] ParseArrayError<ParseIntError>),
+}
+
+impl Locate for ParseError {
+    fn offset(&self, _text: &str) -> Option<usize> {
+        None
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Point {
+pub struct Point {
     x: u32,
     y: u32,
     z: u32,
@@ -37,17 +48,11 @@ impl Point {
     }
 }
 
-impl FromStr for Point {
+impl ParseLine<'_> for Point {
     type Err = ParseError;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split(',');
-        let x = parts.next().ok_or(ParseError::SyntaxError)?.parse()?;
-        let y = parts.next().ok_or(ParseError::SyntaxError)?.parse()?;
-        let z = parts.next().ok_or(ParseError::SyntaxError)?.parse()?;
-        if parts.next().is_some() {
-            return Err(ParseError::SyntaxError);
-        }
+    fn parse_line(s: &str) -> Result<Self, Self::Err> {
+        let [x, y, z] = parse_array(s.split(','))?;
         Ok(Self { x, y, z })
     }
 }
@@ -60,13 +65,30 @@ impl Display for Point {
 }
 
 #[aoc_generator(day8)]
-fn parse(input: &str) -> Result<Vec<Point>, ParseError> {
-    input.lines().map(str::parse).collect()
+fn parse(input: &str) -> Result<Vec<Point>, InputError<ParseError>> {
+    let input = normalize_input_trimmed(input);
+    Vec::parse_input(&input)
 }
 
 #[aoc(day8, part1)]
 fn part_1(points: &[Point]) -> u64 {
-    groups_after_connecting(points, 1000)
+    Day08::largest_circuits(points, Day08::CONNECTIONS)
+}
+
+#[aoc(day8, part1, GridHash)]
+fn part_1_grid_hash(points: &[Point]) -> u64 {
+    largest_groups(&connect(
+        points,
+        &closest_pairs_grid(points, Day08::CONNECTIONS),
+    ))
+}
+
+#[aoc(day8, part1, SelectNth)]
+fn part_1_select_nth(points: &[Point]) -> u64 {
+    largest_groups(&connect(
+        points,
+        &closest_pairs_select(points, Day08::CONNECTIONS),
+    ))
 }
 
 #[aoc(day8, part2)]
@@ -74,19 +96,18 @@ fn part_2(points: &[Point]) -> u64 {
     last_connection(points)
 }
 
-fn groups_after_connecting(points: &[Point], connections: usize) -> u64 {
-    let mut pairs = Vec::new();
-    for (i, p1) in points.iter().enumerate() {
-        for (j, p2) in points[..i].iter().enumerate() {
-            let dist_sq = p1.dist_sq(*p2);
-            pairs.push((dist_sq, j, i));
-        }
-    }
-    let (small, _, _) = pairs.select_nth_unstable(connections);
-    let mut uf = UnionFind::new(points.len());
-    for &(_, i, j) in small.iter() {
-        uf.union(i, j);
-    }
+#[aoc(day8, part2, Heap)]
+fn part_2_heap(points: &[Point]) -> u64 {
+    last_connection_heap(points)
+}
+
+#[aoc(day8, part2, Kruskal)]
+fn part_2_kruskal(points: &[Point]) -> u64 {
+    last_connection_sorted(points)
+}
+
+/// Product of the sizes of the three largest circuits.
+fn largest_groups(uf: &UnionFind) -> u64 {
     let mut sizes = uf.roots().map(|(_, s)| s).collect::<Vec<_>>();
     sizes.sort_unstable();
     let mut prod = 1;
@@ -96,7 +117,204 @@ fn groups_after_connecting(points: &[Point], connections: usize) -> u64 {
     prod
 }
 
+/// A pair of points, as the squared distance between them and their indices.
+type Pair = (u64, usize, usize);
+
+/// Joins the `connections` closest pairs of points into circuits.
+fn connect_closest(points: &[Point], connections: usize) -> UnionFind {
+    connect(points, &closest_pairs_all(points, connections))
+}
+
+/// Joins each of `pairs` into circuits.
+fn connect(points: &[Point], pairs: &[Pair]) -> UnionFind {
+    let mut uf = UnionFind::new(points.len());
+    for &(_, i, j) in pairs {
+        if uf.union(i, j) {
+            trace!(target: "replay", step = "union", a = i, b = j, circuits = uf.num_roots());
+        }
+    }
+    uf
+}
+
+/// The `k` closest pairs of points, in no particular order, from every pair.
+///
+/// Keeps only the `k` closest pairs seen so far, in a max-heap, rather than collecting all
+/// of them.
+fn closest_pairs_all(points: &[Point], k: usize) -> Vec<Pair> {
+    if k == 0 {
+        return Vec::new();
+    }
+    let mut closest = BinaryHeap::with_capacity(k + 1);
+    for (i, p1) in points.iter().enumerate() {
+        for (j, p2) in points[..i].iter().enumerate() {
+            let pair = (p1.dist_sq(*p2), j, i);
+            if closest.len() < k {
+                closest.push(pair);
+            } else if closest.peek().is_some_and(|&farthest| pair < farthest) {
+                closest.pop();
+                closest.push(pair);
+            }
+        }
+    }
+    closest.into_vec()
+}
+
+/// The `k` closest pairs of points, in no particular order, selected from a list of every
+/// pair.
+fn closest_pairs_select(points: &[Point], k: usize) -> Vec<Pair> {
+    let mut pairs = Vec::new();
+    for (i, p1) in points.iter().enumerate() {
+        for (j, p2) in points[..i].iter().enumerate() {
+            pairs.push((p1.dist_sq(*p2), j, i));
+        }
+    }
+    keep_closest(&mut pairs, k);
+    pairs
+}
+
+/// Shrinks `pairs` to the `k` closest.
+fn keep_closest(pairs: &mut Vec<Pair>, k: usize) {
+    if k < pairs.len() {
+        pairs.select_nth_unstable(k);
+        pairs.truncate(k);
+    }
+}
+
+/// The `k` closest pairs of points, in no particular order.
+///
+/// Only pairs within a distance are considered, found by [`pairs_within`]. The first
+/// distance is guessed to have about `k` such pairs if the points were spread evenly, and
+/// doubled until there are at least `k`; then no closer pair can have been missed.
+fn closest_pairs_grid(points: &[Point], k: usize) -> Vec<Pair> {
+    let len = points.len();
+    if k >= len * len.saturating_sub(1) / 2 {
+        return closest_pairs_all(points, k);
+    }
+    let mut side = initial_side(points, k);
+    loop {
+        let mut pairs = pairs_within(points, side);
+        if pairs.len() >= k {
+            keep_closest(&mut pairs, k);
+            return pairs;
+        }
+        side *= 2;
+    }
+}
+
+/// The pairs of points at most `side` apart, in no particular order.
+///
+/// Hashes the points into a grid of cubes with that side, so those pairs are in the same or
+/// adjacent cubes, and only pairs of such points are measured.
+fn pairs_within(points: &[Point], side: u64) -> Vec<Pair> {
+    let cube = |p: &Point| [p.x, p.y, p.z].map(|c| u64::from(c) / side);
+    let mut cubes = FastMap::<[u64; 3], Vec<usize>>::default();
+    for (ix, p) in points.iter().enumerate() {
+        cubes.entry(cube(p)).or_default().push(ix);
+    }
+    let max_sq = side.saturating_mul(side);
+    let mut pairs = Vec::new();
+    for (i, p1) in points.iter().enumerate() {
+        let [cx, cy, cz] = cube(p1);
+        for nx in cx.saturating_sub(1)..=cx + 1 {
+            for ny in cy.saturating_sub(1)..=cy + 1 {
+                for nz in cz.saturating_sub(1)..=cz + 1 {
+                    let Some(near) = cubes.get(&[nx, ny, nz]) else {
+                        continue;
+                    };
+                    for &j in near.iter().filter(|&&j| j < i) {
+                        let dist_sq = p1.dist_sq(points[j]);
+                        if dist_sq <= max_sq {
+                            pairs.push((dist_sq, j, i));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    debug!(side, pairs = pairs.len(), "pairs within a cube's side");
+    pairs
+}
+
+/// The side of a cube expected to hold about `k` pairs within that distance, were the
+/// points spread evenly over their bounding box.
+#[expect(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    reason = "an estimate"
+)]
+fn initial_side(points: &[Point], k: usize) -> u64 {
+    let extent = |coord: fn(&Point) -> u32| {
+        let (min, max) = points
+            .iter()
+            .map(coord)
+            .fold((u32::MAX, 0), |(lo, hi), c| (lo.min(c), hi.max(c)));
+        f64::from(max - min) + 1.0
+    };
+    let volume = extent(|p| p.x) * extent(|p| p.y) * extent(|p| p.z);
+    let n = points.len() as f64;
+    // Pairs within r of each other: n^2 / 2 * (4/3 pi r^3) / volume.
+    let radius = (3.0 * k as f64 * volume / (2.0 * std::f64::consts::PI * n * n)).cbrt();
+    (radius as u64).max(1)
+}
+
+/// Colors each point by its circuit after `connections` connections, largest circuits first.
+fn colored_circuits(points: &[Point], connections: usize) -> Vec<([f64; 3], Rgb)> {
+    let mut uf = connect_closest(points, connections);
+    let mut roots = uf.roots().collect::<Vec<_>>();
+    roots.sort_unstable_by_key(|&(root, size)| (Reverse(size), root));
+    let rank = roots
+        .iter()
+        .enumerate()
+        .map(|(rank, &(root, _))| (root, rank))
+        .collect::<HashMap<_, _>>();
+    points
+        .iter()
+        .enumerate()
+        .map(|(ix, p)| {
+            let coords = [p.x, p.y, p.z].map(f64::from);
+            (coords, category_color(rank[&uf.find(ix)]))
+        })
+        .collect()
+}
+
+/// Joins the closest pairs until all points are in one circuit, and returns the product of
+/// the X coordinates of the last pair joined.
 fn last_connection(points: &[Point]) -> u64 {
+    let pairs = spanning_pairs(points);
+    let mut last = None;
+    for (merges, merge) in Kruskal::new(points.len(), pairs).enumerate() {
+        let circuits = points.len() - merges - 1;
+        trace!(target: "replay", step = "union", a = merge.a, b = merge.b, circuits);
+        last = Some(merge);
+    }
+    let Merge { a, b, .. } = last.expect("Points are never all connected");
+    u64::from(points[a].x) * u64::from(points[b].x)
+}
+
+/// The closest pairs in increasing order of distance, up to the one that brings all points
+/// into one circuit.
+///
+/// The last pair is the longest edge of a minimum spanning tree, so only pairs up to that
+/// length matter. Kruskal's algorithm runs on the sorted pairs within a distance, doubling
+/// it until they connect every point.
+fn spanning_pairs(points: &[Point]) -> Vec<Pair> {
+    let mut side = initial_side(points, points.len());
+    loop {
+        let mut pairs = pairs_within(points, side);
+        pairs.sort_unstable();
+        let mut kruskal = Kruskal::new(points.len(), pairs.iter().copied());
+        let last = kruskal.by_ref().last();
+        if kruskal.is_spanning() {
+            pairs.truncate(last.map_or(0, |merge| merge.edges));
+            return pairs;
+        }
+        side *= 2;
+    }
+}
+
+/// Same as [`last_connection`], but pops every pair from a heap instead.
+fn last_connection_heap(points: &[Point]) -> u64 {
     let mut pairs = Vec::new();
     for (i, p1) in points.iter().enumerate() {
         for (j, p2) in points[..i].iter().enumerate() {
@@ -104,6 +322,7 @@ fn last_connection(points: &[Point]) -> u64 {
             pairs.push((Reverse(dist_sq), j, i));
         }
     }
+    debug!(pairs = pairs.len(), "computed pairwise distances");
     let mut heap = BinaryHeap::<_>::from(pairs);
     let mut uf = UnionFind::new(points.len());
     let mut last_union = None;
@@ -111,6 +330,7 @@ fn last_connection(points: &[Point]) -> u64 {
         && let Some((_, i, j)) = heap.pop()
     {
         if uf.union(i, j) {
+            trace!(target: "replay", step = "union", a = i, b = j, circuits = uf.num_roots());
             last_union = Some((i, j));
         }
     }
@@ -118,8 +338,116 @@ fn last_connection(points: &[Point]) -> u64 {
     u64::from(points[i].x) * u64::from(points[j].x)
 }
 
+/// Same as [`last_connection`], but sorts every pair.
+fn last_connection_sorted(points: &[Point]) -> u64 {
+    let mut pairs = Vec::new();
+    for (i, p1) in points.iter().enumerate() {
+        for (j, p2) in points[..i].iter().enumerate() {
+            pairs.push((p1.dist_sq(*p2), j, i));
+        }
+    }
+    pairs.sort_unstable();
+    let mut kruskal = Kruskal::new(points.len(), pairs);
+    let last = kruskal.by_ref().last();
+    assert!(kruskal.is_spanning(), "Points are never all connected");
+    let Merge { a, b, .. } = last.expect("Points are never all connected");
+    u64::from(points[a].x) * u64::from(points[b].x)
+}
+
+/// The circuits at one point of [`Day08::timeline`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// Pairs connected so far, including those already in the same circuit.
+    pub connections: usize,
+    /// Connections so far that joined two circuits.
+    pub merges: usize,
+    /// Sizes of the circuits, largest first.
+    pub sizes: Vec<usize>,
+}
+
+impl Snapshot {
+    fn new(connections: usize, merges: usize, circuits: &UnionFind) -> Self {
+        let mut sizes = circuits.roots().map(|(_, size)| size).collect::<Vec<_>>();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        Self {
+            connections,
+            merges,
+            sizes,
+        }
+    }
+}
+
+pub struct Day08;
+
+impl Day08 {
+    /// Connections made in part 1.
+    pub const CONNECTIONS: usize = 1000;
+
+    /// The product of the sizes of the three largest circuits after connecting the
+    /// `connections` closest pairs; part 1 makes [`Self::CONNECTIONS`] of them.
+    #[must_use]
+    pub fn largest_circuits(points: &[Point], connections: usize) -> u64 {
+        largest_groups(&connect_closest(points, connections))
+    }
+
+    /// How the circuits coalesce as the closest pairs are connected: a snapshot before any
+    /// connection, after every `every` merges, and once all points are in one circuit.
+    ///
+    /// The circuits of part 1 are those of the last snapshot within
+    /// [`Self::CONNECTIONS`] connections, taking `every` as 1, and part 2 is decided by the
+    /// pair connected last.
+    ///
+    /// # Panics
+    ///
+    /// If `every` is 0.
+    #[must_use]
+    pub fn timeline(points: &[Point], every: usize) -> Vec<Snapshot> {
+        assert!(every > 0, "Snapshots must be at least one merge apart");
+        let mut kruskal = Kruskal::new(points.len(), spanning_pairs(points));
+        let mut snapshots = vec![Snapshot::new(0, 0, kruskal.components())];
+        let mut merges = 0;
+        while let Some(merge) = kruskal.next() {
+            merges += 1;
+            if merges % every == 0 || kruskal.is_spanning() {
+                snapshots.push(Snapshot::new(merge.edges, merges, kruskal.components()));
+            }
+        }
+        snapshots
+    }
+
+    /// Writes the points to a PLY or OBJ file, colored by their circuit after `connections`
+    /// connections.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be written, or has an unknown extension.
+    pub fn export_circuits(points: &[Point], connections: usize, path: &Path) -> io::Result<()> {
+        export_points3d(path, &colored_circuits(points, connections))
+    }
+}
+
+impl DaySolution for Day08 {
+    const DAY: u8 = 8;
+
+    type Parsed = Vec<Point>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        parse(input).map_err(AocError::invalid_input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part_1(parsed).into())
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part_2(parsed).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
     use super::*;
 
     const EXMAPLE: &str = "\
@@ -178,14 +506,81 @@ mod tests {
     #[test]
     fn test_part_1() {
         let points = parse(EXMAPLE).unwrap();
-        let result = groups_after_connecting(&points, 10);
+        let result = Day08::largest_circuits(&points, 10);
         assert_eq!(result, 40);
     }
 
+    #[test]
+    fn test_closest_pairs() {
+        let points = parse(EXMAPLE).unwrap();
+        for k in [1, 10, 50, 189, 190, 500] {
+            let mut grid = closest_pairs_grid(&points, k);
+            let mut all = closest_pairs_all(&points, k);
+            let mut select = closest_pairs_select(&points, k);
+            grid.sort_unstable();
+            all.sort_unstable();
+            select.sort_unstable();
+            assert_eq!(grid, all, "k = {k}");
+            assert_eq!(select, all, "k = {k}");
+        }
+        assert_eq!(part_1_grid_hash(&points), part_1(&points));
+        assert_eq!(part_1_select_nth(&points), part_1(&points));
+        assert!(closest_pairs_all(&points, 0).is_empty());
+    }
+
+    #[test]
+    fn test_timeline() {
+        let points = parse(EXMAPLE).unwrap();
+        let timeline = Day08::timeline(&points, 1);
+        assert_eq!(timeline.len(), points.len());
+        assert_eq!(timeline[0].sizes, [1; 20]);
+        assert!(timeline.iter().enumerate().all(|(ix, s)| s.merges == ix));
+        assert_eq!(timeline.last().unwrap().sizes, [20]);
+        let after_10 = timeline.iter().rfind(|s| s.connections <= 10).unwrap();
+        assert_eq!(after_10.sizes[..3], [5, 4, 2]);
+
+        let sparse = Day08::timeline(&points, 5);
+        let merges = sparse.iter().map(|s| s.merges).collect::<Vec<_>>();
+        assert_eq!(merges, [0, 5, 10, 15, 19]);
+        assert_eq!(sparse[2], timeline[10]);
+    }
+
+    #[test]
+    fn test_colored_circuits() {
+        let points = parse(EXMAPLE).unwrap();
+        let colored = colored_circuits(&points, 10);
+        let largest = colored.iter().filter(|(_, c)| *c == category_color(0));
+        assert_eq!(largest.count(), 5);
+        let colors = colored.iter().map(|&(_, c)| c).collect::<HashSet<_>>();
+        assert_eq!(colors.len(), 11);
+    }
+
     #[test]
     fn test_part_2() {
         let points = parse(EXMAPLE).unwrap();
         let result = last_connection(&points);
         assert_eq!(result, 25272);
     }
+
+    #[test]
+    fn test_part_2_kruskal() {
+        let points = parse(EXMAPLE).unwrap();
+        let result = last_connection_sorted(&points);
+        assert_eq!(result, 25272);
+    }
+
+    #[test]
+    fn test_part_2_heap() {
+        let points = parse(EXMAPLE).unwrap();
+        let result = last_connection_heap(&points);
+        assert_eq!(result, 25272);
+    }
+
+    #[test]
+    fn test_last_connection_outlier() {
+        // One far point: the first distances tried can't reach it.
+        let mut points = parse(EXMAPLE).unwrap();
+        points.push(Point::new(100_000, 0, 0));
+        assert_eq!(last_connection(&points), last_connection_sorted(&points));
+    }
 }
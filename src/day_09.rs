@@ -1,113 +1,126 @@
-use std::collections::BTreeSet;
-use std::fmt::Display;
 use std::num::ParseIntError;
-use std::str::FromStr;
+#[cfg(feature = "svg")]
+use std::{io, path::Path};
 
 use thiserror::Error;
+use tracing::debug;
 
-use crate::shared::{Grid, Pos, UnionFind};
+use crate::shared::compress::CoordCompressor;
+use crate::shared::geometry::{Point, Polygon, PolygonError};
+use crate::shared::input::{InputError, Locate, ParseInput, ParseLine};
+use crate::shared::parse::{ParseArrayError, normalize_input_trimmed, parse_array};
+#[cfg(feature = "svg")]
+use crate::shared::viz::{Rgb, Svg};
+use crate::shared::{Grid, Pos, UnionFind, par};
+use crate::{Answer, AocError, DaySolution};
 
 #[derive(Debug, Error)]
-enum ParseError {
-    #[error("Syntax error")]
-    SyntaxError,
+pub enum ParseError {
     #[error(transparent)]
-    InvalidNumber(#[from] ParseIntError),
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Point {
-    x: u32,
-    y: u32,
+    InvalidPoint(#[from] ParseArrayError<ParseIntError>),
+    #[error(transparent)]
+    InvalidPolygon(#[from] PolygonError),
 }
 
-impl Point {
-    #[allow(unused, reason = "tests")]
-    const fn new(x: u32, y: u32) -> Self {
-        Self { x, y }
-    }
-
-    const fn area(self, other: Self) -> u64 {
-        let dx = self.x.abs_diff(other.x) as u64 + 1;
-        let dy = self.y.abs_diff(other.y) as u64 + 1;
-        dx * dy
+impl Locate for ParseError {
+    fn offset(&self, _text: &str) -> Option<usize> {
+        None
     }
 }
 
-impl FromStr for Point {
+impl ParseLine<'_> for Point {
     type Err = ParseError;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split(',');
-        let x = parts.next().ok_or(ParseError::SyntaxError)?.parse()?;
-        let y = parts.next().ok_or(ParseError::SyntaxError)?.parse()?;
-        if parts.next().is_some() {
-            return Err(ParseError::SyntaxError);
-        }
+    fn parse_line(s: &str) -> Result<Self, Self::Err> {
+        let [x, y] = parse_array(s.split(','))?;
         Ok(Self { x, y })
     }
 }
 
-impl Display for Point {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let &Self { x, y } = self;
-        write!(f, "{x},{y}")
-    }
-}
-
 #[aoc_generator(day9)]
-fn parse(input: &str) -> Result<Vec<Point>, ParseError> {
-    input.lines().map(str::parse).collect()
+fn parse(input: &str) -> Result<Polygon, InputError<ParseError>> {
+    let input = normalize_input_trimmed(input);
+    let points: Vec<Point> = Vec::parse_input(&input)?;
+    let lines = points.len();
+    // Errors about the whole polygon point at its last line.
+    Polygon::new(points).map_err(|err| InputError {
+        line: err
+            .vertex()
+            .map_or_else(|| lines.max(1), |vertex| vertex + 1),
+        col: None,
+        error: err.into(),
+    })
 }
 
 #[aoc(day9, part1)]
-fn part_1(points: &[Point]) -> u64 {
-    points
-        .iter()
-        .enumerate()
-        .flat_map(|(i, p1)| points[..i].iter().map(move |p2| (p1, p2)))
-        .map(|(&p1, &p2)| p1.area(p2))
-        .max()
-        .unwrap()
+fn part_1(polygon: &Polygon) -> u64 {
+    let points = polygon.vertices();
+    par::max_by_index(points.len(), |i| {
+        points[..i]
+            .iter()
+            .map(|&p2| points[i].rect_tiles(p2))
+            .max()
+            .unwrap_or(0)
+    })
+    .unwrap_or(0)
 }
 
 #[aoc(day9, part2)]
-fn part_2(points: &[Point]) -> u64 {
+fn part_2(polygon: &Polygon) -> u64 {
+    polygon
+        .largest_inside_rect()
+        .map_or(0, |(p1, p2)| p1.rect_tiles(p2))
+}
+
+/// Same as [`part_2`], but fills in a grid of the compressed coordinates and checks
+/// rectangles against its prefix sums.
+#[aoc(day9, part2, Compressed)]
+fn part_2_compressed(polygon: &Polygon) -> u64 {
+    let points = polygon.vertices();
     let (xs, ys) = compress_coordinates(points);
+    debug!(
+        columns = xs.len(),
+        rows = ys.len(),
+        "compressed coordinates"
+    );
     let mut grid = draw_outline(points, &xs, &ys);
     fill_interior(&mut grid, &xs, &ys);
     accumulate_sum(&mut grid, &xs, &ys);
     find_max_interior_rectangle(points, &grid, &xs, &ys)
 }
 
-fn compress_coordinates(points: &[Point]) -> (Vec<u32>, Vec<u32>) {
-    let mut xs = BTreeSet::new();
-    let mut ys = BTreeSet::new();
-    xs.insert(0);
-    ys.insert(0);
-    for pt in points {
-        xs.insert(pt.x);
-        xs.insert(pt.x + 1);
-        ys.insert(pt.y);
-        ys.insert(pt.y + 1);
-    }
-    (xs.into_iter().collect(), ys.into_iter().collect())
+/// Compresses the columns and rows so every point's column and row gets an index of its
+/// own, with indices for the gaps between them, and one for the outside before them.
+fn compress_coordinates(points: &[Point]) -> (CoordCompressor<i64>, CoordCompressor<i64>) {
+    let compress = |coord: fn(&Point) -> i64| {
+        let outside = points.iter().map(coord).min().unwrap_or(0) - 1;
+        points
+            .iter()
+            .flat_map(|p| [coord(p), coord(p) + 1])
+            .chain([outside])
+            .collect()
+    };
+    (compress(|p| p.x), compress(|p| p.y))
 }
 
-fn draw_outline(points: &[Point], xs: &[u32], ys: &[u32]) -> Grid<u64> {
+fn draw_outline(
+    points: &[Point],
+    xs: &CoordCompressor<i64>,
+    ys: &CoordCompressor<i64>,
+) -> Grid<u64> {
     let mut grid = Grid::new(vec![0_u64; xs.len() * ys.len()], xs.len(), ys.len());
     for (&p1, &p2) in points.iter().zip(points.iter().cycle().skip(1)) {
         if p1.x == p2.x {
-            let xi = xs.partition_point(|&x| x < p1.x);
-            let yi1 = ys.partition_point(|&y| y < p1.y);
-            let yi2 = ys.partition_point(|&y| y < p2.y);
+            let xi = xs.index(p1.x);
+            let yi1 = ys.index(p1.y);
+            let yi2 = ys.index(p2.y);
             for yi in yi1.min(yi2)..=yi1.max(yi2) {
                 grid[Pos::new(yi, xi)] = 1;
             }
         } else if p1.y == p2.y {
-            let yi = ys.partition_point(|&y| y < p1.y);
-            let xi1 = xs.partition_point(|&x| x < p1.x);
-            let xi2 = xs.partition_point(|&x| x < p2.x);
+            let yi = ys.index(p1.y);
+            let xi1 = xs.index(p1.x);
+            let xi2 = xs.index(p2.x);
             for xi in xi1.min(xi2)..=xi1.max(xi2) {
                 grid[Pos::new(yi, xi)] = 1;
             }
@@ -116,7 +129,7 @@ fn draw_outline(points: &[Point], xs: &[u32], ys: &[u32]) -> Grid<u64> {
     grid
 }
 
-fn fill_interior(grid: &mut Grid<u64>, xs: &[u32], ys: &[u32]) {
+fn fill_interior(grid: &mut Grid<u64>, xs: &CoordCompressor<i64>, ys: &CoordCompressor<i64>) {
     let mut uf = UnionFind::new(xs.len() * ys.len() + 1);
     let outside = xs.len() * ys.len();
     for yi in 0..ys.len() {
@@ -144,41 +157,26 @@ fn fill_interior(grid: &mut Grid<u64>, xs: &[u32], ys: &[u32]) {
         }
     }
 
+    debug!(components = uf.num_roots(), "connected regions");
+    // Empty cells cut off from the outside are inside, however many regions they form.
     let outside_root = uf.find(outside);
-    let edge_root = uf
-        .roots()
-        .map(|(r, _)| r)
-        .find(|&r| grid[Pos::new(r / xs.len(), r % xs.len())] == 1)
-        .unwrap();
-    let inside_root = uf
-        .roots()
-        .map(|(r, _)| r)
-        .find(|&r| r != outside_root && r != edge_root)
-        .unwrap();
     for yi in 0..ys.len() {
         for xi in 0..xs.len() {
-            let index = yi * xs.len() + xi;
-            if uf.find(index) == inside_root {
-                let pos = Pos::new(yi, xi);
+            let pos = Pos::new(yi, xi);
+            if grid[pos] == 0 && uf.find(yi * xs.len() + xi) != outside_root {
                 grid[pos] = 1;
             }
         }
     }
 }
 
-fn accumulate_sum(grid: &mut Grid<u64>, xs: &[u32], ys: &[u32]) {
-    for (yi, (&y1, &y2)) in ys
-        .iter()
-        .zip(ys[1..].iter().chain([ys.last().unwrap()]))
-        .enumerate()
-    {
-        for (xi, (&x1, &x2)) in xs
-            .iter()
-            .zip(xs[1..].iter().chain([xs.last().unwrap()]))
-            .enumerate()
-        {
+fn accumulate_sum(grid: &mut Grid<u64>, xs: &CoordCompressor<i64>, ys: &CoordCompressor<i64>) {
+    for yi in 0..ys.len() {
+        let height = ys.width(yi).map_or(0, i64::unsigned_abs);
+        for xi in 0..xs.len() {
+            let width = xs.width(xi).map_or(0, i64::unsigned_abs);
             let pos = Pos::new(yi, xi);
-            let mut sum = u64::from(grid[pos] > 0) * u64::from(y2 - y1) * u64::from(x2 - x1);
+            let mut sum = u64::from(grid[pos] > 0) * height * width;
             if xi > 0 {
                 sum += grid[Pos::new(yi, xi - 1)];
                 if yi > 0 {
@@ -193,17 +191,35 @@ fn accumulate_sum(grid: &mut Grid<u64>, xs: &[u32], ys: &[u32]) {
     }
 }
 
-fn find_max_interior_rectangle(points: &[Point], grid: &Grid<u64>, xs: &[u32], ys: &[u32]) -> u64 {
-    let mut max_area = 0;
-    for (i, &p1) in points.iter().enumerate() {
-        let xi1 = xs.partition_point(|&x| x < p1.x);
-        let yi1 = ys.partition_point(|&y| y < p1.y);
-        for &p2 in &points[i + 1..] {
-            let xi2 = xs.partition_point(|&x| x < p2.x);
-            let yi2 = ys.partition_point(|&y| y < p2.y);
+/// The largest rectangle between two of the points that lies inside the polygon.
+///
+/// A rectangle is inside when its area in the prefix sums matches its full area. Each
+/// point is paired with those after it, and the points are spread over threads with the
+/// `parallel` feature.
+fn find_max_interior_rectangle(
+    points: &[Point],
+    grid: &Grid<u64>,
+    xs: &CoordCompressor<i64>,
+    ys: &CoordCompressor<i64>,
+) -> u64 {
+    let compressed = points
+        .iter()
+        .map(|&p| {
+            let xi = xs.index(p.x);
+            let yi = ys.index(p.y);
+            (p, xi, yi)
+        })
+        .collect::<Vec<_>>();
+    par::max_by_index(compressed.len(), |i| {
+        let (p1, xi1, yi1) = compressed[i];
+        let mut max_area = 0;
+        for &(p2, xi2, yi2) in &compressed[i + 1..] {
             let (xi1, xi2) = (xi1.min(xi2), xi1.max(xi2));
             let (yi1, yi2) = (yi1.min(yi2), yi1.max(yi2));
-            let expected_area = p1.area(p2);
+            let expected_area = p1.rect_tiles(p2);
+            if expected_area <= max_area {
+                continue;
+            }
             let mut grid_sum = grid[Pos::new(yi2, xi2)];
             if xi1 > 0 {
                 if yi2 > 0 {
@@ -215,11 +231,98 @@ fn find_max_interior_rectangle(points: &[Point], grid: &Grid<u64>, xs: &[u32], y
                 grid_sum -= grid[Pos::new(yi1 - 1, xi2)];
             }
             if grid_sum == expected_area {
-                max_area = max_area.max(expected_area);
+                max_area = expected_area;
             }
         }
+        max_area
+    })
+    .unwrap_or(0)
+}
+
+/// Draws the cells of the compressed grid, green inside and grey outside, the outline
+/// through the centers of its tiles, and the largest rectangle inside in red.
+#[cfg(feature = "svg")]
+#[expect(clippy::cast_precision_loss, reason = "Drawing coordinates")]
+fn draw_svg(polygon: &Polygon) -> Svg {
+    const INSIDE: Rgb = [0x9a, 0xcd, 0x32];
+    const OUTSIDE: Rgb = [0xdd, 0xdd, 0xdd];
+    let points = polygon.vertices();
+    let (xs, ys) = compress_coordinates(points);
+    let mut grid = draw_outline(points, &xs, &ys);
+    fill_interior(&mut grid, &xs, &ys);
+    let coord = |v: i64| v as f64;
+    let (left, top) = (xs.value(0), ys.value(0));
+    let (right, bottom) = (xs.value(xs.len() - 1), ys.value(ys.len() - 1));
+    let mut svg = Svg::new(
+        [coord(left), coord(top)],
+        [coord(right - left), coord(bottom - top)],
+    );
+    for yi in 0..ys.len() - 1 {
+        let height = coord(ys.width(yi).unwrap_or(0));
+        let mut xi = 0;
+        // Neighboring cells of a class are drawn as one rectangle.
+        while xi < xs.len() - 1 {
+            let (start, inside) = (xi, grid[Pos::new(yi, xi)] > 0);
+            while xi < xs.len() - 1 && (grid[Pos::new(yi, xi)] > 0) == inside {
+                xi += 1;
+            }
+            svg.rect(
+                [coord(xs.value(start)), coord(ys.value(yi))],
+                [coord(xs.value(xi) - xs.value(start)), height],
+                Some(if inside { INSIDE } else { OUTSIDE }),
+                None,
+            );
+        }
+    }
+    let outline = points
+        .iter()
+        .map(|p| [coord(p.x) + 0.5, coord(p.y) + 0.5])
+        .collect::<Vec<_>>();
+    svg.polygon(&outline, None, Some([0, 0, 0]));
+    if let Some((p1, p2)) = polygon.largest_inside_rect() {
+        let (x, y) = (p1.x.min(p2.x), p1.y.min(p2.y));
+        let (width, height) = (p1.x.abs_diff(p2.x) + 1, p1.y.abs_diff(p2.y) + 1);
+        svg.rect(
+            [coord(x), coord(y)],
+            [width as f64, height as f64],
+            None,
+            Some([0xff, 0, 0]),
+        );
+    }
+    svg
+}
+
+pub struct Day09;
+
+#[cfg(feature = "svg")]
+impl Day09 {
+    /// Draws `polygon` to an SVG file: the compressed grid with its cells classified as
+    /// inside or outside, the outline, and the largest rectangle inside.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be written.
+    pub fn export_svg(polygon: &Polygon, path: &Path) -> io::Result<()> {
+        draw_svg(polygon).save(path)
+    }
+}
+
+impl DaySolution for Day09 {
+    const DAY: u8 = 9;
+
+    type Parsed = Polygon;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        parse(input).map_err(AocError::invalid_input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part_1(parsed).into())
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part_2(parsed).into())
     }
-    max_area
 }
 
 #[cfg(test)]
@@ -241,7 +344,7 @@ mod tests {
     fn test_parse() {
         let result = parse(EXAMPLE).unwrap();
         assert_eq!(
-            result,
+            result.vertices(),
             [
                 Point::new(7, 1),
                 Point::new(11, 1),
@@ -267,5 +370,78 @@ mod tests {
         let points = parse(EXAMPLE).unwrap();
         let result = part_2(&points);
         assert_eq!(result, 24);
+        assert_eq!(part_2_compressed(&points), 24);
+    }
+
+    #[test]
+    fn test_parse_degenerate() {
+        let err = parse("0,0\n2,0\n2,2\n1,3").unwrap_err();
+        assert_eq!(err.line, 3);
+        assert!(matches!(
+            err.error,
+            ParseError::InvalidPolygon(PolygonError::DiagonalEdge(2))
+        ));
+        let err = parse("0,0\n2,0\n2,2\n0,2\n2,0").unwrap_err();
+        assert_eq!(err.line, 5);
+        assert!(matches!(
+            err.error,
+            ParseError::InvalidPolygon(PolygonError::DuplicateVertex {
+                first: 1,
+                second: 4
+            })
+        ));
+        let err = parse("0,0\n2,0\n2,2\n1,2\n1,3").unwrap_err();
+        assert!(matches!(
+            err.error,
+            ParseError::InvalidPolygon(PolygonError::NotClosed(4))
+        ));
+        let err = parse("0,0\n2,0").unwrap_err();
+        assert_eq!(err.line, 1);
+        assert!(matches!(
+            err.error,
+            ParseError::InvalidPolygon(PolygonError::DoublesBack(0))
+        ));
+    }
+
+    #[test]
+    fn test_part_2_collinear() {
+        let points = parse("7,1\n9,1\n11,1\n11,4\n11,7\n9,7\n9,5\n5,5\n2,5\n2,3\n7,3").unwrap();
+        assert_eq!(part_2(&points), 24);
+        assert_eq!(part_2_compressed(&points), 24);
+    }
+
+    #[test]
+    fn test_part_2_split_interior() {
+        // Notches from both sides leave only the outline between columns 2 and 3 in row 3,
+        // cutting the inside in two.
+        let points = parse("0,0\n5,0\n5,2\n3,2\n3,4\n5,4\n5,6\n0,6\n0,4\n2,4\n2,2\n0,2").unwrap();
+        assert_eq!(part_2(&points), 18);
+        assert_eq!(part_2_compressed(&points), 18);
+    }
+
+    #[test]
+    fn test_part_2_narrow_notch() {
+        // The notch between columns 2 and 3 has no tiles in it, so every tile is covered.
+        let points = parse("0,0\n2,0\n2,4\n3,4\n3,0\n5,0\n5,6\n0,6").unwrap();
+        assert_eq!(part_2(&points), 42);
+        assert_eq!(part_2_compressed(&points), 42);
+        // A wider notch leaves its tiles out.
+        let points = parse("0,0\n2,0\n2,4\n4,4\n4,0\n6,0\n6,6\n0,6").unwrap();
+        assert_eq!(part_2(&points), 21);
+    }
+
+    #[test]
+    #[cfg(feature = "svg")]
+    fn test_draw_svg() {
+        let mut out = Vec::new();
+        draw_svg(&parse(EXAMPLE).unwrap()).write(&mut out).unwrap();
+        let svg = String::from_utf8(out).unwrap();
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="1 0 11 8">"#));
+        assert!(svg.contains(r#"<polygon points="7.5,1.5 11.5,1.5 11.5,7.5 9.5,7.5"#));
+        assert!(
+            svg.contains(
+                r##"<rect x="2" y="3" width="8" height="3" fill="none" stroke="#ff0000""##
+            )
+        );
     }
 }
@@ -1,52 +1,104 @@
-use std::num::ParseIntError;
-use std::str::FromStr;
+use std::cmp::Ordering;
+use std::ops::RangeInclusive;
 
 use microlp::{LinearExpr, OptimizationDirection, Problem};
 use thiserror::Error;
+use tracing::{debug, warn};
+
+use crate::shared::combinators::{
+    self, all_consuming, delimited, literal, sep_by, take_while1, uint,
+};
+use crate::shared::input::{InputError, Locate, ParseInput, ParseLine};
+use crate::shared::par;
+use crate::shared::parse::normalize_input_trimmed;
+use crate::{Answer, AocError, DaySolution};
+
+/// Lights that fit in a mask.
+const MAX_LIGHTS: u32 = u64::BITS;
+
+/// Buttons that fit in a mask.
+const MAX_BUTTONS: u32 = u64::BITS;
 
 #[derive(Debug, Error)]
-enum ParseError {
-    #[error("Syntax error")]
-    SyntaxError,
-    #[error(transparent)]
-    InvalidNumber(#[from] ParseIntError),
+pub enum ParseError {
+    #[error("Syntax error: {0}")]
+    SyntaxError(#[from] combinators::Error),
+    #[error("Machine has {0} lights, at most {MAX_LIGHTS} supported")]
+    TooManyLights(usize),
+    #[error("Button toggles light {0}, at most {MAX_LIGHTS} supported")]
+    LightOutOfRange(u8),
+    #[error("Machine has {0} buttons, at most {MAX_BUTTONS} supported")]
+    TooManyButtons(usize),
+}
+
+impl Locate for ParseError {
+    fn offset(&self, text: &str) -> Option<usize> {
+        match self {
+            Self::SyntaxError(err) => Some(err.offset(text)),
+            Self::TooManyLights(_) | Self::LightOutOfRange(_) | Self::TooManyButtons(_) => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct Machine {
-    indicator_lights: u16,
-    buttons: Vec<u16>,
+pub struct Machine {
+    indicator_lights: u64,
+    buttons: Vec<u64>,
     joltage: Vec<u16>,
 }
 
-impl FromStr for Machine {
+impl ParseLine<'_> for Machine {
     type Err = ParseError;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let rest = s.strip_prefix('[').ok_or(ParseError::SyntaxError)?;
-        let (indicator_lights, rest) = rest.split_once("] ").ok_or(ParseError::SyntaxError)?;
+    fn parse_line(s: &str) -> Result<Self, Self::Err> {
+        // [.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
+        let lights = delimited(
+            literal("["),
+            take_while1("indicator light", |ch| matches!(ch, '.' | '#')),
+            literal("]"),
+        );
+        let button = delimited(literal("("), sep_by(uint::<u8>, literal(",")), literal(")"));
+        let joltage = delimited(
+            literal("{"),
+            sep_by(uint::<u16>, literal(",")),
+            literal("}"),
+        );
+        let (indicator_lights, buttons, joltage) = all_consuming(s, |input| {
+            let indicator_lights = lights(input)?;
+            literal(" ")(input)?;
+            let buttons = sep_by(&button, literal(" "))(input)?;
+            literal(" ")(input)?;
+            Ok((indicator_lights, buttons, joltage(input)?))
+        })?;
+        let too_many = |lights: usize| lights > MAX_LIGHTS as usize;
+        if too_many(indicator_lights.len()) {
+            return Err(ParseError::TooManyLights(indicator_lights.len()));
+        }
+        if too_many(joltage.len()) {
+            return Err(ParseError::TooManyLights(joltage.len()));
+        }
+        if buttons.len() > MAX_BUTTONS as usize {
+            return Err(ParseError::TooManyButtons(buttons.len()));
+        }
+        if let Some(&light) = buttons
+            .iter()
+            .flatten()
+            .find(|&&light| u32::from(light) >= MAX_LIGHTS)
+        {
+            return Err(ParseError::LightOutOfRange(light));
+        }
         let indicator_lights = indicator_lights
             .bytes()
             .enumerate()
-            .fold(0, |bits, (pos, ch)| bits | u16::from(ch & 1) << pos);
-        let (buttons, rest) = rest.split_once(" {").ok_or(ParseError::SyntaxError)?;
+            .fold(0, |bits, (pos, ch)| bits | u64::from(ch & 1) << pos);
         let buttons = buttons
-            .split(' ')
-            .map(|btn| {
-                btn.strip_prefix('(')
-                    .ok_or(ParseError::SyntaxError)?
-                    .strip_suffix(')')
-                    .ok_or(ParseError::SyntaxError)?
-                    .split(',')
-                    .try_fold(0_u16, |mask, light| Ok(mask | (1 << light.parse::<u8>()?)))
+            .into_iter()
+            .map(|lights| {
+                lights
+                    .into_iter()
+                    .fold(0_u64, |mask, light| mask | (1 << light))
             })
-            .collect::<Result<_, ParseError>>()?;
-        let joltage = rest
-            .strip_suffix('}')
-            .ok_or(ParseError::SyntaxError)?
-            .split(',')
-            .map(str::parse)
-            .collect::<Result<_, _>>()?;
+            .collect();
         Ok(Self {
             indicator_lights,
             buttons,
@@ -56,25 +108,126 @@ impl FromStr for Machine {
 }
 
 #[aoc_generator(day10)]
-fn parse(input: &str) -> Result<Vec<Machine>, ParseError> {
-    input.lines().map(str::parse).collect()
+fn parse(input: &str) -> Result<Vec<Machine>, InputError<ParseError>> {
+    let input = normalize_input_trimmed(input);
+    Vec::parse_input(&input)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SolveError {
+    #[error("No presses of the buttons turn on the indicator lights")]
+    LightsUnreachable,
+    #[error("No presses of the buttons reach the joltage levels")]
+    JoltageUnreachable,
+    #[error("LP solver failed: {0}")]
+    Lp(String),
+    #[error("Eliminating the joltage system overflows 64 bits")]
+    Overflow,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Machine {machine}: {source}")]
+pub struct MachineError {
+    /// Index of the machine, in input order.
+    pub machine: usize,
+    pub source: SolveError,
+}
+
+/// The sum of `solve` over every machine, or the first machine that couldn't be solved.
+///
+/// Machines are independent, so with the `parallel` feature they are solved concurrently.
+fn total_presses<F>(machines: &[Machine], solve: F) -> Result<u64, MachineError>
+where
+    F: Fn(&Machine) -> Result<u64, SolveError> + Sync + Send,
+{
+    par::map(machines, solve)
+        .into_iter()
+        .enumerate()
+        .map(|(machine, presses)| presses.map_err(|source| MachineError { machine, source }))
+        .sum()
 }
 
 #[aoc(day10, part1)]
-fn part_1(machines: &[Machine]) -> u64 {
-    let mut sum = 0;
-    for machine in machines {
-        sum += activation_buttons(machine.indicator_lights, &machine.buttons)
-            .expect("No solution found");
+fn part_1(machines: &[Machine]) -> Result<u64, MachineError> {
+    total_presses(machines, |machine| {
+        activation_buttons(machine.indicator_lights, &machine.buttons)
+            .ok_or(SolveError::LightsUnreachable)
+    })
+}
+
+/// The fewest buttons whose presses turn on the indicator lights. Solves the system over
+/// GF(2) with Gaussian elimination, only enumerating the free buttons.
+fn activation_buttons(indicator_lights: u64, buttons: &[u64]) -> Option<u64> {
+    // One row per light: the buttons that toggle it, and whether it has to end up on.
+    let mut rows: Vec<(u64, bool)> = (0..MAX_LIGHTS)
+        .map(|light| {
+            let toggles = buttons
+                .iter()
+                .enumerate()
+                .filter(|&(_, &b)| b & (1 << light) != 0)
+                .fold(0_u64, |acc, (ix, _)| acc | 1 << ix);
+            (toggles, indicator_lights & (1 << light) != 0)
+        })
+        .collect();
+    let mut rank = 0;
+    let mut free = Vec::new();
+    for col in 0..buttons.len() {
+        let Some(found) = (rank..rows.len()).find(|&r| rows[r].0 & (1 << col) != 0) else {
+            free.push(col);
+            continue;
+        };
+        rows.swap(rank, found);
+        let (pivot, pivot_on) = rows[rank];
+        for (r, row) in rows.iter_mut().enumerate() {
+            if r != rank && row.0 & (1 << col) != 0 {
+                row.0 ^= pivot;
+                row.1 ^= pivot_on;
+            }
+        }
+        rank += 1;
+    }
+    if rows[rank..].iter().any(|&(_, on)| on) {
+        return None;
     }
-    sum
+    subsets(free.len())
+        .map(|assignment| {
+            let pressed_free = free
+                .iter()
+                .enumerate()
+                .filter(|&(ix, _)| assignment & (1 << ix) != 0)
+                .fold(0_u64, |acc, (_, &col)| acc | 1 << col);
+            let pressed_pivots = rows[..rank]
+                .iter()
+                .filter(|&&(row, on)| on ^ ((row & pressed_free).count_ones() % 2 == 1))
+                .count();
+            u64::from(assignment.count_ones()) + pressed_pivots as u64
+        })
+        .min()
+}
+
+/// Every subset of `len` items, at most [`MAX_BUTTONS`], as a mask.
+fn subsets(len: usize) -> RangeInclusive<u64> {
+    let len = u32::try_from(len).expect("at most MAX_BUTTONS items");
+    0..=u64::MAX.checked_shr(u64::BITS - len).unwrap_or(0)
+}
+
+#[aoc(day10, part1, BruteForce)]
+fn part_1_brute_force(machines: &[Machine]) -> Result<u64, MachineError> {
+    total_presses(machines, |machine| {
+        activation_buttons_brute_force(machine.indicator_lights, &machine.buttons)
+            .ok_or(SolveError::LightsUnreachable)
+    })
 }
 
-fn activation_buttons(indicator_lights: u16, buttons: &[u16]) -> Option<u64> {
+/// Same as [`activation_buttons`], but tries every subset of the buttons: a reference for
+/// the elimination, only feasible for few buttons.
+fn activation_buttons_brute_force(indicator_lights: u64, buttons: &[u64]) -> Option<u64> {
     let mut minimal = u32::MAX;
-    for mask in 0..(1_u16 << buttons.len()) {
+    for mask in subsets(buttons.len()) {
         let num_active = mask.count_ones();
-        if num_active >= minimal { continue; }
+        if num_active >= minimal {
+            continue;
+        }
         let remaining_indicators = buttons
             .iter()
             .enumerate()
@@ -88,20 +241,321 @@ fn activation_buttons(indicator_lights: u16, buttons: &[u16]) -> Option<u64> {
 }
 
 #[aoc(day10, part2)]
-fn part_2(machines: &[Machine]) -> u64 {
-    let mut sum = 0;
-    for machine in machines {
-        sum += minimum_presses(&machine.buttons, &machine.joltage);
+fn part_2(machines: &[Machine]) -> Result<u64, MachineError> {
+    total_presses(machines, |machine| {
+        minimum_presses_exact(&machine.buttons, &machine.joltage)
+    })
+}
+
+#[aoc(day10, part2, Lp)]
+fn part_2_lp(machines: &[Machine]) -> Result<u64, MachineError> {
+    total_presses(machines, |machine| {
+        minimum_presses(&machine.buttons, &machine.joltage)
+    })
+}
+
+/// Same as [`minimum_presses`], but exact: solves the system in integers with Gauss-Jordan
+/// elimination, then searches the presses of the free buttons within their bounds.
+fn minimum_presses_exact(buttons: &[u64], target: &[u16]) -> Result<u64, SolveError> {
+    let system = ReducedSystem::new(buttons, target)?;
+    debug!(
+        buttons = buttons.len(),
+        counters = target.len(),
+        free = system.bounds.len(),
+        "reduced"
+    );
+    let mut free_presses = vec![0; system.bounds.len()];
+    let mut best = None;
+    system.search(&mut free_presses, 0, 0, &mut best);
+    best.ok_or(SolveError::JoltageUnreachable)
+}
+
+/// The joltage equations in reduced row echelon form, scaled to integers: each row has one
+/// pivot button, and coefficients for the free buttons only.
+#[derive(Debug)]
+struct ReducedSystem {
+    /// For each pivot button: its coefficient, the coefficients of the free buttons, and the
+    /// right-hand side.
+    rows: Vec<(i64, Vec<i64>, i64)>,
+    /// The most presses each free button could take.
+    bounds: Vec<i64>,
+    /// Total presses times `scale`, as a constant plus a weight per free button.
+    objective: (i64, Vec<i64>),
+    /// The least common multiple of the pivot coefficients.
+    scale: i64,
+}
+
+impl ReducedSystem {
+    fn new(buttons: &[u64], target: &[u16]) -> Result<Self, SolveError> {
+        let cols = buttons.len();
+        // One row per counter: how much each button adds to it, then the level to reach.
+        let mut rows = (0..target.len())
+            .map(|counter| {
+                buttons
+                    .iter()
+                    .map(|&b| i64::from(b >> counter & 1 != 0))
+                    .chain([i64::from(target[counter])])
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut pivots = Vec::new();
+        let mut free = Vec::new();
+        for col in 0..cols {
+            let rank = pivots.len();
+            let Some(found) = (rank..rows.len()).find(|&r| rows[r][col] != 0) else {
+                free.push(col);
+                continue;
+            };
+            rows.swap(rank, found);
+            let pivot_row = rows[rank].clone();
+            for (r, row) in rows.iter_mut().enumerate() {
+                if r != rank && row[col] != 0 {
+                    eliminate(row, &pivot_row, col)?;
+                }
+            }
+            pivots.push(col);
+        }
+        if rows[pivots.len()..].iter().any(|row| row[cols] != 0) {
+            return Err(SolveError::JoltageUnreachable);
+        }
+        // No button can be pressed more often than the lowest level it adds to.
+        let bound = |col: usize| {
+            target
+                .iter()
+                .enumerate()
+                .filter(|&(counter, _)| buttons[col] >> counter & 1 != 0)
+                .map(|(_, &level)| i64::from(level))
+                .min()
+                .unwrap_or(0)
+        };
+        let rows = pivots
+            .iter()
+            .zip(rows)
+            .map(|(&col, mut row)| {
+                if row[col] < 0 {
+                    for value in &mut row {
+                        *value = -*value;
+                    }
+                }
+                let coefficients = free.iter().map(|&f| row[f]).collect::<Vec<_>>();
+                (row[col], coefficients, row[cols])
+            })
+            .collect::<Vec<_>>();
+        let bounds = free.into_iter().map(bound).collect::<Vec<_>>();
+        // Each pivot button is pressed (rhs - coefficients · free presses) / pivot times.
+        let scale = rows
+            .iter()
+            .try_fold(1_i64, |acc, &(pivot, _, _)| {
+                (acc / gcd(acc, pivot)).checked_mul(pivot)
+            })
+            .ok_or(SolveError::Overflow)?;
+        let constant = scaled_sum(rows.iter().map(|&(pivot, _, rhs)| (rhs, pivot)), scale)
+            .ok_or(SolveError::Overflow)?;
+        let weights = (0..bounds.len())
+            .map(|f| {
+                scaled_sum(rows.iter().map(|(pivot, c, _)| (c[f], *pivot)), scale)
+                    .and_then(|sum| scale.checked_sub(sum))
+                    .ok_or(SolveError::Overflow)
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            rows,
+            bounds,
+            objective: (constant, weights),
+            scale,
+        })
+    }
+
+    /// Total presses of the pivot buttons, given the presses of the free buttons, or `None`
+    /// if some pivot button would need a negative or fractional number of presses.
+    fn pivot_presses(&self, free_presses: &[i64]) -> Option<i64> {
+        self.rows
+            .iter()
+            .map(|(pivot, coefficients, rhs)| {
+                let rest = rhs
+                    - coefficients
+                        .iter()
+                        .zip(free_presses)
+                        .map(|(c, x)| c * x)
+                        .sum::<i64>();
+                (rest >= 0 && rest % pivot == 0).then_some(rest / pivot)
+            })
+            .sum()
+    }
+
+    /// Tries every number of presses of the free buttons from `ix` on, with `pressed` presses
+    /// of the ones before, keeping the fewest total presses in `best`.
+    fn search(&self, free_presses: &mut [i64], ix: usize, pressed: i64, best: &mut Option<u64>) {
+        if best.is_some_and(|best| {
+            self.lower_bound(free_presses, ix)
+                >= i64::try_from(best).unwrap_or(i64::MAX) * self.scale
+        }) {
+            return;
+        }
+        if ix == self.bounds.len() {
+            if let Some(pivots) = self.pivot_presses(free_presses) {
+                let total = u64::try_from(pressed + pivots).expect("Presses are positive");
+                *best = Some(best.map_or(total, |best| best.min(total)));
+            }
+            return;
+        }
+        for presses in self.press_range(free_presses, ix) {
+            free_presses[ix] = presses;
+            self.search(free_presses, ix + 1, pressed + presses, best);
+        }
+        free_presses[ix] = 0;
+    }
+
+    /// The fewest total presses times `scale`, with the free buttons before `ix` fixed and the
+    /// rest anywhere within their bounds.
+    fn lower_bound(&self, free_presses: &[i64], ix: usize) -> i64 {
+        let (constant, weights) = &self.objective;
+        let fixed = weights[..ix]
+            .iter()
+            .zip(free_presses)
+            .map(|(w, x)| w * x)
+            .sum::<i64>();
+        let rest = weights[ix..]
+            .iter()
+            .zip(&self.bounds[ix..])
+            .map(|(&w, &bound)| w.min(0) * bound)
+            .sum::<i64>();
+        constant + fixed + rest
+    }
+
+    /// The presses of free button `ix` that leave every pivot button a non-negative number of
+    /// presses, with the free buttons before it fixed and the rest anywhere within their bounds.
+    fn press_range(&self, free_presses: &[i64], ix: usize) -> RangeInclusive<i64> {
+        let (mut low, mut high) = (0, self.bounds[ix]);
+        for (_, coefficients, rhs) in &self.rows {
+            let fixed = coefficients[..ix]
+                .iter()
+                .zip(free_presses)
+                .map(|(c, x)| c * x)
+                .sum::<i64>();
+            let rest = coefficients[ix + 1..]
+                .iter()
+                .zip(&self.bounds[ix + 1..])
+                .map(|(&c, &bound)| c.min(0) * bound)
+                .sum::<i64>();
+            let slack = rhs - fixed - rest;
+            match coefficients[ix].cmp(&0) {
+                Ordering::Greater => high = high.min(slack.div_euclid(coefficients[ix])),
+                Ordering::Less => low = low.max(-slack.div_euclid(-coefficients[ix])),
+                Ordering::Equal if slack < 0 => high = -1,
+                Ordering::Equal => {}
+            }
+        }
+        low..=high
+    }
+}
+
+/// The sum of `value * (scale / pivot)` over the `(value, pivot)` terms, or `None` if it
+/// overflows.
+fn scaled_sum(mut terms: impl Iterator<Item = (i64, i64)>, scale: i64) -> Option<i64> {
+    terms.try_fold(0_i64, |acc, (value, pivot)| {
+        acc.checked_add(value.checked_mul(scale / pivot)?)
+    })
+}
+
+/// Clears column `col` of `row` with a multiple of `pivot_row`, keeping the values integers.
+fn eliminate(row: &mut [i64], pivot_row: &[i64], col: usize) -> Result<(), SolveError> {
+    let (pivot, factor) = (pivot_row[col], row[col]);
+    for (value, &p) in row.iter_mut().zip(pivot_row) {
+        *value = value
+            .checked_mul(pivot)
+            .zip(p.checked_mul(factor))
+            .and_then(|(a, b)| a.checked_sub(b))
+            .ok_or(SolveError::Overflow)?;
+    }
+    reduce(row);
+    Ok(())
+}
+
+/// Divides `row` by the greatest common divisor of its values.
+fn reduce(row: &mut [i64]) {
+    let divisor = row.iter().fold(0, |acc, &value| gcd(acc, value.abs()));
+    if divisor > 1 {
+        for value in row {
+            *value /= divisor;
+        }
+    }
+}
+
+const fn gcd(mut a: i64, mut b: i64) -> i64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
     }
-    sum
+    a
+}
+
+fn minimum_presses(buttons: &[u64], target: &[u16]) -> Result<u64, SolveError> {
+    let target_max = target.iter().copied().max().unwrap_or(0);
+    let bounds = vec![(0.0, f64::from(target_max)); buttons.len()];
+    let mut best = None;
+    branch_presses(buttons, target, bounds, &mut best)?;
+    debug!(
+        buttons = buttons.len(),
+        counters = target.len(),
+        presses = best,
+        "LP solved"
+    );
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "Value should be less than sum(target). Any problem with a solution > u64::MAX not present."
+    )]
+    #[expect(
+        clippy::cast_sign_loss,
+        reason = "All cofficients and variables are positive, so minimal solution should also be positive."
+    )]
+    best.map(|best| best as u64)
+        .ok_or(SolveError::JoltageUnreachable)
 }
 
-fn minimum_presses(buttons: &[u16], target: &[u16]) -> u64 {
+/// How far from a whole number an LP value may be and still count as one.
+const EPSILON: f64 = 1e-6;
+
+/// Branch and bound over the LP relaxation of [`minimum_presses`] within `bounds`: splits on
+/// the first button pressed a fractional number of times, keeping the fewest total presses in
+/// `best`.
+fn branch_presses(
+    buttons: &[u64],
+    target: &[u16],
+    bounds: Vec<(f64, f64)>,
+    best: &mut Option<f64>,
+) -> Result<(), SolveError> {
+    let Some((objective, presses)) = relaxed_presses(buttons, target, &bounds)? else {
+        return Ok(());
+    };
+    // Whole presses in this branch add up to at least the rounded up objective.
+    if best.is_some_and(|best| (objective - EPSILON).ceil() >= best) {
+        return Ok(());
+    }
+    let Some(ix) = presses.iter().position(|p| (p - p.round()).abs() > EPSILON) else {
+        let total = presses.iter().map(|p| p.round()).sum::<f64>();
+        *best = Some(best.map_or(total, |best| best.min(total)));
+        return Ok(());
+    };
+    debug!(button = ix, presses = presses[ix], "fractional LP solution");
+    let mut below = bounds.clone();
+    below[ix].1 = presses[ix].floor();
+    branch_presses(buttons, target, below, best)?;
+    let mut above = bounds;
+    above[ix].0 = presses[ix].ceil();
+    branch_presses(buttons, target, above, best)
+}
+
+/// The fewest total presses and the presses of each button, allowing fractional presses
+/// within `bounds`, or `None` if no presses reach `target`.
+fn relaxed_presses(
+    buttons: &[u64],
+    target: &[u16],
+    bounds: &[(f64, f64)],
+) -> Result<Option<(f64, Vec<f64>)>, SolveError> {
     let mut problem = Problem::new(OptimizationDirection::Minimize);
-    let target_max = target.iter().copied().max().unwrap();
-    let button_vars = buttons
+    let button_vars = bounds
         .iter()
-        .map(|_| problem.add_integer_var(1.0, (0, i32::from(target_max))))
+        .map(|&bound| problem.add_var(1.0, bound))
         .collect::<Vec<_>>();
     for (light_ix, &trg) in target.iter().enumerate() {
         let mut expr = LinearExpr::empty();
@@ -112,17 +566,40 @@ fn minimum_presses(buttons: &[u16], target: &[u16]) -> u64 {
         }
         problem.add_constraint(expr, microlp::ComparisonOp::Eq, f64::from(trg));
     }
-    let solution = problem.solve().expect("Any solution");
-    #[expect(
-        clippy::cast_possible_truncation,
-        reason = "Value should be less than sum(target). Any problem with a solution > u64::MAX not present."
-    )]
-    #[expect(
-        clippy::cast_sign_loss,
-        reason = "All cofficients and variables are positive, so minimal solution should also be positive."
-    )]
-    {
-        solution.objective().round() as u64
+    match problem.solve() {
+        Ok(solution) => Ok(Some((
+            solution.objective(),
+            button_vars.iter().map(|&var| solution[var]).collect(),
+        ))),
+        Err(microlp::Error::Infeasible) => Ok(None),
+        Err(err) => {
+            warn!(%err, ?target, "LP failed");
+            Err(SolveError::Lp(err.to_string()))
+        }
+    }
+}
+
+pub struct Day10;
+
+impl DaySolution for Day10 {
+    const DAY: u8 = 10;
+
+    type Parsed = Vec<Machine>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        parse(input).map_err(AocError::invalid_input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        part_1(parsed)
+            .map(Answer::from)
+            .map_err(|err| AocError::SolverFailed(err.to_string()))
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        part_2(parsed)
+            .map(Answer::from)
+            .map_err(|err| AocError::SolverFailed(err.to_string()))
     }
 }
 
@@ -162,17 +639,139 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_error() {
+        let input = "[.##.] (3) (1,3) {3,5}\n[.#] (0) (1 {1,1}";
+        let err = parse(input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Syntax error: Expected ) at line 2, col 12"
+        );
+    }
+
+    #[test]
+    fn test_parse_wide() {
+        let lights = format!("[{}#]", ".".repeat(19));
+        let joltage = format!("{{1,{}1}}", "0,".repeat(18));
+        let machines = parse(&format!("{lights} (0,19) (0) {joltage}")).unwrap();
+        assert_eq!(machines[0].indicator_lights, 1 << 19);
+        assert_eq!(machines[0].buttons, [1 << 19 | 1, 1]);
+        assert_eq!(part_1(&machines), Ok(2));
+        assert_eq!(part_2(&machines), Ok(1));
+
+        let err = parse("[.#] (0,64) {1,1}").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Button toggles light 64, at most 64 supported at line 1"
+        );
+        let err = parse(&format!("[{}] (0) {{1}}", ".".repeat(65))).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Machine has 65 lights, at most 64 supported at line 1"
+        );
+        let err = parse(&format!("[#] {}{{1}}", "(0) ".repeat(65))).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Machine has 65 buttons, at most 64 supported at line 1"
+        );
+    }
+
+    #[test]
+    fn test_many_buttons() {
+        // A button per light for 40 lights, and 10 more each toggling one of them.
+        let buttons = (0..50_u32).map(|ix| 1 << (ix % 40)).collect::<Vec<u64>>();
+        let lights = (1 << 40) - 1;
+        assert_eq!(activation_buttons(lights, &buttons), Some(40));
+        assert_eq!(activation_buttons(lights ^ 1, &buttons), Some(39));
+        assert_eq!(subsets(0), 0..=0);
+        assert_eq!(subsets(3), 0..=0b111);
+        assert_eq!(subsets(64), 0..=u64::MAX);
+    }
+
     #[test]
     fn test_part_1() {
         let machines = parse(EXAMPLE).unwrap();
         let result = part_1(&machines);
-        assert_eq!(result, 7);
+        assert_eq!(result, Ok(7));
+    }
+
+    #[test]
+    fn test_part_1_brute_force() {
+        let machines = parse(EXAMPLE).unwrap();
+        let result = part_1_brute_force(&machines);
+        assert_eq!(result, Ok(7));
     }
 
     #[test]
     fn test_part_2() {
         let machines = parse(EXAMPLE).unwrap();
         let result = part_2(&machines);
-        assert_eq!(result, 33);
+        assert_eq!(result, Ok(33));
+    }
+
+    #[test]
+    fn test_part_2_lp() {
+        let machines = parse(EXAMPLE).unwrap();
+        let result = part_2_lp(&machines);
+        assert_eq!(result, Ok(33));
+    }
+
+    #[test]
+    fn test_unsolvable_machine() {
+        // Machine 1 can't raise counter 1 past counter 0, machine 2 can't toggle light 1.
+        let machines = parse("[##] (0,1) {1,1}\n[.#] (0) (0,1) {1,2}\n[.#] (0) {0,0}").unwrap();
+        let err = part_1(&machines).unwrap_err();
+        assert_eq!(err.machine, 2);
+        assert_eq!(err.source, SolveError::LightsUnreachable);
+        assert_eq!(part_1_brute_force(&machines), Err(err));
+        let err = part_2(&machines).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Machine 1: No presses of the buttons reach the joltage levels"
+        );
+        assert_eq!(part_2_lp(&machines), Err(err));
+    }
+
+    #[test]
+    fn test_minimum_presses_fractional() {
+        // Pressing each pair half a time is the LP optimum, but not a whole number of presses.
+        let pairs = [0b011, 0b110, 0b101];
+        assert_eq!(
+            minimum_presses(&pairs, &[1, 1, 1]),
+            Err(SolveError::JoltageUnreachable)
+        );
+        assert_eq!(
+            minimum_presses(&[0b011, 0b110, 0b101, 0b001], &[1, 1, 1]),
+            Ok(2)
+        );
+        assert_eq!(minimum_presses(&pairs, &[2, 2, 2]), Ok(3));
+    }
+
+    #[test]
+    fn test_eliminate() {
+        let mut row = [2, 4, 6];
+        eliminate(&mut row, &[1, 1, 1], 0).unwrap();
+        assert_eq!(row, [0, 1, 2]);
+        let mut row = [i64::MAX / 2, 3, 1];
+        assert_eq!(
+            eliminate(&mut row, &[3, 1, 1], 0),
+            Err(SolveError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_minimum_presses_exact() {
+        // Pressing the button shared by both counters is cheapest.
+        assert_eq!(minimum_presses_exact(&[0b11, 0b01, 0b10], &[4, 6]), Ok(6));
+        assert_eq!(minimum_presses_exact(&[0b011, 0b110], &[1, 2, 1]), Ok(2));
+        assert_eq!(
+            minimum_presses_exact(&[0b011, 0b110], &[1, 1, 1]),
+            Err(SolveError::JoltageUnreachable)
+        );
+        assert_eq!(
+            minimum_presses_exact(&[0b01], &[1, 1]),
+            Err(SolveError::JoltageUnreachable)
+        );
+        assert_eq!(minimum_presses_exact(&[0b01, 0b10], &[0, 0]), Ok(0));
     }
 }
@@ -1,195 +1,240 @@
-use std::collections::HashMap;
-use std::str::FromStr;
+use std::sync::Arc;
 
 use thiserror::Error;
+use tracing::debug;
+
+use crate::shared::borrowed::{Borrowed, Yokeable, parse_borrowed};
+use crate::shared::graph::{Condensation, Graph as Adjacency, GraphBuilder, Inline, Interner};
+use crate::shared::input::{InputError, Locate, ParseInput};
+use crate::{Answer, AocError, DaySolution};
 
 #[derive(Debug, Error)]
-enum ParseError {
+pub enum ParseError {
     #[error("Syntax error")]
     SyntaxError,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-enum NodeId {
-    Svr,
-    You,
-    Dac,
-    Fft,
-    Out,
-    Other(usize),
-}
-
-impl NodeId {
-    const fn index(self) -> usize {
-        match self {
-            Self::Svr => 0,
-            Self::You => 1,
-            Self::Dac => 2,
-            Self::Fft => 3,
-            Self::Out => 4,
-            Self::Other(ix) => ix,
-        }
-    }
-}
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct Node {
-    id: NodeId,
-    neighbors: Vec<NodeId>,
-}
-
-impl Node {
-    const fn new(id: NodeId) -> Self {
-        Self {
-            id,
-            neighbors: Vec::new(),
-        }
-    }
-
-    fn neighbors(&self) -> impl Iterator<Item = NodeId> {
-        self.neighbors.iter().copied()
+impl Locate for ParseError {
+    fn offset(&self, _text: &str) -> Option<usize> {
+        None
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct Graph {
-    names: Vec<String>,
-    nodes: Vec<Node>,
+#[derive(Debug, Clone, PartialEq, Eq, Yokeable)]
+pub struct Graph<'a> {
+    devices: Interner<'a>,
+    adjacency: Adjacency,
+    /// The wiring with every cycle contracted, found while parsing.
+    condensed: Condensation,
 }
 
-impl Graph {
-    const fn len(&self) -> usize {
-        self.nodes.len()
+impl Graph<'_> {
+    fn len(&self) -> usize {
+        self.adjacency.len()
     }
 
-    fn node(&self, id: NodeId) -> &Node {
-        &self.nodes[id.index()]
+    /// The id of the device called `name`.
+    fn node(&self, name: &str) -> Result<usize, CountError> {
+        self.devices
+            .get(name)
+            .ok_or_else(|| CountError::UnknownNode(name.to_string()))
     }
 }
 
-impl FromStr for Graph {
+impl<'a> ParseInput<'a> for Graph<'a> {
     type Err = ParseError;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut lookup = HashMap::new();
-        let mut names = Vec::new();
-        let mut nodes = Vec::new();
-        for (name, id) in [
-            ("svr", NodeId::Svr),
-            ("you", NodeId::You),
-            ("dac", NodeId::Dac),
-            ("fft", NodeId::Fft),
-            ("out", NodeId::Out),
-        ] {
-            names.push(name.to_string());
-            lookup.insert(name, id);
-            nodes.push(Node::new(id));
-        }
-        for line in s.lines() {
-            let (source, dests) = line.split_once(": ").ok_or(ParseError::SyntaxError)?;
-            let next_id = lookup.len();
-            let source = *lookup.entry(source).or_insert_with(|| {
-                let id = NodeId::Other(next_id);
-                nodes.push(Node::new(id));
-                names.push(source.to_string());
-                id
-            });
+    fn parse_input(s: &'a str) -> Result<Self, InputError<Self::Err>> {
+        let mut interner = Interner::new();
+        let mut builder = GraphBuilder::new();
+        for (row, line) in s.lines().enumerate() {
+            let (source, dests) = line
+                .split_once(": ")
+                .ok_or_else(|| InputError::in_line(row, line, ParseError::SyntaxError))?;
+            let source = interner.intern(source);
             for dest in dests.split(' ') {
-                let next_id = lookup.len();
-                let dest = *lookup.entry(dest).or_insert_with(|| {
-                    let id = NodeId::Other(next_id);
-                    nodes.push(Node::new(id));
-                    names.push(dest.to_string());
-                    id
-                });
-                nodes[source.index()].neighbors.push(dest);
+                builder.add_edge(source, interner.intern(dest));
             }
         }
-        Ok(Self { names, nodes })
+        builder.reserve_nodes(interner.len());
+        let adjacency = builder.build::<Inline>();
+        let condensed = adjacency.condense();
+        if let Some(cycle) = condensed.cyclic.iter().position(|&cyclic| cyclic) {
+            debug!(
+                node = interner.name(condensed.components[cycle][0]),
+                "Wiring has a cycle"
+            );
+        }
+        Ok(Self {
+            devices: interner,
+            adjacency,
+            condensed,
+        })
     }
 }
 
 #[aoc_generator(day11)]
-fn parse(input: &str) -> Result<Graph, ParseError> {
-    input.parse()
+fn parse(input: &Arc<str>) -> Result<Borrowed<Graph<'static>>, InputError<ParseError>> {
+    parse_borrowed(input)
+}
+
+/// Waypoints a path count can track at once.
+const MAX_WAYPOINTS: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CountError {
+    #[error("No device named {0:?}")]
+    UnknownNode(String),
+    #[error("Wiring loops through {0} on the way to the end")]
+    Cycle(String),
+    #[error("{0} waypoints, at most {MAX_WAYPOINTS} supported")]
+    TooManyWaypoints(usize),
+    #[error("More than {} paths", u64::MAX)]
+    Overflow,
 }
 
 #[aoc(day11, part1)]
-fn part_1(graph: &Graph) -> u64 {
-    fn dfs(graph: &Graph, visited: &mut [bool], id: NodeId) -> u64 {
-        if id == NodeId::Out {
-            return 1;
+fn part_1(graph: &Borrowed<Graph<'static>>) -> Result<u64, CountError> {
+    let graph = graph.get();
+    match Day11::count_paths_through(graph, "you", "out", &[]) {
+        Err(CountError::Cycle(node)) => {
+            debug!(node, "Wiring loops, counting simple paths");
+            Ok(count_simple_paths(
+                graph,
+                graph.node("you")?,
+                graph.node("out")?,
+            ))
         }
-        let mut count = 0;
-        for next in graph.node(id).neighbors() {
-            if visited[next.index()] {
-                continue;
-            }
-            visited[next.index()] = true;
-            count += dfs(graph, visited, next);
-            visited[next.index()] = false;
-        }
-        count
+        count => count,
     }
-    dfs(graph, &mut vec![false; graph.len()], NodeId::You)
 }
 
-#[aoc(day11, part2)]
-fn part_2(graph: &Graph) -> u64 {
-    // TODO:
-    // Try topological sort
-    // Count with dynamic programming
-    // DONE:
-    // Possible exploit: treat high degree nodes as gates
-    let mut in_count = vec![0; graph.len()];
-    for node in &graph.nodes {
-        for &next in &node.neighbors {
-            in_count[next.index()] += 1;
-        }
+/// Counts the paths from `from` to `to` that pass every one of `waypoints`, one component of
+/// the condensed wiring at a time, or fails if one of the paths could go around a cycle.
+fn count_paths(
+    graph: &Graph,
+    from: usize,
+    to: usize,
+    waypoints: &[usize],
+) -> Result<u64, CountError> {
+    if waypoints.len() > MAX_WAYPOINTS {
+        return Err(CountError::TooManyWaypoints(waypoints.len()));
+    }
+    let condensed = &graph.condensed;
+    let len = condensed.components.len();
+    let mut waypoint_bits = vec![0; len];
+    for (bit, &waypoint) in waypoints.iter().enumerate() {
+        waypoint_bits[condensed.component_of[waypoint]] |= 1 << bit;
     }
-    let mut targets = Vec::new();
-    for node in &graph.nodes {
-        if matches!(
-            node.id,
-            NodeId::Svr | NodeId::Fft | NodeId::Dac | NodeId::Out
-        ) || node.neighbors.len() > 5
-            || in_count[node.id.index()] > 5
+    // Paths to each component, by which waypoints they have passed.
+    let states = 1 << waypoints.len();
+    let mut paths = vec![0_u64; len * states];
+    // A cycle that some path to each component could go around.
+    let mut cycles = vec![None; len];
+    let (start, end) = (condensed.component_of[from], condensed.component_of[to]);
+    paths[start * states + waypoint_bits[start]] = 1;
+    for component in start..=end {
+        if paths[component * states..][..states]
+            .iter()
+            .all(|&count| count == 0)
         {
-            targets.push(node.id);
+            continue;
+        }
+        if condensed.cyclic[component] {
+            cycles[component] = Some(component);
+        }
+        for &next in condensed.dag.neighbors(component) {
+            cycles[next] = cycles[next].or(cycles[component]);
+            for visited in 0..states {
+                let count = paths[component * states + visited];
+                let total = &mut paths[next * states + (visited | waypoint_bits[next])];
+                *total = total.checked_add(count).ok_or(CountError::Overflow)?;
+            }
         }
     }
-    let mut paths = HashMap::<NodeId, HashMap<NodeId, u64>>::new();
-    let mut pending = Vec::new();
-    for &trg in &targets {
-        pending.push((trg, trg));
+    if let Some(cycle) = cycles[end] {
+        let node = condensed.components[cycle][0];
+        return Err(CountError::Cycle(graph.devices.name(node).to_string()));
     }
-    while let Some((origin, cur)) = pending.pop() {
-        if cur != origin && targets.contains(&cur) {
-            *paths.entry(origin).or_default().entry(cur).or_default() += 1;
-            continue;
-        }
-        for next in graph.node(cur).neighbors() {
-            pending.push((origin, next));
+    Ok(paths[end * states + states - 1])
+}
+
+/// Counts the paths from `from` to `to` that visit no node twice, trying every one of them.
+fn count_simple_paths(graph: &Graph, from: usize, to: usize) -> u64 {
+    if from == to {
+        return 1;
+    }
+    let mut on_path = vec![false; graph.len()];
+    on_path[from] = true;
+    let mut stack = vec![(from, 0)];
+    let mut count = 0;
+    while let Some((node, tried)) = stack.last_mut() {
+        let node = *node;
+        if let Some(&next) = graph.adjacency.neighbors(node).get(*tried) {
+            *tried += 1;
+            if next == to {
+                count += 1;
+            } else if !on_path[next] {
+                on_path[next] = true;
+                stack.push((next, 0));
+            }
+        } else {
+            on_path[node] = false;
+            stack.pop();
         }
     }
-    dfs(&paths, false, false, NodeId::Svr)
+    count
 }
 
-fn dfs(paths: &HashMap<NodeId, HashMap<NodeId, u64>>, fft: bool, dac: bool, id: NodeId) -> u64 {
-    if id == NodeId::Out {
-        return u64::from(fft && dac);
-    }
-    let mut total = 0;
-    for (&next, &count) in &paths[&id] {
-        total += count
-            * dfs(
-                paths,
-                fft || id == NodeId::Fft,
-                dac || id == NodeId::Dac,
-                next,
-            );
+#[aoc(day11, part2)]
+fn part_2(graph: &Borrowed<Graph<'static>>) -> Result<u64, CountError> {
+    Day11::count_paths_through(graph.get(), "svr", "out", &["fft", "dac"])
+}
+
+pub struct Day11;
+
+impl Day11 {
+    /// Counts the paths through the wiring from `from` to `to` that pass every device in
+    /// `required`, in any order; part 2 requires `fft` and `dac` between `svr` and `out`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a device isn't in the wiring, if some path could go around a cycle, if more
+    /// than 16 devices are required, or if the count does not fit in a `u64`.
+    pub fn count_paths_through(
+        graph: &Graph,
+        from: &str,
+        to: &str,
+        required: &[&str],
+    ) -> Result<u64, CountError> {
+        let waypoints = required
+            .iter()
+            .map(|name| graph.node(name))
+            .collect::<Result<Vec<_>, _>>()?;
+        count_paths(graph, graph.node(from)?, graph.node(to)?, &waypoints)
+    }
+}
+
+impl DaySolution for Day11 {
+    const DAY: u8 = 11;
+
+    type Parsed = Borrowed<Graph<'static>>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        parse(&Arc::from(input)).map_err(AocError::invalid_input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        part_1(parsed)
+            .map(Answer::from)
+            .map_err(|err| AocError::SolverFailed(err.to_string()))
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        part_2(parsed)
+            .map(Answer::from)
+            .map_err(|err| AocError::SolverFailed(err.to_string()))
     }
-    total
 }
 
 #[cfg(test)]
@@ -211,9 +256,62 @@ mod tests {
 
     #[test]
     fn test_part_1() {
-        let graph = parse(EXAMPLE1).unwrap();
+        let graph = parse(&EXAMPLE1.into()).unwrap();
         let result = part_1(&graph);
-        assert_eq!(result, 5);
+        assert_eq!(result, Ok(5));
+    }
+
+    #[test]
+    fn test_part_1_missing_start() {
+        let graph = parse(&"aaa: bbb ccc\nbbb: out\nccc: out".into()).unwrap();
+        assert_eq!(
+            part_1(&graph),
+            Err(CountError::UnknownNode("you".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_part_1_deep() {
+        use std::fmt::Write;
+
+        // 50 diamonds, then a chain far deeper than the call stack would allow.
+        let mut input = String::from("you: a0\n");
+        for ix in 0..50 {
+            let next = ix + 1;
+            writeln!(input, "a{ix}: b{ix} c{ix}\nb{ix}: a{next}\nc{ix}: a{next}").unwrap();
+        }
+        for ix in 50..200_000 {
+            let next = ix + 1;
+            writeln!(input, "a{ix}: a{next}").unwrap();
+        }
+        input += "a200000: out";
+        let graph = parse(&input.into()).unwrap();
+        assert_eq!(part_1(&graph), Ok(1 << 50));
+    }
+
+    #[test]
+    fn test_part_1_overflow() {
+        use std::fmt::Write;
+
+        // 64 diamonds in a row double the paths 64 times.
+        let mut input = String::from("you: a0\n");
+        for ix in 0..64 {
+            let next = ix + 1;
+            writeln!(input, "a{ix}: b{ix} c{ix}\nb{ix}: a{next}\nc{ix}: a{next}").unwrap();
+        }
+        input += "a64: out";
+        let graph = parse(&input.into()).unwrap();
+        assert_eq!(part_1(&graph), Err(CountError::Overflow));
+    }
+
+    #[test]
+    fn test_part_1_cycle() {
+        let graph = parse(&"you: aaa\naaa: bbb out\nbbb: aaa ccc\nccc: out".into()).unwrap();
+        assert_eq!(
+            Day11::count_paths_through(graph.get(), "you", "out", &[]),
+            Err(CountError::Cycle("aaa".to_string()))
+        );
+        assert_eq!(part_1(&graph), Ok(2));
     }
 
     const EXAMPLE2: &str = "\
@@ -234,8 +332,42 @@ mod tests {
 
     #[test]
     fn test_part_2() {
-        let graph = parse(EXAMPLE2).unwrap();
+        let graph = parse(&EXAMPLE2.into()).unwrap();
         let result = part_2(&graph);
-        assert_eq!(result, 2);
+        assert_eq!(result, Ok(2));
+    }
+
+    #[test]
+    fn test_count_paths_through() {
+        let graph = parse(&EXAMPLE2.into()).unwrap();
+        let graph = graph.get();
+        let count = |from, to, required| Day11::count_paths_through(graph, from, to, required);
+        assert_eq!(count("svr", "out", &[]), Ok(8));
+        assert_eq!(count("svr", "out", &["fft"]), Ok(4));
+        assert_eq!(count("svr", "out", &["hub", "fff", "tty"]), Ok(2));
+        assert_eq!(count("ccc", "fff", &["eee", "ddd"]), Ok(0));
+        assert_eq!(count("aaa", "ggg", &["ccc"]), Ok(2));
+        assert_eq!(
+            count("svr", "out", &["zzz"]),
+            Err(CountError::UnknownNode("zzz".to_string()))
+        );
+        assert_eq!(
+            count("svr", "out", &["fft"; 17]),
+            Err(CountError::TooManyWaypoints(17))
+        );
+    }
+
+    #[test]
+    fn test_part_2_cycle() {
+        // The loop between aaa and bbb is off every path from svr, the one through ccc isn't.
+        let input = "svr: fft\nfft: dac aaa\ndac: out\naaa: bbb\nbbb: aaa";
+        let graph = parse(&input.into()).unwrap();
+        assert_eq!(part_2(&graph), Ok(1));
+        let graph = parse(&format!("{input}\ndac: ccc\nccc: dac").into()).unwrap();
+        let err = part_2(&graph).unwrap_err();
+        assert!(
+            matches!(&err, CountError::Cycle(node) if node == "dac" || node == "ccc"),
+            "{err}"
+        );
     }
 }
@@ -1,45 +1,134 @@
+use std::convert::Infallible;
 use std::num::ParseIntError;
-use std::str::FromStr;
+use std::ops::Range;
+use std::time::{Duration, Instant};
 
+use smallvec::SmallVec;
 use thiserror::Error;
+use tracing::debug;
+
+use crate::shared::input::{InputError, Locate, ParseInput, ParseLine, lines_before, offset_in};
+use crate::shared::parse::{ParseArrayError, input_blocks, normalize_input_trimmed, parse_array};
+use crate::shared::shape::{MAX_SIZE, Shape};
+use crate::shared::{FastSet, par};
+use crate::{Answer, AocError, DaySolution};
+
+/// Widest region the masks of a [`Packing`] can hold.
+const MAX_WIDTH: u8 = 64;
+
+/// Longest the packing search may spend on a single region.
+const REGION_BUDGET: Duration = Duration::from_secs(5);
 
 #[derive(Debug, Error)]
-enum ParseError {
+pub enum ParseError {
     #[error("Syntax error")]
     SyntaxError,
     #[error(transparent)]
-    InvalidNumber(#[from] ParseIntError),
+    InvalidNumbers(#[from] ParseArrayError<ParseIntError>),
+    #[error("Tile rows must be equally long, of '#' and '.'")]
+    InvalidTile,
+    #[error("Tile has no cells")]
+    EmptyTile,
+    #[error("Tile is {0} wide or high, at most {MAX_SIZE} supported")]
+    TileTooLarge(usize),
+    #[error("Region is {0} wide, at most {MAX_WIDTH} supported")]
+    RegionTooWide(u8),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl Locate for ParseError {
+    fn offset(&self, _text: &str) -> Option<usize> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Region {region}: packing not decided within {budget:?}")]
+pub struct RegionTimedOut {
+    /// Index of the region, in input order.
+    pub region: usize,
+    pub budget: Duration,
+}
+
+/// A present as drawn in the input.
+type Tile = Shape;
+
+/// How many more cells `tile` covers of one color of a checkerboard than of the other.
+/// Moving, turning or flipping it can only swap the colors.
+fn color_imbalance(tile: &Tile) -> u32 {
+    const EVEN: u64 = 0x5555_5555_5555_5555;
+    let even = tile
+        .rows()
+        .iter()
+        .enumerate()
+        .map(|(y, row)| (row & EVEN << (y & 1)).count_ones())
+        .sum::<u32>();
+    even.abs_diff(tile.area() - even)
+}
+
+fn parse_tile(s: &str) -> Result<Tile, InputError<ParseError>> {
+    // 0:
+    // ###
+    // ##.
+    // ##.
+    let mut lines = s.lines();
+    let header = lines.next().unwrap_or_default();
+    let index = header.strip_suffix(':').map(str::parse::<u8>);
+    if !matches!(index, Some(Ok(_))) {
+        return Err(InputError::in_line(0, header, ParseError::SyntaxError));
+    }
+    let mut width = 0;
+    let mut rows = Vec::new();
+    for (y, line) in lines.enumerate() {
+        let invalid = |error| InputError::in_line(y + 1, line, error);
+        if y == 0 {
+            width = line.len();
+        }
+        if line.is_empty() || line.len() != width {
+            return Err(invalid(ParseError::InvalidTile));
+        }
+        if width > usize::from(MAX_SIZE) || y >= usize::from(MAX_SIZE) {
+            return Err(invalid(ParseError::TileTooLarge(width.max(y + 1))));
+        }
+        let mut row = 0;
+        for (x, ch) in line.bytes().enumerate() {
+            match ch {
+                b'#' => row |= 1 << x,
+                b'.' => {}
+                _ => return Err(invalid(ParseError::InvalidTile)),
+            }
+        }
+        rows.push(row);
+    }
+    if rows.iter().all(|&row| row == 0) {
+        return Err(InputError::in_line(0, header, ParseError::EmptyTile));
+    }
+    #[expect(clippy::cast_possible_truncation, reason = "Checked against MAX_SIZE")]
+    Ok(Shape::new(width as u8, rows))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct Region {
     width: u8,
     height: u8,
-    quantities: [u8; 6],
+    /// Presents of each tile, in the order of the tiles.
+    quantities: Vec<u8>,
 }
 
-impl FromStr for Region {
+impl ParseLine<'_> for Region {
     type Err = ParseError;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn parse_line(s: &str) -> Result<Self, Self::Err> {
         // 12x5: 1 0 1 0 2 2
-        let mut words = s.split(['x', ':', ' ']);
-        let width = words.next().ok_or(ParseError::SyntaxError)?.parse()?;
-        let height = words.next().ok_or(ParseError::SyntaxError)?.parse()?;
-        if words.next() != Some("") {
-            return Err(ParseError::SyntaxError);
-        }
-        let quantities = [
-            words.next().ok_or(ParseError::SyntaxError)?.parse()?,
-            words.next().ok_or(ParseError::SyntaxError)?.parse()?,
-            words.next().ok_or(ParseError::SyntaxError)?.parse()?,
-            words.next().ok_or(ParseError::SyntaxError)?.parse()?,
-            words.next().ok_or(ParseError::SyntaxError)?.parse()?,
-            words.next().ok_or(ParseError::SyntaxError)?.parse()?,
-        ];
-        if words.next().is_some() {
-            return Err(ParseError::SyntaxError);
+        let (size, quantities) = s.split_once(": ").ok_or(ParseError::SyntaxError)?;
+        let [width, height] = parse_array(size.split('x'))?;
+        if width > MAX_WIDTH {
+            return Err(ParseError::RegionTooWide(width));
         }
+        let quantities = quantities
+            .split(' ')
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .map_err(ParseArrayError::InvalidItem)?;
         Ok(Self {
             width,
             height,
@@ -49,44 +138,525 @@ impl FromStr for Region {
 }
 
 #[derive(Debug, Clone)]
-struct Input {
+pub struct Input {
+    tiles: Vec<Tile>,
     regions: Vec<Region>,
 }
 
-impl FromStr for Input {
+impl ParseInput<'_> for Input {
     type Err = ParseError;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split("\n\n");
-        let regions = parts
-            .nth(6) // Skip tiles
-            .ok_or(ParseError::SyntaxError)?
-            .lines()
-            .map(str::parse)
-            .collect::<Result<_, _>>()?;
-        if parts.next().is_some() {
-            return Err(ParseError::SyntaxError);
+    fn parse_input(s: &str) -> Result<Self, InputError<Self::Err>> {
+        let mut parts = input_blocks(s).peekable();
+        // Tiles come first, each headed by its index; the regions follow.
+        let mut tiles = Vec::new();
+        while let Some(block) =
+            parts.next_if(|block| block.lines().next().is_some_and(|line| line.ends_with(':')))
+        {
+            let tile = parse_tile(block).map_err(|err| err.below(lines_before(s, block)))?;
+            tiles.push(tile);
+        }
+        let block = parts
+            .next()
+            .ok_or_else(|| InputError::at_offset(s, s.len(), ParseError::SyntaxError))?;
+        let regions =
+            Vec::<Region>::parse_input(block).map_err(|err| err.below(lines_before(s, block)))?;
+        for (row, (region, line)) in regions.iter().zip(block.lines()).enumerate() {
+            if region.quantities.len() != tiles.len() {
+                let error = ParseArrayError::WrongLength {
+                    expected: tiles.len(),
+                    found: region.quantities.len(),
+                };
+                return Err(
+                    InputError::in_line(row, line, error.into()).below(lines_before(s, block))
+                );
+            }
+        }
+        if let Some(extra) = parts.next() {
+            return Err(InputError::at_offset(
+                s,
+                offset_in(s, extra),
+                ParseError::SyntaxError,
+            ));
         }
-        Ok(Self { regions })
+        Ok(Self { tiles, regions })
     }
 }
 
 #[aoc_generator(day12)]
-fn parse(input: &str) -> Result<Input, ParseError> {
-    input.parse()
+fn parse(input: &str) -> Result<Input, InputError<ParseError>> {
+    let input = normalize_input_trimmed(input);
+    Input::parse_input(&input)
 }
 
 #[aoc(day12, part1)]
-fn part_1(input: &Input) -> usize {
-    input
-        .regions
+fn part_1(input: &Input) -> Result<usize, RegionTimedOut> {
+    count_packable(input, REGION_BUDGET)
+}
+
+/// How many regions fit all their presents, giving up if one takes longer than `budget`.
+fn count_packable(input: &Input, budget: Duration) -> Result<usize, RegionTimedOut> {
+    let orientations = input
+        .tiles
+        .iter()
+        .map(Shape::orientations)
+        .collect::<Vec<_>>();
+    count_fitting(input, budget, |region, slack, deadline| {
+        RowPacking::new(&orientations, region, slack, deadline).search(0)
+    })
+}
+
+#[aoc(day12, part1, Backtrack)]
+fn part_1_backtrack(input: &Input) -> usize {
+    let orientations = input
+        .tiles
         .iter()
-        .filter(|r| {
-            let total_area = u64::from(r.width) * u64::from(r.height);
-            let sum_shapes_area = r.quantities.iter().map(|&c| u64::from(c)).sum::<u64>() * 9;
-            total_area >= sum_shapes_area
+        .map(Shape::orientations)
+        .collect::<Vec<_>>();
+    // The plain search is the reference to compare against, so it runs without a budget.
+    par::sum(&input.regions, |region| {
+        let Ok(fits) = fits(&input.tiles, region, |slack| {
+            let mut packing = Packing {
+                width: usize::from(region.width),
+                rows: vec![0; usize::from(region.height)],
+                orientations: &orientations,
+                remaining: region.quantities.clone(),
+                slack,
+            };
+            Ok::<_, Infallible>(packing.search(0))
+        });
+        usize::from(fits)
+    })
+}
+
+/// How many regions fit all their presents, deciding each with `search`, or the first
+/// region it couldn't decide within `budget`.
+///
+/// Regions are independent, so with the `parallel` feature they are searched concurrently.
+fn count_fitting<F>(input: &Input, budget: Duration, search: F) -> Result<usize, RegionTimedOut>
+where
+    F: Fn(&Region, u32, Deadline) -> Result<bool, OutOfTime> + Sync + Send,
+{
+    par::map(&input.regions, |region| {
+        fits(&input.tiles, region, |slack| {
+            search(region, slack, Deadline::after(budget))
         })
-        .count()
+    })
+    .into_iter()
+    .enumerate()
+    .map(|(region, fits)| {
+        fits.map(usize::from)
+            .map_err(|OutOfTime| RegionTimedOut { region, budget })
+    })
+    .sum()
+}
+
+/// The search ran past its [`Deadline`].
+#[derive(Debug)]
+struct OutOfTime;
+
+/// When a search has to give up. The clock is only read every few thousand steps.
+struct Deadline {
+    at: Option<Instant>,
+    steps: u32,
+}
+
+impl Deadline {
+    fn after(budget: Duration) -> Self {
+        // There is no clock to read in a browser, so searches there run to the end.
+        let at = if cfg!(target_arch = "wasm32") {
+            None
+        } else {
+            Instant::now().checked_add(budget)
+        };
+        Self { at, steps: 0 }
+    }
+
+    /// Counts a step of the search, failing once the deadline has passed.
+    fn step(&mut self) -> Result<(), OutOfTime> {
+        let check = self.steps.is_multiple_of(4096);
+        self.steps = self.steps.wrapping_add(1);
+        match self.at {
+            Some(at) if check && Instant::now() >= at => Err(OutOfTime),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Whether every present of `region` fits in it at once, running `search` with the cells
+/// that may be left empty unless the prefilters or the presents' area alone decide it.
+fn fits<E>(
+    tiles: &[Tile],
+    region: &Region,
+    search: impl FnOnce(u32) -> Result<bool, E>,
+) -> Result<bool, E> {
+    if let Some(prefilter) = Prefilter::rejecting(tiles, region) {
+        debug!(
+            width = region.width,
+            height = region.height,
+            ?prefilter,
+            "presents can't fit"
+        );
+        return Ok(false);
+    }
+    let (width, height) = (u32::from(region.width), u32::from(region.height));
+    // Enough room to give every present a box as large as the largest tile of its own.
+    let presents = region
+        .quantities
+        .iter()
+        .map(|&count| u32::from(count))
+        .sum::<u32>();
+    let (box_width, box_height) =
+        presents_of(tiles, region).fold((1, 1), |(box_width, box_height), (tile, _)| {
+            (
+                box_width.max(u32::from(tile.width())),
+                box_height.max(u32::from(tile.height())),
+            )
+        });
+    if (width / box_width) * (height / box_height) >= presents {
+        return Ok(true);
+    }
+    search(width * height - needed_area(tiles, region))
+}
+
+/// The tiles `region` has presents of, with how many.
+fn presents_of<'a>(
+    tiles: &'a [Tile],
+    region: &'a Region,
+) -> impl Iterator<Item = (&'a Tile, u32)> + 'a {
+    tiles
+        .iter()
+        .zip(&region.quantities)
+        .filter(|&(_, &count)| count > 0)
+        .map(|(tile, &count)| (tile, u32::from(count)))
+}
+
+/// Cells covered by all the presents of `region`.
+fn needed_area(tiles: &[Tile], region: &Region) -> u32 {
+    presents_of(tiles, region)
+        .map(|(tile, count)| tile.area() * count)
+        .sum()
+}
+
+/// A cheap condition every region that fits its presents meets, to rule out regions
+/// before searching them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prefilter {
+    /// The presents cover no more cells than the region has.
+    Area,
+    /// Every present fits inside the region on its own, turned either way.
+    Bounds,
+    /// The presents can be colored like a checkerboard matching the region's.
+    Coloring,
+}
+
+impl Prefilter {
+    /// Every prefilter, cheapest first.
+    pub const ALL: [Self; 3] = [Self::Area, Self::Bounds, Self::Coloring];
+
+    fn passes(self, tiles: &[Tile], region: &Region) -> bool {
+        match self {
+            Self::Area => fits_area(tiles, region),
+            Self::Bounds => fits_bounds(tiles, region),
+            Self::Coloring => fits_coloring(tiles, region),
+        }
+    }
+
+    /// The first prefilter that rules out fitting the presents of `region`, if any.
+    fn rejecting(tiles: &[Tile], region: &Region) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|prefilter| !prefilter.passes(tiles, region))
+    }
+}
+
+fn fits_area(tiles: &[Tile], region: &Region) -> bool {
+    needed_area(tiles, region) <= u32::from(region.width) * u32::from(region.height)
+}
+
+fn fits_bounds(tiles: &[Tile], region: &Region) -> bool {
+    let (width, height) = (region.width, region.height);
+    presents_of(tiles, region).all(|(tile, _)| {
+        let shape = tile.trim();
+        let (tile_width, tile_height) = (shape.width(), shape.height());
+        let fits = |width: u8, height: u8| tile_width <= width && tile_height <= height;
+        fits(width, height) || fits(height, width)
+    })
+}
+
+/// Colors the region like a checkerboard. Each present covers a fixed number of cells more of
+/// one color than of the other, either one, and the region must have enough cells of both.
+fn fits_coloring(tiles: &[Tile], region: &Region) -> bool {
+    let area = u32::from(region.width) * u32::from(region.height);
+    let (dark, light) = (area.div_ceil(2), area / 2);
+    let needed = needed_area(tiles, region);
+    // The presents cover `imbalance` more dark cells than light ones, with the imbalance of
+    // each present added or subtracted. That makes it at most `most`, and as the imbalances
+    // are multiples of `step`, it differs from `most` by a multiple of twice that.
+    let (most, step) = presents_of(tiles, region).fold((0, 0), |(most, step), (tile, count)| {
+        let imbalance = color_imbalance(tile);
+        (most + imbalance * count, gcd(step, imbalance))
+    });
+    let (most, step) = (i64::from(most), i64::from(step));
+    // Half of `needed + imbalance` must be dark, and half of `needed - imbalance` light.
+    let low = (i64::from(needed) - 2 * i64::from(light)).max(-most);
+    let high = (2 * i64::from(dark) - i64::from(needed)).min(most);
+    if step == 0 {
+        return low <= 0 && 0 <= high;
+    }
+    low + (most - low).rem_euclid(2 * step) <= high
+}
+
+const fn gcd(mut a: u32, mut b: u32) -> u32 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// A backtracking search placing presents in a region, filling its cells in reading order.
+struct Packing<'a> {
+    width: usize,
+    /// Taken cells of each row, bit `x` for column `x`.
+    rows: Vec<u64>,
+    orientations: &'a [Vec<Shape>],
+    /// Presents of each tile still to place.
+    remaining: Vec<u8>,
+    /// Cells that may still be left empty.
+    slack: u32,
+}
+
+impl Packing<'_> {
+    /// Whether the remaining presents can be placed, with every cell before `cell` decided.
+    fn search(&mut self, cell: usize) -> bool {
+        if self.remaining.iter().all(|&count| count == 0) {
+            return true;
+        }
+        let Some(cell) = (cell..self.width * self.rows.len())
+            .find(|&cell| self.rows[cell / self.width] >> (cell % self.width) & 1 == 0)
+        else {
+            return false;
+        };
+        let (x, y) = (cell % self.width, cell / self.width);
+        for tile in 0..self.remaining.len() {
+            if self.remaining[tile] == 0 {
+                continue;
+            }
+            for shape in &self.orientations[tile] {
+                // Shapes are placed by their anchor, the first cell of their top row.
+                let Some(left) = x.checked_sub(usize::from(shape.anchor())) else {
+                    continue;
+                };
+                if !shape.fits_at(&self.rows, self.width, left, y) {
+                    continue;
+                }
+                shape.toggle_at(&mut self.rows, left, y);
+                self.remaining[tile] -= 1;
+                let found = self.search(cell + 1);
+                self.remaining[tile] += 1;
+                shape.toggle_at(&mut self.rows, left, y);
+                if found {
+                    return true;
+                }
+            }
+        }
+        if self.slack > 0 {
+            self.slack -= 1;
+            self.rows[y] |= 1 << x;
+            let found = self.search(cell + 1);
+            self.rows[y] &= !(1 << x);
+            self.slack += 1;
+            return found;
+        }
+        false
+    }
+}
+
+/// The cells a present covers, as the range of [`RowPacking::masks`] for the row of its
+/// anchor and the rows below.
+type Placement = Range<usize>;
+
+/// Presents of each tile, kept inline for the usual handful of tiles.
+type Counts = SmallVec<[u8; 8]>;
+
+/// Same search as [`Packing`], but with every placement precomputed for each column, and
+/// remembering the rows at which the search has already failed: with the rows above
+/// decided, what is left only depends on the cells the presents above stick into this row
+/// and the next few, and on the presents still to place.
+struct RowPacking {
+    width: usize,
+    rows: Vec<u64>,
+    /// Row masks of every placement.
+    masks: Vec<u64>,
+    /// For each column and tile, where its presents can go with the anchor in that column.
+    placements: Vec<Vec<Vec<Placement>>>,
+    /// How many rows from the anchor's the presents above can still reach.
+    reach: usize,
+    remaining: Counts,
+    slack: u32,
+    /// Row, the cells taken in the rows it can reach, and the presents left, that failed.
+    failed: FastSet<(usize, SmallVec<[u64; 2]>, Counts)>,
+    deadline: Deadline,
+}
+
+impl RowPacking {
+    fn new(orientations: &[Vec<Shape>], region: &Region, slack: u32, deadline: Deadline) -> Self {
+        let width = usize::from(region.width);
+        let mut masks = Vec::new();
+        let placements = (0..width)
+            .map(|x| {
+                orientations
+                    .iter()
+                    .map(|shapes| {
+                        shapes
+                            .iter()
+                            .filter_map(|shape| {
+                                let left = x.checked_sub(usize::from(shape.anchor()))?;
+                                if left + usize::from(shape.width()) > width {
+                                    return None;
+                                }
+                                let start = masks.len();
+                                masks.extend(shape.rows().iter().map(|&row| row << left));
+                                Some(start..masks.len())
+                            })
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect();
+        let reach = orientations
+            .iter()
+            .flatten()
+            .map(|shape| shape.rows().len() - 1)
+            .max()
+            .unwrap_or(0);
+        Self {
+            width,
+            rows: vec![0; usize::from(region.height)],
+            masks,
+            placements,
+            reach,
+            remaining: region.quantities.iter().copied().collect(),
+            slack,
+            failed: FastSet::default(),
+            deadline,
+        }
+    }
+
+    /// Whether the remaining presents can be placed, with every cell before `start` decided.
+    fn search(&mut self, start: usize) -> Result<bool, OutOfTime> {
+        if self.remaining.iter().all(|&count| count == 0) {
+            return Ok(true);
+        }
+        self.deadline.step()?;
+        let Some(cell) = (start..self.width * self.rows.len())
+            .find(|&cell| self.rows[cell / self.width] >> (cell % self.width) & 1 == 0)
+        else {
+            return Ok(false);
+        };
+        let (x, y) = (cell % self.width, cell / self.width);
+        // Remember only the first free cell of each row, before any present is anchored in
+        // it; deeper in the row the states rarely repeat.
+        if start != 0 && (start - 1) / self.width == y {
+            return self.place_at(cell, x, y);
+        }
+        let profile = SmallVec::from_slice(&self.rows[y..(y + self.reach).min(self.rows.len())]);
+        let key = (y, profile, self.remaining.clone());
+        if self.failed.contains(&key) {
+            return Ok(false);
+        }
+        let found = self.place_at(cell, x, y)?;
+        if !found {
+            self.failed.insert(key);
+        }
+        Ok(found)
+    }
+
+    /// Tries every present with its anchor on the free cell `cell`, then leaving it empty.
+    fn place_at(&mut self, cell: usize, x: usize, y: usize) -> Result<bool, OutOfTime> {
+        let below = self.rows.len() - y;
+        for tile in 0..self.remaining.len() {
+            if self.remaining[tile] == 0 {
+                continue;
+            }
+            for ix in 0..self.placements[x][tile].len() {
+                let masks = self.placements[x][tile][ix].clone();
+                let fits = masks.len() <= below
+                    && self.masks[masks.clone()]
+                        .iter()
+                        .zip(&self.rows[y..])
+                        .all(|(mask, taken)| mask & taken == 0);
+                if !fits {
+                    continue;
+                }
+                self.toggle(masks.clone(), y);
+                self.remaining[tile] -= 1;
+                let found = self.search(cell + 1);
+                self.remaining[tile] += 1;
+                self.toggle(masks, y);
+                if found? {
+                    return Ok(true);
+                }
+            }
+        }
+        if self.slack > 0 {
+            self.slack -= 1;
+            self.rows[y] |= 1 << x;
+            let found = self.search(cell + 1);
+            self.rows[y] &= !(1 << x);
+            self.slack += 1;
+            return found;
+        }
+        Ok(false)
+    }
+
+    fn toggle(&mut self, masks: Placement, y: usize) {
+        for (taken, mask) in self.rows[y..].iter_mut().zip(&self.masks[masks]) {
+            *taken ^= mask;
+        }
+    }
+}
+
+/// The last day has no second puzzle: its star comes with the other 23, so this only
+/// completes the day for the runner.
+#[aoc(day12, part2)]
+const fn part_2(_input: &Input) -> &'static str {
+    "Merry Christmas!"
+}
+
+pub struct Day12;
+
+impl Day12 {
+    /// For each region, in input order, the first [`Prefilter`] that rules it out, or `None`
+    /// if the region passes them all and is left to the packing search.
+    #[must_use]
+    pub fn rejections(input: &Input) -> Vec<Option<Prefilter>> {
+        input
+            .regions
+            .iter()
+            .map(|region| Prefilter::rejecting(&input.tiles, region))
+            .collect()
+    }
+}
+
+impl DaySolution for Day12 {
+    const DAY: u8 = 12;
+
+    type Parsed = Input;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        parse(input).map_err(AocError::invalid_input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        part_1(parsed)
+            .map(Answer::from)
+            .map_err(|err| AocError::SolverFailed(err.to_string()))
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(Answer::from(part_2(parsed)))
+    }
 }
 
 #[cfg(test)]
@@ -129,6 +699,20 @@ mod tests {
         12x5: 1 0 1 0 3 2\
     ";
 
+    const DOMINOES: &str = "\
+        0:\n\
+        ##\n\
+        \n\
+        1:\n\
+        #.\n\
+        ##\n\
+        \n\
+        2x2: 2 0\n\
+        3x2: 0 2\n\
+        2x2: 0 2\n\
+        3x3: 3 1\
+    ";
+
     #[test]
     fn test_parse() {
         let input = parse(EXAMPLE).unwrap();
@@ -138,16 +722,140 @@ mod tests {
             Region {
                 width: 4,
                 height: 4,
-                quantities: [0, 0, 0, 0, 2, 0]
+                quantities: vec![0, 0, 0, 0, 2, 0]
             }
         );
     }
 
     #[test]
-    #[ignore = "Algorithm does not work for the example"]
+    fn test_parse_error() {
+        let input = EXAMPLE.replace("12x5: 1 0 1 0 3 2", "12x5: 1 0 1 0 3");
+        let err = parse(&input).unwrap_err();
+        assert_eq!(err.to_string(), "Expected 6 items, found 5 at line 33");
+        let input = EXAMPLE.replace(".##\n###", ".##\n#x#");
+        let err = parse(&input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Tile rows must be equally long, of '#' and '.' at line 13"
+        );
+        let input = EXAMPLE.replace(".##\n###", ".##\n##");
+        let err = parse(&input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Tile rows must be equally long, of '#' and '.' at line 13"
+        );
+        let input = EXAMPLE.replace("###\n.#.\n###", "...\n...\n...");
+        let err = parse(&input).unwrap_err();
+        assert_eq!(err.to_string(), "Tile has no cells at line 26");
+    }
+
+    #[test]
+    fn test_parse_tile_set() {
+        let input = parse(DOMINOES).unwrap();
+        assert_eq!(
+            input.tiles,
+            [Shape::new(2, vec![0b11]), Shape::new(2, vec![0b01, 0b11]),]
+        );
+        assert_eq!(input.regions[3].quantities, [3, 1]);
+        let input = DOMINOES.replace("3x3: 3 1", "3x3: 3 1 0");
+        let err = parse(&input).unwrap_err();
+        assert_eq!(err.to_string(), "Expected 2 items, found 3 at line 11");
+    }
+
+    #[test]
+    fn test_part_1_backtrack() {
+        let input = parse(EXAMPLE).unwrap();
+        let result = part_1_backtrack(&input);
+        assert_eq!(result, 2);
+    }
+
+    #[test]
+    fn test_part_2() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(part_2(&input), "Merry Christmas!");
+    }
+
+    #[test]
+    fn test_orientations() {
+        let input = parse(EXAMPLE).unwrap();
+        let counts = input
+            .tiles
+            .iter()
+            .map(|tile| tile.orientations().len())
+            .collect::<Vec<_>>();
+        assert_eq!(counts, [8, 8, 2, 4, 4, 2]);
+        // Bit 0 is the leftmost column, so the anchor is the second column of the top row.
+        let shape = Shape::new(3, vec![0b000, 0b110, 0b011]).trim();
+        assert_eq!(
+            (shape.rows(), shape.anchor(), shape.width()),
+            (&[0b110, 0b011][..], 1, 3)
+        );
+        let shape = Shape::new(3, vec![0b100, 0b110, 0b100]).trim();
+        assert_eq!(
+            (shape.rows(), shape.anchor(), shape.width()),
+            (&[0b10, 0b11, 0b10][..], 1, 2)
+        );
+    }
+
+    #[test]
     fn test_part_1() {
         let input = parse(EXAMPLE).unwrap();
         let result = part_1(&input);
-        assert_eq!(result, 2);
+        assert_eq!(result, Ok(2));
+    }
+
+    #[test]
+    fn test_part_1_out_of_time() {
+        let input = parse(EXAMPLE).unwrap();
+        let result = count_packable(&input, Duration::ZERO);
+        assert_eq!(
+            result,
+            Err(RegionTimedOut {
+                region: 0,
+                budget: Duration::ZERO
+            })
+        );
+        let err = result.unwrap_err();
+        assert_eq!(err.to_string(), "Region 0: packing not decided within 0ns");
+    }
+
+    #[test]
+    fn test_part_1_tile_set() {
+        let input = parse(DOMINOES).unwrap();
+        assert_eq!(part_1(&input), Ok(3));
+        assert_eq!(part_1_backtrack(&input), 3);
+    }
+
+    #[test]
+    fn test_prefilters() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(Day12::rejections(&input), [None, None, None]);
+        // ###
+        // .#.
+        let tee = parse("0:\n###\n.#.\n\n4x4: 4\n4x3: 3\n3x4: 4\n1x9: 1\n4x2: 2").unwrap();
+        assert_eq!(
+            Day12::rejections(&tee),
+            [
+                None,
+                Some(Prefilter::Coloring),
+                Some(Prefilter::Area),
+                Some(Prefilter::Bounds),
+                None
+            ]
+        );
+        assert_eq!(color_imbalance(&tee.tiles[0]), 2);
+        let region = &tee.regions[1];
+        assert!(fits_area(&tee.tiles, region));
+        assert!(fits_bounds(&tee.tiles, region));
+        assert!(!fits_coloring(&tee.tiles, region));
+        // Three tees leave room for one more in a 4×4 region, with either coloring.
+        let region = Region {
+            width: 4,
+            height: 4,
+            quantities: vec![3],
+        };
+        assert!(fits_coloring(&tee.tiles, &region));
+        // Two tees can't fill a 4×2 region, but that takes the search to tell.
+        assert_eq!(part_1(&tee), Ok(1));
     }
 }
@@ -0,0 +1,21 @@
+use std::fmt::Display;
+
+use thiserror::Error;
+
+/// Errors from solving a puzzle through [`solve_day`](crate::solve_day).
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AocError {
+    #[error("No solution for day {day} part {part}")]
+    NoSolution { day: u8, part: u8 },
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+    #[error("Solver failed: {0}")]
+    SolverFailed(String),
+}
+
+impl AocError {
+    /// Wraps a parser error.
+    pub fn invalid_input(err: impl Display) -> Self {
+        Self::InvalidInput(err.to_string())
+    }
+}
@@ -0,0 +1,92 @@
+//! Downloads puzzle inputs using the `AOC_SESSION` cookie, caching them under `input/<year>/`.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use thiserror::Error;
+
+use crate::runner;
+
+/// Minimum time between two download attempts for the same day.
+pub const COOLDOWN: Duration = Duration::from_mins(15);
+
+const SESSION_VAR: &str = "AOC_SESSION";
+const USER_AGENT: &str = "github.com/MizardX/AdventOfCode_2025 input fetcher";
+
+#[derive(Debug, Error)]
+pub enum FetchError {
+    #[error("{SESSION_VAR} is not set")]
+    MissingSession,
+    #[error("Input for day {day} was requested recently, try again in {}s", remaining.as_secs())]
+    Cooldown { day: u8, remaining: Duration },
+    #[error("Download failed: {0}")]
+    Http(#[from] ureq::Error),
+    #[error("Cache error: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Returns the cached input for `day` of `year`, downloading it first if it isn't cached yet.
+///
+/// # Errors
+///
+/// Fails if the session cookie is missing, the previous attempt was within [`COOLDOWN`],
+/// or the download or cache write fails.
+pub fn fetch_input(year: u16, day: u8) -> Result<String, FetchError> {
+    let path = runner::input_path(year, day);
+    match fs::read_to_string(&path) {
+        Ok(input) => return Ok(input),
+        Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err.into()),
+        Err(_) => {}
+    }
+    let session = std::env::var(SESSION_VAR)
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+        .ok_or(FetchError::MissingSession)?;
+    let marker = marker_path(&path);
+    if let Some(remaining) = cooldown_remaining(&marker) {
+        return Err(FetchError::Cooldown { day, remaining });
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&marker, "")?;
+    let input = ureq::get(&format!("https://adventofcode.com/{year}/day/{day}/input"))
+        .header("Cookie", &format!("session={}", session.trim()))
+        .header("User-Agent", USER_AGENT)
+        .call()?
+        .body_mut()
+        .read_to_string()?;
+    fs::write(&path, &input)?;
+    Ok(input)
+}
+
+fn marker_path(path: &Path) -> PathBuf {
+    let name = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{name}.fetched"))
+}
+
+fn cooldown_remaining(marker: &Path) -> Option<Duration> {
+    let last = fs::metadata(marker).and_then(|m| m.modified()).ok()?;
+    let elapsed = SystemTime::now().duration_since(last).unwrap_or_default();
+    COOLDOWN.checked_sub(elapsed).filter(|d| !d.is_zero())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_marker_path() {
+        assert_eq!(
+            marker_path(Path::new("input/2025/day3.txt")),
+            Path::new("input/2025/.day3.fetched")
+        );
+    }
+
+    #[test]
+    fn test_cooldown_missing_marker() {
+        assert_eq!(cooldown_remaining(Path::new("input/2025/.missing")), None);
+    }
+}
@@ -0,0 +1,122 @@
+//! C ABI, enabled with the `ffi` feature, for calling the solutions from other languages.
+//! The matching declarations are in `include/aoc.h`.
+//!
+//! Build the shared library with `cargo rustc --lib --release --features ffi --crate-type
+//! cdylib`.
+
+use std::{panic, ptr, slice, str};
+
+use crate::{AocError, solve_day};
+
+/// The answer was written to the output buffer.
+pub const AOC_OK: i32 = 0;
+/// A required pointer was null.
+pub const AOC_NULL_POINTER: i32 = -1;
+/// The input is not valid UTF-8.
+pub const AOC_INVALID_UTF8: i32 = -2;
+/// No solution is registered for the day and part.
+pub const AOC_NO_SOLUTION: i32 = -3;
+/// The solution failed; its error message was written to the output buffer.
+pub const AOC_SOLVER_ERROR: i32 = -4;
+/// The output buffer is too small for the answer or error message.
+pub const AOC_BUFFER_TOO_SMALL: i32 = -5;
+/// The solution panicked.
+pub const AOC_PANIC: i32 = -6;
+
+/// Solves `part` of `day` for the `len` bytes of UTF-8 at `input`, writing the answer to
+/// `out_buf` as a NUL-terminated string. Returns one of the `AOC_*` codes.
+///
+/// # Safety
+///
+/// `input` must be valid for reads of `len` bytes, and `out_buf` for writes of `out_len` bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn aoc_solve(
+    day: u8,
+    part: u8,
+    input: *const u8,
+    len: usize,
+    out_buf: *mut u8,
+    out_len: usize,
+) -> i32 {
+    if input.is_null() || out_buf.is_null() {
+        return AOC_NULL_POINTER;
+    }
+    let input = unsafe { slice::from_raw_parts(input, len) };
+    let Ok(input) = str::from_utf8(input) else {
+        return AOC_INVALID_UTF8;
+    };
+    let (code, message) = match panic::catch_unwind(|| solve_day(day, part, input)) {
+        Ok(Ok(answer)) => (AOC_OK, answer.to_string()),
+        Ok(Err(AocError::NoSolution { .. })) => return AOC_NO_SOLUTION,
+        Ok(Err(err @ (AocError::InvalidInput(_) | AocError::SolverFailed(_)))) => {
+            (AOC_SOLVER_ERROR, err.to_string())
+        }
+        Err(_) => return AOC_PANIC,
+    };
+    if message.len() >= out_len {
+        return AOC_BUFFER_TOO_SMALL;
+    }
+    unsafe {
+        ptr::copy_nonoverlapping(message.as_ptr(), out_buf, message.len());
+        out_buf.add(message.len()).write(0);
+    }
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "day01")]
+    use std::ffi::CStr;
+
+    use super::*;
+
+    fn solve(day: u8, part: u8, input: &str, out: &mut [u8]) -> i32 {
+        unsafe {
+            aoc_solve(
+                day,
+                part,
+                input.as_ptr(),
+                input.len(),
+                out.as_mut_ptr(),
+                out.len(),
+            )
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "day01")]
+    fn test_solve() {
+        let mut out = [0xff; 16];
+        let code = solve(
+            1,
+            2,
+            "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82",
+            &mut out,
+        );
+        assert_eq!(code, AOC_OK);
+        assert_eq!(CStr::from_bytes_until_nul(&out).unwrap(), c"6");
+    }
+
+    #[test]
+    #[cfg(feature = "day01")]
+    fn test_errors() {
+        let mut out = [0; 64];
+        assert_eq!(solve(1, 1, "X1", &mut out), AOC_SOLVER_ERROR);
+        assert_eq!(
+            CStr::from_bytes_until_nul(&out).unwrap(),
+            c"Invalid input: Syntax error"
+        );
+        assert_eq!(solve(1, 1, "L1", &mut out[..1]), AOC_BUFFER_TOO_SMALL);
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        let mut out = [0; 16];
+        assert_eq!(solve(26, 1, "", &mut out), AOC_NO_SOLUTION);
+        let code = unsafe { aoc_solve(1, 1, ptr::null(), 0, out.as_mut_ptr(), out.len()) };
+        assert_eq!(code, AOC_NULL_POINTER);
+        let bytes = [0xff, 0xfe];
+        let code = unsafe { aoc_solve(1, 1, bytes.as_ptr(), 2, out.as_mut_ptr(), out.len()) };
+        assert_eq!(code, AOC_INVALID_UTF8);
+    }
+}
@@ -0,0 +1,44 @@
+//! Heap profiling of solutions with dhat, installed with the `dhat-heap` feature.
+//! It takes precedence over the counting allocator of `alloc-stats`.
+//!
+//! The output attributes every allocation to its call site, and can be opened in dhat's viewer:
+//! <https://nnethercote.github.io/dh_view/dh_view.html>
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::runner::Solution;
+
+#[global_allocator]
+static GLOBAL: dhat::Alloc = dhat::Alloc;
+
+/// Where heap profiles are written.
+pub const PROFILE_DIR: &str = "profiles";
+
+/// Where the heap profile for `solution` is written, like `profiles/day08-part1.dhat.json`.
+#[must_use]
+pub fn dhat_path(solution: &Solution) -> PathBuf {
+    Path::new(PROFILE_DIR).join(format!("{}.dhat.json", solution.file_stem()))
+}
+
+/// Runs `f` while recording every heap allocation, and writes the profile to `path`.
+///
+/// Allocations are recorded from all threads.
+///
+/// # Errors
+///
+/// Fails if the directory for `path` can't be created.
+///
+/// # Panics
+///
+/// If another heap profile is being recorded at the same time.
+pub fn profile_heap<T>(path: &Path, f: impl FnOnce() -> T) -> io::Result<T> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let profiler = dhat::Profiler::builder().file_name(path).build();
+    let result = f();
+    drop(profiler);
+    Ok(result)
+}
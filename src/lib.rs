@@ -5,19 +5,87 @@ extern crate aoc_runner;
 #[macro_use]
 extern crate aoc_runner_derive;
 
-mod shared;
-
-mod day_01;
-mod day_02;
-mod day_03;
-mod day_04;
-mod day_05;
-mod day_06;
-mod day_07;
-mod day_08;
-mod day_09;
-mod day_10;
-mod day_11;
-mod day_12;
+pub mod alloc_stats;
+mod answer;
+pub mod compare;
+mod error;
+#[cfg(feature = "download")]
+pub mod fetch;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "dhat-heap")]
+pub mod heap_profile;
+pub mod minimize;
+#[cfg(feature = "profile")]
+pub mod profile;
+pub mod report;
+pub mod results;
+pub mod runner;
+pub mod scaffold;
+pub mod shared;
+mod solution;
+pub mod verify;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
+pub use answer::Answer;
+pub use error::AocError;
+pub use runner::solve_day;
+pub use solution::DaySolution;
+
+include!(concat!(env!("OUT_DIR"), "/days.rs"));
+
+macro_rules! declare_days {
+    (
+        { $($module:ident $struct:ident $day:literal { $(($part:literal, $name:expr, $trait:ident, $method:ident))* })* }
+        $($year_module:ident $year:literal {
+            $($past_module:ident $past_struct:ident)*
+        })*
+    ) => {
+        $(mod $module;)*
+
+        /// The [`DaySolution`] of every enabled day.
+        pub mod days {
+            $(pub use super::$module::$struct;)*
+        }
+
+        $(
+            #[doc = concat!("Solutions to Advent of Code ", $year, ".")]
+            pub mod $year_module {
+                $(mod $past_module;)*
+
+                /// The [`DaySolution`](crate::DaySolution) of every day of the year.
+                pub mod days {
+                    $(pub use super::$past_module::$past_struct;)*
+                }
+            }
+        )*
+
+        static SOLUTIONS: &[runner::Solution] = &[
+            $($(
+                runner::Solution::of::<$year_module::days::$past_struct, 1>($year),
+                runner::Solution::of::<$year_module::days::$past_struct, 2>($year),
+            )*)*
+            $($(runner::Solution {
+                year: runner::YEAR,
+                day: $day,
+                part: $part,
+                name: $name,
+                factory: <Factory as $trait>::$method,
+                typed: Some(runner::Typed::of::<days::$struct>()),
+            },)*)*
+        ];
+    };
+}
+
+for_each_day!(declare_days);
+
+/// The template `aoc new` renders, for a day without a module, compiled with the tests.
+#[cfg(test)]
+#[allow(dead_code, reason = "Only compiled, not solved with")]
+mod scaffolded_day {
+    include!(concat!(env!("OUT_DIR"), "/scaffolded_day.rs"));
+}
+
+// This year's registry. Earlier years are registered through their `DaySolution`s instead.
 aoc_lib! { year = 2025 }
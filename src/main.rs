@@ -0,0 +1,632 @@
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+use std::error::Error;
+#[cfg(feature = "image")]
+use std::fs::File;
+#[cfg(feature = "image")]
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use std::{fs, io};
+
+use advent_of_code_2025::report::Report;
+use advent_of_code_2025::results::{Record, SavedRun};
+use advent_of_code_2025::runner::{self, Outcome, Solution};
+use advent_of_code_2025::scaffold;
+#[cfg(feature = "image")]
+use advent_of_code_2025::shared::viz::{self, Gif};
+use advent_of_code_2025::shared::viz::{Animator, Terminal};
+use advent_of_code_2025::verify::{self, Answers};
+use advent_of_code_2025::{compare, minimize};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, fmt};
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Advent of Code 2025 solutions")]
+struct Cli {
+    /// Write the solvers' step-by-step events to this file, as JSON lines
+    #[arg(long, global = true)]
+    trace_log: Option<PathBuf>,
+    /// Puzzle year
+    #[arg(short, long, global = true, default_value_t = runner::YEAR)]
+    year: u16,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the solutions for a day, or for every day with an input
+    Run(RunArgs),
+    /// Run every variant of a day's solutions and check that they agree
+    Compare {
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// Input file, or `-` for stdin [default: input/<YEAR>/day<DAY>.txt]
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+    },
+    /// Shrink an input on which two implementations of a part disagree
+    Minimize {
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        /// Name of the first implementation, or `default` [default: the first registered]
+        #[arg(short, long)]
+        a: Option<String>,
+        /// Name of the second implementation, or `default` [default: the second registered]
+        #[arg(short, long)]
+        b: Option<String>,
+        /// Input file, or `-` for stdin [default: input/<YEAR>/day<DAY>.txt]
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+        /// Don't count inputs that take longer than this many seconds
+        #[arg(short, long, value_parser = parse_timeout)]
+        timeout: Option<Duration>,
+    },
+    /// Run every day and write a table of the runtimes
+    Report {
+        /// Output file
+        #[arg(short, long, default_value = "report.md")]
+        output: PathBuf,
+        /// Output format [default: from the file extension]
+        #[arg(short, long)]
+        format: Option<Format>,
+        /// Give up on a part after this many seconds
+        #[arg(short, long, value_parser = parse_timeout)]
+        timeout: Option<Duration>,
+    },
+    /// Check every solution against the answers in answers.toml, or answers-<YEAR>.toml
+    Verify {
+        /// Give up on a part after this many seconds
+        #[arg(short, long, value_parser = parse_timeout)]
+        timeout: Option<Duration>,
+    },
+    /// Play a day's simulation in the terminal
+    Animate {
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// Input file, or `-` for stdin [default: input/<YEAR>/day<DAY>.txt]
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+        /// Milliseconds between frames
+        #[arg(long, default_value_t = 100)]
+        delay: u64,
+        /// Write the frames to this animated GIF instead of the terminal
+        #[cfg(feature = "image")]
+        #[arg(long)]
+        gif: Option<PathBuf>,
+    },
+    /// Export the day 8 junction boxes as a colored point cloud, and print the product of the
+    /// three largest circuits
+    Circuits {
+        /// Input file, or `-` for stdin [default: input/2025/day8.txt]
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+        /// Output file, PLY or OBJ by extension
+        #[arg(short, long, default_value = "circuits.ply")]
+        output: PathBuf,
+        /// Number of closest pairs to connect
+        #[arg(short, long, default_value_t = 1000)]
+        connections: usize,
+    },
+    /// Draw the day 9 polygon, its compressed grid and the largest rectangle inside as an SVG
+    #[cfg(feature = "svg")]
+    Polygon {
+        /// Input file, or `-` for stdin [default: input/2025/day9.txt]
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+        /// Output file
+        #[arg(short, long, default_value = "polygon.svg")]
+        output: PathBuf,
+    },
+    /// Create the module for a new day from the template
+    New {
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+    },
+    /// Download and cache the input for a day
+    #[cfg(feature = "download")]
+    Fetch {
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+    },
+}
+
+impl Command {
+    /// Whether the command is about the days of [`runner::YEAR`] only.
+    const fn only_current_year(&self) -> bool {
+        match self {
+            Self::Animate { .. } | Self::Circuits { .. } | Self::New { .. } => true,
+            #[cfg(feature = "svg")]
+            Self::Polygon { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Args)]
+struct RunArgs {
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: Option<u8>,
+    /// Only run this part
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+    /// Input file, or `-` for stdin [default: input/<YEAR>/day<DAY>.txt]
+    #[arg(short, long, requires = "day")]
+    input: Option<PathBuf>,
+    /// Give up on a part after this many seconds
+    #[arg(short, long, value_parser = parse_timeout)]
+    timeout: Option<Duration>,
+    /// Record the answers and timings to this file
+    #[arg(long)]
+    save: Option<PathBuf>,
+    /// Compare the answers and timings against a run recorded with --save
+    #[arg(long)]
+    check: Option<PathBuf>,
+    /// Percentage by which a part may be slower than in the checked run
+    #[arg(long, default_value_t = 20.0)]
+    threshold: f64,
+    /// Write a flamegraph of each part to the profiles directory
+    #[cfg(feature = "profile")]
+    #[arg(long)]
+    profile: bool,
+    /// Record the heap allocations of each part to the profiles directory, for dhat
+    #[cfg(feature = "dhat-heap")]
+    #[arg(long)]
+    heap_profile: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Markdown,
+    Html,
+}
+
+fn load_input(year: u16, day: u8, input: Option<PathBuf>) -> Result<String, Box<dyn Error>> {
+    #[cfg(feature = "download")]
+    if input.is_none() {
+        return Ok(advent_of_code_2025::fetch::fetch_input(year, day)?);
+    }
+    let path = input.unwrap_or_else(|| runner::input_path(year, day));
+    runner::read_input(&path)
+        .map_err(|err| format!("Failed to read {}: {err}", path.display()).into())
+}
+
+fn parse_timeout(arg: &str) -> Result<Duration, String> {
+    let seconds: f64 = arg.parse().map_err(|err| format!("{err}"))?;
+    Duration::try_from_secs_f64(seconds).map_err(|err| format!("{err}"))
+}
+
+fn run(year: u16, args: RunArgs) -> Result<(), Box<dyn Error>> {
+    let RunArgs {
+        day,
+        part,
+        input,
+        timeout,
+        save,
+        check,
+        threshold,
+        #[cfg(feature = "profile")]
+        profile,
+        #[cfg(feature = "dhat-heap")]
+        heap_profile,
+    } = args;
+    let solutions: Vec<&Solution> = runner::solutions_for(year)
+        .iter()
+        .filter(|s| day.is_none_or(|d| s.day == d) && part.is_none_or(|p| s.part == p))
+        .collect();
+    if solutions.is_empty() {
+        let days = day.map_or_else(|| "any day".to_string(), |day| format!("day {day}"));
+        return Err(format!("No solutions for {days}").into());
+    }
+    let checked = check.as_deref().map(SavedRun::load).transpose()?;
+    let mut saved = SavedRun::default();
+    let mut failures = 0;
+    let mut regressions = 0;
+    for solutions in solutions.chunk_by(|a, b| a.day == b.day) {
+        let input = if day.is_some() {
+            load_input(year, solutions[0].day, input.clone())?
+        } else if let Ok(input) = runner::read_input(&runner::input_path(year, solutions[0].day)) {
+            input
+        } else {
+            println!("Day {}: no input", solutions[0].day);
+            continue;
+        };
+        let input = Arc::from(input);
+        for &solution in solutions {
+            let run = || solution.run_limited(&input, timeout);
+            #[cfg(feature = "profile")]
+            let run = || {
+                if profile {
+                    profiled(solution, run)
+                } else {
+                    Ok(run())
+                }
+            };
+            #[cfg(not(feature = "profile"))]
+            let run = || Ok::<_, Box<dyn Error>>(run());
+            #[cfg(feature = "dhat-heap")]
+            let run = || {
+                if heap_profile {
+                    heap_profiled(solution, run)
+                } else {
+                    run()
+                }
+            };
+            let result = run()?;
+            let outcome = match result {
+                Ok(outcome) => outcome,
+                Err(err) => {
+                    println!("{solution}: {err}");
+                    failures += 1;
+                    continue;
+                }
+            };
+            print_outcome(solution, &outcome);
+            if let Some(record) = checked.as_ref().and_then(|c| c.find(solution)) {
+                for regression in record.regressions(&outcome, threshold / 100.0) {
+                    println!("    regression: {regression}");
+                    regressions += 1;
+                }
+            }
+            saved.records.push(Record::new(solution, &outcome));
+        }
+    }
+    if let Some(path) = save {
+        saved.save(&path)?;
+        println!(
+            "Saved {} result(s) to {}",
+            saved.records.len(),
+            path.display()
+        );
+    }
+    if failures > 0 {
+        return Err(format!("{failures} solution(s) failed").into());
+    }
+    if regressions > 0 {
+        return Err(format!("{regressions} regression(s) since the checked run").into());
+    }
+    Ok(())
+}
+
+fn print_outcome(solution: &Solution, outcome: &Outcome) {
+    println!("{solution}: {}", outcome.answer);
+    println!(
+        "    generator: {:?}, runner: {:?}",
+        outcome.parse_time, outcome.run_time
+    );
+    if let (Some(parse), Some(run)) = (outcome.parse_alloc, outcome.run_alloc) {
+        println!(
+            "    generator: {} allocations, {} bytes peak; runner: {} allocations, {} bytes peak",
+            parse.allocations, parse.peak_bytes, run.allocations, run.peak_bytes
+        );
+    }
+}
+
+#[cfg(feature = "profile")]
+fn profiled<T>(solution: &Solution, run: impl FnOnce() -> T) -> Result<T, Box<dyn Error>> {
+    use advent_of_code_2025::profile;
+
+    let path = profile::flamegraph_path(solution);
+    let result = profile::profile(&path, run)?;
+    println!("{solution}: flamegraph written to {}", path.display());
+    Ok(result)
+}
+
+#[cfg(feature = "dhat-heap")]
+fn heap_profiled<T>(
+    solution: &Solution,
+    run: impl FnOnce() -> Result<T, Box<dyn Error>>,
+) -> Result<T, Box<dyn Error>> {
+    use advent_of_code_2025::heap_profile;
+
+    let path = heap_profile::dhat_path(solution);
+    let result = heap_profile::profile_heap(&path, run)??;
+    println!("{solution}: heap profile written to {}", path.display());
+    Ok(result)
+}
+
+fn compare(year: u16, day: u8, input: Option<PathBuf>) -> Result<(), Box<dyn Error>> {
+    let input = load_input(year, day, input)?;
+    let comparisons = compare::compare_day(year, day, &input)?;
+    if comparisons.is_empty() {
+        return Err(format!("No solutions for day {day}").into());
+    }
+    let mut disagreements = 0;
+    for comparison in &comparisons {
+        for variant in &comparison.variants {
+            println!(
+                "{}: {} ({:?}, {:.2}x)",
+                variant.solution,
+                variant.outcome.answer,
+                variant.outcome.run_time,
+                comparison.relative_time(variant),
+            );
+        }
+        if !comparison.agrees() {
+            eprintln!("Part {}: variants disagree", comparison.part);
+            disagreements += 1;
+        }
+    }
+    if disagreements > 0 {
+        return Err(format!("{disagreements} part(s) with disagreeing variants").into());
+    }
+    Ok(())
+}
+
+fn minimize(
+    year: u16,
+    day: u8,
+    part: u8,
+    names: [Option<String>; 2],
+    input: Option<PathBuf>,
+    timeout: Option<Duration>,
+) -> Result<(), Box<dyn Error>> {
+    let variants: Vec<&'static Solution> = runner::solutions_for(year)
+        .iter()
+        .filter(|s| s.day == day && s.part == part)
+        .collect();
+    let mut picked = Vec::new();
+    for (ix, name) in names.into_iter().enumerate() {
+        let solution = match name.as_deref() {
+            None => variants.get(ix).copied(),
+            Some("default") => runner::find(year, day, part, None),
+            Some(name) => runner::find(year, day, part, Some(name)),
+        };
+        let solution = solution.ok_or_else(|| {
+            let names = variants
+                .iter()
+                .map(|s| s.name.unwrap_or("default"))
+                .collect::<Vec<_>>();
+            format!("Day {day} part {part} needs two implementations, has {names:?}")
+        })?;
+        picked.push(solution);
+    }
+    let (a, b) = (picked[0], picked[1]);
+    let input = load_input(year, day, input)?;
+    // Shrinking leads to plenty of inputs that the solutions panic on, which don't count.
+    std::panic::set_hook(Box::new(|_| {}));
+    if !minimize::disagree(a, b, &input, timeout) {
+        return Err(format!("{a} and {b} agree on this input").into());
+    }
+    let minimal = minimize::minimize(&input, |input| minimize::disagree(a, b, input, timeout));
+    let _ = std::panic::take_hook();
+    println!("{minimal}");
+    Ok(())
+}
+
+fn verify(year: u16, timeout: Option<Duration>) -> Result<(), Box<dyn Error>> {
+    let answers = Answers::load(&verify::answers_path(year))?;
+    let checks = verify::verify_all(year, &answers, timeout);
+    for check in &checks {
+        println!("{}: {}", check.solution, check.status);
+    }
+    let failures = checks.iter().filter(|c| c.status.is_failure()).count();
+    if failures > 0 {
+        return Err(format!("{failures} solution(s) failed verification").into());
+    }
+    Ok(())
+}
+
+fn animate(
+    day: u8,
+    input: Option<PathBuf>,
+    delay: Duration,
+    #[cfg(feature = "image")] gif: Option<PathBuf>,
+) -> Result<(), Box<dyn Error>> {
+    let input = load_input(runner::YEAR, day, input)?;
+    #[cfg(feature = "image")]
+    if let Some(path) = gif {
+        let out = File::create(&path)
+            .map_err(|err| format!("Failed to create {}: {err}", path.display()))?;
+        let mut gif = Gif::new(BufWriter::new(out), viz::glyph_palette::<char>, delay);
+        play(day, &input, &mut gif)?;
+        gif.finish()?;
+        println!("Wrote {}", path.display());
+        return Ok(());
+    }
+    let mut terminal = Terminal::new(io::stdout().lock(), delay);
+    play(day, &input, &mut terminal)?;
+    Ok(terminal.finish()?)
+}
+
+#[cfg_attr(
+    not(any(feature = "day04", feature = "day07")),
+    allow(
+        unused,
+        clippy::needless_pass_by_ref_mut,
+        reason = "No animated days enabled"
+    )
+)]
+fn play(day: u8, input: &str, animator: &mut impl Animator<char>) -> Result<(), Box<dyn Error>> {
+    use advent_of_code_2025::DaySolution;
+
+    match day {
+        #[cfg(feature = "day04")]
+        4 => {
+            use advent_of_code_2025::days::Day04;
+            Day04::animate(&Day04::parse(input)?, animator);
+        }
+        #[cfg(feature = "day07")]
+        7 => {
+            use advent_of_code_2025::days::Day07;
+            Day07::animate(&Day07::parse(input)?, animator);
+        }
+        _ => return Err(format!("No animation for day {day}").into()),
+    }
+    Ok(())
+}
+
+#[cfg(feature = "day08")]
+fn circuits(
+    input: Option<PathBuf>,
+    output: &Path,
+    connections: usize,
+) -> Result<(), Box<dyn Error>> {
+    use advent_of_code_2025::DaySolution;
+    use advent_of_code_2025::days::Day08;
+
+    let points = Day08::parse(&load_input(runner::YEAR, 8, input)?)?;
+    println!(
+        "Three largest circuits after {connections} connections: {}",
+        Day08::largest_circuits(&points, connections)
+    );
+    Day08::export_circuits(&points, connections, output)
+        .map_err(|err| format!("Failed to write {}: {err}", output.display()))?;
+    println!("Wrote {}", output.display());
+    Ok(())
+}
+
+#[cfg(not(feature = "day08"))]
+fn circuits(
+    _input: Option<PathBuf>,
+    _output: &Path,
+    _connections: usize,
+) -> Result<(), Box<dyn Error>> {
+    Err("Day 8 is not enabled".into())
+}
+
+#[cfg(all(feature = "svg", feature = "day09"))]
+fn polygon(input: Option<PathBuf>, output: &Path) -> Result<(), Box<dyn Error>> {
+    use advent_of_code_2025::DaySolution;
+    use advent_of_code_2025::days::Day09;
+
+    let polygon = Day09::parse(&load_input(runner::YEAR, 9, input)?)?;
+    Day09::export_svg(&polygon, output)
+        .map_err(|err| format!("Failed to write {}: {err}", output.display()))?;
+    println!("Wrote {}", output.display());
+    Ok(())
+}
+
+#[cfg(all(feature = "svg", not(feature = "day09")))]
+fn polygon(_input: Option<PathBuf>, _output: &Path) -> Result<(), Box<dyn Error>> {
+    Err("Day 9 is not enabled".into())
+}
+
+fn report(
+    year: u16,
+    output: &Path,
+    format: Option<Format>,
+    timeout: Option<Duration>,
+) -> Result<(), Box<dyn Error>> {
+    let format = format.unwrap_or_else(|| match output.extension().and_then(|ext| ext.to_str()) {
+        Some("html" | "htm") => Format::Html,
+        _ => Format::Markdown,
+    });
+    let report = Report::run_all(year, timeout);
+    let rendered = match format {
+        Format::Markdown => report.to_markdown(),
+        Format::Html => report.to_html(),
+    };
+    fs::write(output, rendered)
+        .map_err(|err| format!("Failed to write {}: {err}", output.display()))?;
+    println!("Wrote {} ({:.1?} total)", output.display(), report.total());
+    Ok(())
+}
+
+/// Logs to stderr as configured by `RUST_LOG`, which is off when unset.
+///
+/// With `trace_log`, the solvers' `replay` events are also written there as JSON lines.
+fn init_tracing(trace_log: Option<&Path>) -> Result<(), Box<dyn Error>> {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("off"));
+    let log = fmt::layer().with_writer(io::stderr).with_filter(filter);
+    let replay = trace_log
+        .map(|path| {
+            let file = fs::File::create(path)
+                .map_err(|err| format!("Failed to create {}: {err}", path.display()))?;
+            let layer = fmt::layer()
+                .json()
+                .flatten_event(true)
+                .with_current_span(false)
+                .with_span_list(true)
+                .with_target(false)
+                .with_level(false)
+                .without_time()
+                .with_writer(Mutex::new(file))
+                .with_filter(filter_fn(|meta| {
+                    meta.target() == "replay" || meta.is_span() && meta.name() == "solution"
+                }));
+            Ok::<_, String>(layer)
+        })
+        .transpose()?;
+    tracing_subscriber::registry().with(log).with(replay).init();
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if let Err(err) = init_tracing(cli.trace_log.as_deref()) {
+        eprintln!("error: {err}");
+        return ExitCode::FAILURE;
+    }
+    let year = cli.year;
+    if year != runner::YEAR && cli.command.only_current_year() {
+        eprintln!("error: Only available for {}", runner::YEAR);
+        return ExitCode::FAILURE;
+    }
+    let result = match cli.command {
+        Command::Run(args) => run(year, args),
+        Command::Compare { day, input } => compare(year, day, input),
+        Command::Report {
+            output,
+            format,
+            timeout,
+        } => report(year, &output, format, timeout),
+        Command::Minimize {
+            day,
+            part,
+            a,
+            b,
+            input,
+            timeout,
+        } => minimize(year, day, part, [a, b], input, timeout),
+        Command::Verify { timeout } => verify(year, timeout),
+        Command::Animate {
+            day,
+            input,
+            delay,
+            #[cfg(feature = "image")]
+            gif,
+        } => animate(
+            day,
+            input,
+            Duration::from_millis(delay),
+            #[cfg(feature = "image")]
+            gif,
+        ),
+        Command::Circuits {
+            input,
+            output,
+            connections,
+        } => circuits(input, &output, connections),
+        #[cfg(feature = "svg")]
+        Command::Polygon { input, output } => polygon(input, &output),
+        Command::New { day } => scaffold::create_day(day)
+            .map(|path| println!("Created {}", path.display()))
+            .map_err(|err| {
+                format!(
+                    "Failed to create {}: {err}",
+                    scaffold::day_path(day).display()
+                )
+                .into()
+            }),
+        #[cfg(feature = "download")]
+        Command::Fetch { day } => advent_of_code_2025::fetch::fetch_input(year, day)
+            .map(|input| println!("Day {day}: {} lines", input.lines().count()))
+            .map_err(Into::into),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! Delta debugging of inputs on which two implementations of a part disagree.
+//!
+//! Starting from an input that shows the disagreement, lines are dropped and numbers are
+//! shrunk for as long as the disagreement persists, leaving a small case to debug by hand.
+
+use std::panic;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::runner::Solution;
+
+/// Whether `a` and `b` both solve `input`, with different answers.
+///
+/// Inputs that either fails or panics on, or takes longer than `timeout` for, don't count, so
+/// minimizing doesn't wander off into malformed input.
+#[must_use]
+pub fn disagree(
+    a: &'static Solution,
+    b: &'static Solution,
+    input: &str,
+    timeout: Option<Duration>,
+) -> bool {
+    let input = Arc::from(input);
+    let run = |solution: &'static Solution| {
+        panic::catch_unwind(|| solution.run_limited(&input, timeout))
+            .ok()
+            .and_then(Result::ok)
+    };
+    match (run(a), run(b)) {
+        (Some(a), Some(b)) => a.answer != b.answer,
+        _ => false,
+    }
+}
+
+/// Shrinks `input` while `keep` still holds for it, and returns the smallest input found.
+///
+/// Alternates between dropping runs of lines and shrinking numbers until neither helps.
+/// `keep` is expected to hold for `input` itself.
+pub fn minimize(input: &str, mut keep: impl FnMut(&str) -> bool) -> String {
+    let mut current = input.to_string();
+    loop {
+        let shrunk = shrink_numbers(&drop_lines(&current, &mut keep), &mut keep);
+        if shrunk == current {
+            return current;
+        }
+        current = shrunk;
+    }
+}
+
+/// Drops runs of lines, starting with halves and going down to single lines.
+fn drop_lines(input: &str, keep: &mut impl FnMut(&str) -> bool) -> String {
+    let mut lines = input.lines().collect::<Vec<_>>();
+    let mut chunk = lines.len().div_ceil(2);
+    while chunk > 0 {
+        let mut start = 0;
+        while start < lines.len() {
+            let end = lines.len().min(start + chunk);
+            let candidate = [&lines[..start], &lines[end..]].concat();
+            if !candidate.is_empty() && keep(&candidate.join("\n")) {
+                lines = candidate;
+            } else {
+                start = end;
+            }
+        }
+        chunk /= 2;
+    }
+    lines.join("\n")
+}
+
+/// Lowers each number as far as the input is kept, in steps halving from the number itself.
+fn shrink_numbers(input: &str, keep: &mut impl FnMut(&str) -> bool) -> String {
+    let mut current = input.to_string();
+    let mut pos = 0;
+    while let Some((start, mut end)) = next_number(&current, pos) {
+        let Ok(mut value) = current[start..end].parse::<u64>() else {
+            pos = end;
+            continue;
+        };
+        let mut step = value;
+        while step > 0 {
+            let smaller = (value - step).to_string();
+            let candidate = format!("{}{smaller}{}", &current[..start], &current[end..]);
+            if keep(&candidate) {
+                current = candidate;
+                value -= step;
+                end = start + smaller.len();
+                step = step.min(value);
+            } else {
+                step /= 2;
+            }
+        }
+        pos = end;
+    }
+    current
+}
+
+/// The byte range of the first run of digits at or after `from`.
+fn next_number(text: &str, from: usize) -> Option<(usize, usize)> {
+    let bytes = text.as_bytes();
+    let start = from + bytes[from..].iter().position(u8::is_ascii_digit)?;
+    let len = bytes[start..]
+        .iter()
+        .position(|b| !b.is_ascii_digit())
+        .unwrap_or(bytes.len() - start);
+    Some((start, start + len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drop_lines() {
+        let input = "a\nb\nc\nd\ne\nf\ng";
+        let result = drop_lines(input, &mut |s: &str| s.contains('c') && s.contains('f'));
+        assert_eq!(result, "c\nf");
+    }
+
+    #[test]
+    fn test_shrink_numbers() {
+        let result = shrink_numbers("17,3\n250", &mut |s: &str| {
+            s.split(['\n', ','])
+                .any(|n| n.parse::<u32>().unwrap() >= 100)
+        });
+        assert_eq!(result, "0,0\n100");
+    }
+
+    #[test]
+    fn test_minimize() {
+        let input = "5 9\n100 3\n42 8\n7 70";
+        // "Diverges" on any line whose first number is larger than its second.
+        let result = minimize(input, |s| {
+            s.lines().any(|line| {
+                let (a, b) = line.split_once(' ').unwrap();
+                a.parse::<u32>().unwrap() > b.parse::<u32>().unwrap()
+            })
+        });
+        assert_eq!(result, "1 0");
+    }
+
+    #[test]
+    #[cfg(feature = "day08")]
+    fn test_disagree() {
+        let a = crate::runner::find(crate::runner::YEAR, 8, 2, Some("Heap")).unwrap();
+        let b = crate::runner::find(crate::runner::YEAR, 8, 2, Some("Kruskal")).unwrap();
+        assert!(!disagree(a, b, "0,0,0\n1,0,0\n3,0,0", None));
+        // The heap breaks ties between equally long connections the other way around.
+        let ties = "1,2,3\n4,5,6\n7,8,9";
+        assert!(disagree(a, b, ties, None));
+        // A single point has no connections, which both panic on.
+        assert!(!disagree(a, b, "1,2,3", None));
+    }
+}
@@ -0,0 +1,83 @@
+//! CPU profiling of solutions, writing a flamegraph per part.
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::runner::Solution;
+
+/// Where flamegraphs are written.
+pub const PROFILE_DIR: &str = "profiles";
+
+/// Samples per second. Prime, to avoid sampling in lockstep with periodic work.
+const FREQUENCY: i32 = 997;
+
+#[derive(Debug, Error)]
+pub enum ProfileError {
+    #[error("Profiler failed: {0}")]
+    Pprof(#[from] pprof::Error),
+    #[error("Failed to write flamegraph: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Where the flamegraph for `solution` is written, like `profiles/day09-part2.svg`.
+#[must_use]
+pub fn flamegraph_path(solution: &Solution) -> PathBuf {
+    Path::new(PROFILE_DIR)
+        .join(solution.file_stem())
+        .with_extension("svg")
+}
+
+/// Runs `f` under the sampling profiler, and writes a flamegraph of it to `path`.
+///
+/// # Errors
+///
+/// Fails if the profiler can't be started, or the flamegraph can't be written.
+pub fn profile<T>(path: &Path, f: impl FnOnce() -> T) -> Result<T, ProfileError> {
+    let guard = pprof::ProfilerGuardBuilder::default()
+        .frequency(FREQUENCY)
+        .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+        .build()?;
+    let result = f();
+    let report = guard.report().build()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    report.flamegraph(BufWriter::new(File::create(path)?))?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hint::black_box;
+
+    use super::*;
+
+    #[test]
+    fn test_flamegraph_path() {
+        let solution = Solution {
+            year: crate::runner::YEAR,
+            day: 9,
+            part: 2,
+            name: Some("Compressed"),
+            factory: |_| unreachable!(),
+            typed: None,
+        };
+        assert_eq!(
+            flamegraph_path(&solution),
+            Path::new("profiles/day09-part2-compressed.svg")
+        );
+    }
+
+    #[test]
+    fn test_profile() {
+        let path = std::env::temp_dir().join("aoc-profile-test.svg");
+        let sum = profile(&path, || (0..10_000_000_u64).map(black_box).sum::<u64>()).unwrap();
+        let svg = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(sum, 49_999_995_000_000);
+        assert!(svg.contains("<svg"));
+    }
+}
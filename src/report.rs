@@ -0,0 +1,249 @@
+//! Runs every solution on its input and renders the runtimes as a Markdown or HTML table.
+
+use std::fmt::Write;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::runner::{self, Outcome, Solution};
+
+const BAR_WIDTH: f64 = 30.0;
+
+/// The result of running one solution.
+#[derive(Debug)]
+pub struct Entry {
+    pub solution: &'static Solution,
+    pub result: Result<Outcome, String>,
+}
+
+impl Entry {
+    /// Generator and solver time together, or `None` if the solution failed.
+    #[must_use]
+    pub fn total(&self) -> Option<Duration> {
+        self.result
+            .as_ref()
+            .ok()
+            .map(|outcome| outcome.parse_time + outcome.run_time)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Report {
+    pub entries: Vec<Entry>,
+}
+
+impl Report {
+    /// Runs every registered solution of `year` on its cached input, giving up on any that
+    /// takes longer than `timeout`.
+    #[must_use]
+    pub fn run_all(year: u16, timeout: Option<Duration>) -> Self {
+        let mut entries = Vec::new();
+        for solutions in runner::solutions_for(year).chunk_by(|a, b| a.day == b.day) {
+            let input =
+                runner::read_input(&runner::input_path(year, solutions[0].day)).map(Arc::from);
+            for solution in solutions {
+                let result = input.as_ref().map_or_else(
+                    |_| Err("No input".to_string()),
+                    |input| {
+                        solution
+                            .run_limited(input, timeout)
+                            .map_err(|err| err.to_string())
+                    },
+                );
+                entries.push(Entry { solution, result });
+            }
+        }
+        Self { entries }
+    }
+
+    /// Sum of the runtimes of every successful solution.
+    #[must_use]
+    pub fn total(&self) -> Duration {
+        self.entries.iter().filter_map(Entry::total).sum()
+    }
+
+    fn max(&self) -> Duration {
+        self.entries
+            .iter()
+            .filter_map(Entry::total)
+            .max()
+            .unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let max = self.max();
+        let mut out = String::from(
+            "| Day | Part | Answer | Generator | Runner | Total | |\n\
+             |----:|-----:|-------:|----------:|-------:|------:|:--|\n",
+        );
+        for entry in &self.entries {
+            let solution = entry.solution;
+            let _ = write!(out, "| {} | {} ", solution.day, part_label(solution));
+            let _ = match &entry.result {
+                Ok(outcome) => writeln!(
+                    out,
+                    "| {} | {:.1?} | {:.1?} | {:.1?} | `{}` |",
+                    outcome.answer,
+                    outcome.parse_time,
+                    outcome.run_time,
+                    outcome.parse_time + outcome.run_time,
+                    bar(outcome.parse_time + outcome.run_time, max),
+                ),
+                Err(err) => writeln!(out, "| {err} | | | | |"),
+            };
+        }
+        let _ = writeln!(out, "| **Total** | | | | | **{:.1?}** | |", self.total());
+        out
+    }
+
+    #[must_use]
+    pub fn to_html(&self) -> String {
+        let max = self.max();
+        let year = self
+            .entries
+            .first()
+            .map_or(runner::YEAR, |entry| entry.solution.year);
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>Advent of Code {year} runtimes</title>\n<style>\n\
+             td {{ padding: 0 0.5em; text-align: right; }}\n\
+             .bar {{ background: #4a8; height: 1em; }}\n\
+             </style>\n</head>\n<body>\n<table>\n\
+             <tr><th>Day</th><th>Part</th><th>Answer</th><th>Generator</th><th>Runner</th><th>Total</th><th></th></tr>\n",
+        );
+        for entry in &self.entries {
+            let solution = entry.solution;
+            let _ = write!(
+                out,
+                "<tr><td>{}</td><td>{}</td>",
+                solution.day,
+                escape_html(&part_label(solution))
+            );
+            let _ = match &entry.result {
+                Ok(outcome) => {
+                    let total = outcome.parse_time + outcome.run_time;
+                    writeln!(
+                        out,
+                        "<td>{}</td><td>{:.1?}</td><td>{:.1?}</td><td>{total:.1?}</td>\
+                         <td><div class=\"bar\" style=\"width: {:.1}%\"></div></td></tr>",
+                        escape_html(&outcome.answer.to_string()),
+                        outcome.parse_time,
+                        outcome.run_time,
+                        fraction(total, max) * 100.0,
+                    )
+                }
+                Err(err) => writeln!(out, "<td colspan=\"5\">{}</td></tr>", escape_html(err)),
+            };
+        }
+        let _ = write!(
+            out,
+            "<tr><th>Total</th><td></td><td></td><td></td><td></td><th>{:.1?}</th><td></td></tr>\n\
+             </table>\n</body>\n</html>\n",
+            self.total()
+        );
+        out
+    }
+}
+
+fn part_label(solution: &Solution) -> String {
+    solution.name.map_or_else(
+        || solution.part.to_string(),
+        |name| format!("{} ({name})", solution.part),
+    )
+}
+
+fn fraction(value: Duration, max: Duration) -> f64 {
+    if max.is_zero() {
+        0.0
+    } else {
+        value.as_secs_f64() / max.as_secs_f64()
+    }
+}
+
+fn bar(value: Duration, max: Duration) -> String {
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "fraction is between 0 and 1"
+    )]
+    let width = (fraction(value, max) * BAR_WIDTH).round() as usize;
+    "█".repeat(width.max(1))
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dummy(day: u8, part: u8) -> &'static Solution {
+        Box::leak(Box::new(Solution {
+            year: runner::YEAR,
+            day,
+            part,
+            name: None,
+            factory: |_| Err("not run".into()),
+            typed: None,
+        }))
+    }
+
+    fn example() -> Report {
+        let outcome = |answer: &str, micros| Outcome {
+            answer: answer.into(),
+            parse_time: Duration::from_micros(micros),
+            run_time: Duration::from_micros(micros),
+            parse_alloc: None,
+            run_alloc: None,
+        };
+        Report {
+            entries: vec![
+                Entry {
+                    solution: dummy(1, 1),
+                    result: Ok(outcome("3", 10)),
+                },
+                Entry {
+                    solution: dummy(1, 2),
+                    result: Ok(outcome("6", 20)),
+                },
+                Entry {
+                    solution: dummy(2, 1),
+                    result: Err("No input".to_string()),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_total() {
+        assert_eq!(example().total(), Duration::from_micros(60));
+    }
+
+    #[test]
+    fn test_markdown() {
+        let markdown = example().to_markdown();
+        let lines: Vec<&str> = markdown.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(
+            lines[2],
+            format!(
+                "| 1 | 1 | 3 | 10.0µs | 10.0µs | 20.0µs | `{}` |",
+                "█".repeat(15)
+            )
+        );
+        assert_eq!(lines[4], "| 2 | 1 | No input | | | | |");
+        assert_eq!(lines[5], "| **Total** | | | | | **60.0µs** | |");
+    }
+
+    #[test]
+    fn test_html() {
+        let html = example().to_html();
+        assert!(html.contains("<td>3</td>"));
+        assert!(html.contains("width: 50.0%"));
+        assert!(html.contains("width: 100.0%"));
+        assert!(html.contains("<td colspan=\"5\">No input</td>"));
+    }
+}
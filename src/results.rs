@@ -0,0 +1,194 @@
+//! Saved runs, for comparing later runs against answer by answer and timing by timing.
+
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::runner::{Outcome, Solution};
+
+/// Slowdowns smaller than this are noise, however large relative to the saved time.
+const MIN_SLOWDOWN: Duration = Duration::from_millis(1);
+
+#[derive(Debug, Error)]
+pub enum ResultsError {
+    #[error("Failed to access saved results: {0}")]
+    Io(#[from] io::Error),
+    #[error("Invalid saved results: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// The answer and timings of one solution in a saved run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Record {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub name: Option<String>,
+    pub answer: String,
+    pub parse_time: Duration,
+    pub run_time: Duration,
+}
+
+impl Record {
+    #[must_use]
+    pub fn new(solution: &Solution, outcome: &Outcome) -> Self {
+        Self {
+            year: solution.year,
+            day: solution.day,
+            part: solution.part,
+            name: solution.name.map(str::to_string),
+            answer: outcome.answer.to_string(),
+            parse_time: outcome.parse_time,
+            run_time: outcome.run_time,
+        }
+    }
+
+    fn total_time(&self) -> Duration {
+        self.parse_time + self.run_time
+    }
+
+    /// How `outcome` has gotten worse than this record: a different answer, or a total time
+    /// more than `threshold` (a fraction, like 0.2 for 20%) slower.
+    #[must_use]
+    pub fn regressions(&self, outcome: &Outcome, threshold: f64) -> Vec<Regression> {
+        let mut regressions = Vec::new();
+        let answer = outcome.answer.to_string();
+        if answer != self.answer {
+            regressions.push(Regression::Answer {
+                saved: self.answer.clone(),
+                actual: answer,
+            });
+        }
+        let saved = self.total_time();
+        let actual = outcome.parse_time + outcome.run_time;
+        if actual > saved.mul_f64(1.0 + threshold) && actual.saturating_sub(saved) >= MIN_SLOWDOWN {
+            regressions.push(Regression::Slower { saved, actual });
+        }
+        regressions
+    }
+}
+
+/// A way a solution did worse than in the saved run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Regression {
+    Answer { saved: String, actual: String },
+    Slower { saved: Duration, actual: Duration },
+}
+
+impl Display for Regression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Answer { saved, actual } => {
+                write!(f, "answer changed from {saved} to {actual}")
+            }
+            Self::Slower { saved, actual } => {
+                let ratio = actual.as_secs_f64() / saved.as_secs_f64();
+                write!(f, "slower: {actual:?}, was {saved:?} ({ratio:.2}x)")
+            }
+        }
+    }
+}
+
+/// All records of a run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedRun {
+    pub records: Vec<Record>,
+}
+
+impl SavedRun {
+    /// Reads a run saved with [`SavedRun::save`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be read or isn't a saved run.
+    pub fn load(path: &Path) -> Result<Self, ResultsError> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// Writes the run to `path` as JSON.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be written.
+    pub fn save(&self, path: &Path) -> Result<(), ResultsError> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// The record of `solution`, if it was part of the run.
+    #[must_use]
+    pub fn find(&self, solution: &Solution) -> Option<&Record> {
+        self.records.iter().find(|r| {
+            r.year == solution.year
+                && r.day == solution.day
+                && r.part == solution.part
+                && r.name.as_deref() == solution.name
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Answer;
+
+    const SOLUTION: Solution = Solution {
+        year: crate::runner::YEAR,
+        day: 3,
+        part: 2,
+        name: Some("Fast"),
+        factory: |_| unreachable!(),
+        typed: None,
+    };
+
+    fn outcome(answer: u64, millis: u64) -> Outcome {
+        Outcome {
+            answer: Answer::U64(answer),
+            parse_time: Duration::ZERO,
+            run_time: Duration::from_millis(millis),
+            parse_alloc: None,
+            run_alloc: None,
+        }
+    }
+
+    #[test]
+    fn test_regressions() {
+        let record = Record::new(&SOLUTION, &outcome(42, 10));
+        assert_eq!(record.regressions(&outcome(42, 11), 0.2), []);
+        assert_eq!(
+            record.regressions(&outcome(41, 13), 0.2),
+            [
+                Regression::Answer {
+                    saved: "42".to_string(),
+                    actual: "41".to_string()
+                },
+                Regression::Slower {
+                    saved: Duration::from_millis(10),
+                    actual: Duration::from_millis(13)
+                }
+            ]
+        );
+        let tiny = Record::new(&SOLUTION, &outcome(42, 0));
+        assert_eq!(tiny.regressions(&outcome(42, 0), 0.2), []);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let run = SavedRun {
+            records: vec![Record::new(&SOLUTION, &outcome(42, 10))],
+        };
+        let json = serde_json::to_string(&run).unwrap();
+        assert_eq!(serde_json::from_str::<SavedRun>(&json).unwrap(), run);
+        assert!(run.find(&SOLUTION).is_some());
+        let other = Solution {
+            name: None,
+            ..SOLUTION
+        };
+        assert!(run.find(&other).is_none());
+    }
+}
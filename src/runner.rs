@@ -0,0 +1,467 @@
+use std::any::Any;
+use std::borrow::Borrow;
+use std::error::Error;
+use std::fmt::Display;
+use std::io::Read;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, mpsc};
+use std::time::{Duration, Instant};
+use std::{fs, io, thread};
+
+use aoc_runner::{ArcStr, Runner};
+use thiserror::Error;
+use tracing::{debug_span, info_span};
+
+use crate::alloc_stats::{self, AllocStats};
+use crate::{Answer, AocError, DaySolution};
+
+/// The year of the puzzles in the crate root; earlier years live in `yYYYY` modules.
+pub const YEAR: u16 = 2025;
+
+/// Generates the input for a solution, returning a runner for it.
+pub type Factory = fn(ArcStr) -> Result<Box<dyn Runner>, Box<dyn Error>>;
+
+/// A day's [`DaySolution`], with the type of its parsed input erased.
+#[derive(Clone, Copy)]
+pub struct Typed {
+    parse: fn(&str) -> Result<Box<dyn Any>, AocError>,
+    solve: fn(u8, &dyn Any) -> Result<Answer, AocError>,
+}
+
+impl Typed {
+    #[must_use]
+    pub const fn of<D>() -> Self
+    where
+        D: DaySolution,
+        D::Parsed: 'static,
+    {
+        Self {
+            parse: parse_any::<D>,
+            solve: solve_any::<D>,
+        }
+    }
+}
+
+fn parse_any<D>(input: &str) -> Result<Box<dyn Any>, AocError>
+where
+    D: DaySolution,
+    D::Parsed: 'static,
+{
+    Ok(Box::new(D::parse(input)?))
+}
+
+fn solve_any<D>(part: u8, parsed: &dyn Any) -> Result<Answer, AocError>
+where
+    D: DaySolution,
+    D::Parsed: 'static,
+{
+    let parsed = parsed
+        .downcast_ref::<D::Parsed>()
+        .expect("parsed by the same day");
+    match part {
+        1 => D::part1(parsed),
+        2 => D::part2(parsed),
+        _ => Err(AocError::NoSolution { day: D::DAY, part }),
+    }
+}
+
+/// A solution registered with `#[aoc(dayN, partM)]` or `#[aoc(dayN, partM, Name)]`.
+#[derive(Clone, Copy)]
+pub struct Solution {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub name: Option<&'static str>,
+    pub factory: Factory,
+    /// The day's [`DaySolution`], which default solutions are run through to keep the type
+    /// of their answers. Named variants only display theirs, so their answers are text.
+    pub typed: Option<Typed>,
+}
+
+/// Runs `PART` of a day through its [`DaySolution`], for days outside aoc-runner's registry.
+struct DayRunner<D: DaySolution, const PART: u8> {
+    parsed: D::Parsed,
+    day: PhantomData<D>,
+}
+
+impl<D: DaySolution, const PART: u8> Runner for DayRunner<D, PART> {
+    fn r#gen(input: ArcStr) -> Self {
+        Self::try_gen(input).expect("valid input")
+    }
+
+    fn run(&self) -> Box<dyn Display> {
+        self.try_run().expect("solvable input")
+    }
+
+    fn bench(&self, black_box: fn(&dyn Display)) {
+        black_box(&self.run());
+    }
+
+    fn try_gen(input: ArcStr) -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            parsed: D::parse(input.borrow())?,
+            day: PhantomData,
+        })
+    }
+
+    fn try_run(&self) -> Result<Box<dyn Display>, Box<dyn Error>> {
+        let answer = match PART {
+            1 => D::part1(&self.parsed),
+            _ => D::part2(&self.parsed),
+        };
+        Ok(Box::new(answer?))
+    }
+}
+
+fn day_factory<D, const PART: u8>(input: ArcStr) -> Result<Box<dyn Runner>, Box<dyn Error>>
+where
+    D: DaySolution + 'static,
+    D::Parsed: 'static,
+{
+    Ok(Box::new(DayRunner::<D, PART>::try_gen(input)?))
+}
+
+impl Solution {
+    /// The default solution for `PART` of a day of `year` that is registered through its
+    /// [`DaySolution`] alone, as earlier years' days are.
+    #[must_use]
+    pub const fn of<D, const PART: u8>(year: u16) -> Self
+    where
+        D: DaySolution + 'static,
+        D::Parsed: 'static,
+    {
+        Self {
+            year,
+            day: D::DAY,
+            part: PART,
+            name: None,
+            factory: day_factory::<D, PART>,
+            typed: Some(Typed::of::<D>()),
+        }
+    }
+}
+
+impl std::fmt::Debug for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Solution")
+            .field("year", &self.year)
+            .field("day", &self.day)
+            .field("part", &self.part)
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl std::fmt::Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.year != YEAR {
+            write!(f, "{} ", self.year)?;
+        }
+        write!(f, "Day {} - Part {}", self.day, self.part)?;
+        if let Some(name) = self.name {
+            write!(f, " - {name}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Every registered solution, ordered by year, day, part and name.
+#[must_use]
+pub fn solutions() -> &'static [Solution] {
+    crate::SOLUTIONS
+}
+
+/// The registered solutions of `year`, ordered by day, part and name.
+#[must_use]
+pub fn solutions_for(year: u16) -> &'static [Solution] {
+    let solutions = solutions();
+    let start = solutions.partition_point(|s| s.year < year);
+    let end = solutions.partition_point(|s| s.year <= year);
+    &solutions[start..end]
+}
+
+/// The solution for `day` and `part` of `year`, either the default one or the one with the
+/// given name.
+#[must_use]
+pub fn find(year: u16, day: u8, part: u8, name: Option<&str>) -> Option<&'static Solution> {
+    solutions_for(year).iter().find(|s| {
+        s.day == day
+            && s.part == part
+            && match (s.name, name) {
+                (None, None) => true,
+                (Some(registered), Some(name)) => registered.eq_ignore_ascii_case(name),
+                _ => false,
+            }
+    })
+}
+
+/// The answer of a solution, with the time spent generating its input and solving.
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    pub answer: Answer,
+    pub parse_time: Duration,
+    pub run_time: Duration,
+    /// Heap usage of the generator, if the `alloc-stats` feature is enabled.
+    pub parse_alloc: Option<AllocStats>,
+    /// Heap usage of the solver, if the `alloc-stats` feature is enabled.
+    pub run_alloc: Option<AllocStats>,
+}
+
+impl Solution {
+    /// A file name stem for output about this solution, like `day09-part2-compressed`, or
+    /// `y2024-day01-part1` for earlier years.
+    #[must_use]
+    pub fn file_stem(&self) -> String {
+        let mut stem = format!("day{:02}-part{}", self.day, self.part);
+        if self.year != YEAR {
+            stem = format!("y{}-{stem}", self.year);
+        }
+        if let Some(name) = self.name {
+            stem = format!("{stem}-{}", name.to_lowercase());
+        }
+        stem
+    }
+
+    /// Runs the generator on `input`, returning a runner that solves it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the generator's error.
+    pub fn generate(&self, input: &str) -> Result<Box<dyn Runner>, Box<dyn Error>> {
+        (self.factory)(ArcStr::from(input))
+    }
+
+    /// Parses `input`, through the day's [`DaySolution`] for the default solution.
+    fn prepare(&self, input: &str) -> Result<Prepared, AocError> {
+        match self.typed.filter(|_| self.name.is_none()) {
+            Some(typed) => Ok(Prepared::Typed(typed, (typed.parse)(input)?)),
+            None => self
+                .generate(input)
+                .map(Prepared::Runner)
+                .map_err(|err| AocError::InvalidInput(err.to_string())),
+        }
+    }
+
+    /// Parses `input` and runs the solution on it.
+    ///
+    /// # Errors
+    ///
+    /// Returns the generator's or the solver's error.
+    pub fn run(&self, input: &str) -> Result<Outcome, Box<dyn Error>> {
+        let _span = info_span!(
+            "solution",
+            day = self.day,
+            part = self.part,
+            name = self.name
+        )
+        .entered();
+        let start = Instant::now();
+        let (prepared, parse_alloc) =
+            debug_span!("generate").in_scope(|| alloc_stats::measure(|| self.prepare(input)));
+        let prepared = prepared?;
+        let parsed = Instant::now();
+        let (answer, run_alloc) =
+            debug_span!("solve").in_scope(|| alloc_stats::measure(|| prepared.solve(self.part)));
+        let answer = answer?;
+        let done = Instant::now();
+        Ok(Outcome {
+            answer,
+            parse_time: parsed - start,
+            run_time: done - parsed,
+            parse_alloc,
+            run_alloc,
+        })
+    }
+}
+
+/// Input generated for a solution, ready to solve.
+enum Prepared {
+    /// Parsed by the day's [`DaySolution`].
+    Typed(Typed, Box<dyn Any>),
+    /// Generated for an `#[aoc]` runner, which only displays its answer.
+    Runner(Box<dyn Runner>),
+}
+
+impl Prepared {
+    fn solve(&self, part: u8) -> Result<Answer, AocError> {
+        match self {
+            Self::Typed(typed, parsed) => (typed.solve)(part, parsed.as_ref()),
+            Self::Runner(runner) => runner
+                .try_run()
+                .map(|answer| Answer::Text(answer.to_string()))
+                .map_err(|err| AocError::SolverFailed(err.to_string())),
+        }
+    }
+}
+
+/// Why a solution run under [`Solution::run_limited`] produced no answer.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum RunError {
+    #[error("{0}")]
+    Failed(String),
+    #[error("Timed out after {0:?}")]
+    TimedOut(Duration),
+    #[error("Panicked")]
+    Panicked,
+}
+
+impl Solution {
+    /// Runs the solution like [`Solution::run`], but if `timeout` is given, on a worker thread
+    /// that is abandoned once the time is up.
+    ///
+    /// # Errors
+    ///
+    /// Returns the solution's error, or whether it timed out or panicked.
+    pub fn run_limited(
+        &'static self,
+        input: &Arc<str>,
+        timeout: Option<Duration>,
+    ) -> Result<Outcome, RunError> {
+        let Some(timeout) = timeout else {
+            return self
+                .run(input)
+                .map_err(|err| RunError::Failed(err.to_string()));
+        };
+        let (sender, receiver) = mpsc::channel();
+        let input = Arc::clone(input);
+        thread::Builder::new()
+            .name(self.to_string())
+            .spawn(move || {
+                let result = self.run(&input).map_err(|err| err.to_string());
+                let _ = sender.send(result);
+            })
+            .map_err(|err| RunError::Failed(err.to_string()))?;
+        match receiver.recv_timeout(timeout) {
+            Ok(result) => result.map_err(RunError::Failed),
+            Err(mpsc::RecvTimeoutError::Timeout) => Err(RunError::TimedOut(timeout)),
+            Err(mpsc::RecvTimeoutError::Disconnected) => Err(RunError::Panicked),
+        }
+    }
+}
+
+/// Solves `part` of `day` of [`YEAR`] for `input` with the default solution.
+///
+/// # Errors
+///
+/// Fails if there is no such solution, or if it fails to parse or solve `input`.
+pub fn solve_day(day: u8, part: u8, input: &str) -> Result<Answer, AocError> {
+    let solution = find(YEAR, day, part, None).ok_or(AocError::NoSolution { day, part })?;
+    let _span = info_span!("solution", day, part).entered();
+    let prepared = debug_span!("generate").in_scope(|| solution.prepare(input))?;
+    debug_span!("solve").in_scope(|| prepared.solve(part))
+}
+
+/// Path of the puzzle input for `day` of `year`, where `cargo aoc input` stores it.
+#[must_use]
+pub fn input_path(year: u16, day: u8) -> PathBuf {
+    PathBuf::from(format!("input/{year}/day{day}.txt"))
+}
+
+/// Reads the puzzle input from `path`, or from stdin if `path` is `-`.
+///
+/// # Errors
+///
+/// Returns any error from reading the file or stdin.
+pub fn read_input(path: &Path) -> io::Result<String> {
+    if path == Path::new("-") {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        Ok(input)
+    } else {
+        fs::read_to_string(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registered() {
+        assert!(solutions().is_sorted_by_key(|s| (s.year, s.day, s.part, s.name)));
+        assert_eq!(find(YEAR, 1, 1, None).is_some(), cfg!(feature = "day01"));
+        assert_eq!(find(YEAR, 11, 2, None).is_some(), cfg!(feature = "day11"));
+        assert_eq!(find(YEAR, 12, 1, None).is_some(), cfg!(feature = "day12"));
+        assert_eq!(find(2024, 1, 2, None).is_some(), cfg!(feature = "y2024"));
+        assert!(solutions_for(YEAR).iter().all(|s| s.year == YEAR));
+    }
+
+    #[test]
+    #[cfg(feature = "day01")]
+    fn test_run() {
+        let solution = find(YEAR, 1, 1, None).unwrap();
+        let outcome = solution
+            .run("L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n")
+            .unwrap();
+        assert!(matches!(outcome.answer, Answer::U64(3)));
+        assert!(solution.run("X").is_err());
+        // Variants only display their answers, which are kept as the text they show.
+        let streaming = find(YEAR, 1, 1, Some("Streaming")).unwrap();
+        let outcome = streaming.run("L50").unwrap();
+        assert!(matches!(outcome.answer, Answer::Text(text) if text == "1"));
+    }
+
+    #[test]
+    #[cfg(feature = "y2024")]
+    fn test_run_earlier_year() {
+        let solution = find(2024, 1, 1, None).unwrap();
+        assert_eq!(solution.to_string(), "2024 Day 1 - Part 1");
+        let outcome = solution
+            .run("3   4\n4   3\n2   5\n1   3\n3   9\n3   3")
+            .unwrap();
+        assert!(matches!(outcome.answer, Answer::U64(11)));
+        assert!(solution.generate("3").is_err());
+        assert!(find(YEAR, 1, 1, Some("Y2024")).is_none());
+    }
+
+    #[test]
+    fn test_run_limited() {
+        static SLOW: Solution = Solution {
+            year: YEAR,
+            day: 1,
+            part: 1,
+            name: Some("Slow"),
+            factory: |_| {
+                thread::sleep(Duration::from_secs(1));
+                Err("too late".into())
+            },
+            typed: None,
+        };
+        static PANICKING: Solution = Solution {
+            year: YEAR,
+            day: 1,
+            part: 1,
+            name: Some("Panicking"),
+            factory: |_| panic!("bad input"),
+            typed: None,
+        };
+        let input = Arc::from("");
+        let timeout = Duration::from_millis(10);
+        assert_eq!(
+            SLOW.run_limited(&input, Some(timeout)).unwrap_err(),
+            RunError::TimedOut(timeout)
+        );
+        assert_eq!(
+            PANICKING
+                .run_limited(&input, Some(timeout * 100))
+                .unwrap_err(),
+            RunError::Panicked
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "day01")]
+    fn test_solve_day() {
+        let input = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82";
+        assert_eq!(solve_day(1, 2, input), Ok(Answer::from(6_u64)));
+        assert_eq!(
+            solve_day(1, 1, "X1"),
+            Err(AocError::InvalidInput("Syntax error at line 1".to_string()))
+        );
+        assert_eq!(
+            solve_day(26, 1, input),
+            Err(AocError::NoSolution { day: 26, part: 1 })
+        );
+    }
+}
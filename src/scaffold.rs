@@ -0,0 +1,59 @@
+//! Generates the skeleton of a new day from `templates/day.rs`.
+
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+const TEMPLATE: &str = include_str!("../templates/day.rs");
+
+/// Path of the module for `day`; the build script registers it automatically.
+#[must_use]
+pub fn day_path(day: u8) -> PathBuf {
+    PathBuf::from(format!("src/day_{day:02}.rs"))
+}
+
+/// Renders the template for `day`.
+#[must_use]
+pub fn render(day: u8) -> String {
+    TEMPLATE
+        .replace("@DAY2@", &format!("{day:02}"))
+        .replace("@DAY@", &day.to_string())
+}
+
+/// Writes a new module for `day`, refusing to overwrite an existing one.
+///
+/// # Errors
+///
+/// Returns [`io::ErrorKind::AlreadyExists`] if the module exists, or any error from writing it.
+pub fn create_day(day: u8) -> io::Result<PathBuf> {
+    let path = day_path(day);
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)?;
+    file.write_all(render(day).as_bytes())?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        // The build script renders the same template for a free day, which the crate then
+        // compiles as `scaffolded_day`.
+        let Ok(day) = env!("SCAFFOLDED_DAY").parse() else {
+            return;
+        };
+        let compiled = include_str!(concat!(env!("OUT_DIR"), "/scaffolded_day.rs"));
+        assert_eq!(render(day), compiled);
+        assert!(!compiled.contains('@'));
+    }
+
+    #[test]
+    fn test_create_existing() {
+        let err = create_day(1).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+    }
+}
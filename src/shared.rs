@@ -1,9 +1,46 @@
 #![allow(unused)]
+#![allow(
+    clippy::missing_errors_doc,
+    clippy::missing_panics_doc,
+    reason = "Puzzle helpers; errors and panics are described in prose"
+)]
 
+pub mod arena;
+pub mod automaton;
+#[cfg(feature = "bigint")]
+pub mod bigint;
+pub mod borrowed;
+pub mod combinators;
+pub mod compress;
+pub mod cycle;
+pub mod erosion;
+pub mod geometry;
+pub mod graph;
+pub mod input;
+pub mod math;
+pub mod mst;
+pub mod par;
+pub mod parse;
+pub mod ranges;
+pub mod scan;
+pub mod shape;
+pub mod stats;
+pub mod viz;
+
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
 use std::ops::{Index, IndexMut};
 use std::str::FromStr;
 
+use rustc_hash::FxBuildHasher;
+use thiserror::Error;
+
+/// A `HashMap` with the fast, non-cryptographic Fx hasher. Create with `FastMap::default()`.
+pub type FastMap<K, V> = HashMap<K, V, FxBuildHasher>;
+
+/// A `HashSet` with the fast, non-cryptographic Fx hasher. Create with `FastSet::default()`.
+pub type FastSet<T> = HashSet<T, FxBuildHasher>;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Pos {
     pub row: usize,
@@ -11,12 +48,13 @@ pub struct Pos {
 }
 
 impl Pos {
+    #[must_use]
     pub const fn new(row: usize, col: usize) -> Self {
         Self { row, col }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Grid<T> {
     data: Vec<T>,
     width: usize,
@@ -24,6 +62,7 @@ pub struct Grid<T> {
 }
 
 impl<T> Grid<T> {
+    #[must_use]
     pub fn new(data: Vec<T>, width: usize, height: usize) -> Self {
         assert_eq!(data.len(), width * height);
         Self {
@@ -33,27 +72,95 @@ impl<T> Grid<T> {
         }
     }
 
+    /// Parses a grid of bytes, translating each with `legend`.
+    ///
+    /// Reports the position of the first byte the legend does not recognize,
+    /// and of any row that differs in width from the first.
+    pub fn parse_with_legend(
+        input: &str,
+        legend: impl Fn(u8) -> Option<T>,
+    ) -> Result<Self, GridError> {
+        let width = input.lines().next().unwrap_or_default().len();
+        let mut data = Vec::with_capacity(input.len());
+        let mut height = 0;
+        for (row, line) in input.lines().enumerate() {
+            if line.len() != width {
+                return Err(GridError::RaggedRow {
+                    line: row + 1,
+                    expected: width,
+                    found: line.len(),
+                });
+            }
+            for (col, ch) in line.bytes().enumerate() {
+                let tile = legend(ch).ok_or_else(|| GridError::InvalidTile {
+                    ch: char::from(ch),
+                    pos: Pos::new(row, col),
+                })?;
+                data.push(tile);
+            }
+            height += 1;
+        }
+        Ok(Self {
+            data,
+            width,
+            height,
+        })
+    }
+
     const fn get_index(&self, pos: Pos) -> Option<usize> {
-        if pos.row < self.height || pos.col < self.width {
+        if pos.row < self.height && pos.col < self.width {
             Some(pos.row * self.width + pos.col)
         } else {
             None
         }
     }
 
+    /// The up to eight positions surrounding `pos` that lie within the grid.
+    pub fn neighbors(&self, pos: Pos) -> impl Iterator<Item = Pos> {
+        (pos.row.saturating_sub(1)..self.height.min(pos.row + 2))
+            .flat_map(move |row| {
+                (pos.col.saturating_sub(1)..self.width.min(pos.col + 2))
+                    .map(move |col| Pos::new(row, col))
+            })
+            .filter(move |&neighbor| neighbor != pos)
+    }
+
     pub fn all_positions(&self) -> impl Iterator<Item = Pos> {
         (0..self.height).flat_map(|row| (0..self.width).map(move |col| Pos::new(row, col)))
     }
 
+    /// A grid of the same shape with each tile translated by `f`.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            data: self.data.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    #[must_use]
     pub const fn width(&self) -> usize {
         self.width
     }
 
+    #[must_use]
     pub const fn height(&self) -> usize {
         self.height
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum GridError {
+    #[error("Invalid character {ch:?} at line {}, column {}", pos.row + 1, pos.col + 1)]
+    InvalidTile { ch: char, pos: Pos },
+    #[error("Line {line} has width {found}, expected {expected}")]
+    RaggedRow {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
 impl<T> Index<Pos> for Grid<T> {
     type Output = T;
 
@@ -121,6 +228,7 @@ pub struct UnionFind {
 }
 
 impl UnionFind {
+    #[must_use]
     pub fn new(size: usize) -> Self {
         let nodes = (0..size).map(|parent| UFNode { parent, size: 1 }).collect();
         Self {
@@ -162,6 +270,7 @@ impl UnionFind {
             .filter_map(|(ix, n)| (n.parent == ix).then_some((ix, n.size)))
     }
 
+    #[must_use]
     pub const fn num_roots(&self) -> usize {
         self.num_roots
     }
@@ -0,0 +1,117 @@
+//! A bump arena for many small slices.
+//!
+//! All slices live in one growing `Vec`, and are referred to by [`Span`] handles instead of
+//! references, so the arena can keep growing while handles are held, and structures built on
+//! it stay free of lifetimes.
+
+use std::ops::Range;
+
+/// Handle to a slice allocated in an [`Arena`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Span {
+    start: u32,
+    end: u32,
+}
+
+impl Span {
+    #[must_use]
+    pub const fn len(self) -> usize {
+        (self.end - self.start) as usize
+    }
+
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.start == self.end
+    }
+
+    const fn range(self) -> Range<usize> {
+        self.start as usize..self.end as usize
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Arena<T> {
+    items: Vec<T>,
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Self { items: Vec::new() }
+    }
+}
+
+impl<T> Arena<T> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An arena that can hold `capacity` items before reallocating.
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            items: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Appends the items as one contiguous slice.
+    ///
+    /// # Panics
+    ///
+    /// If the arena grows beyond `u32::MAX` items.
+    pub fn alloc_extend(&mut self, items: impl IntoIterator<Item = T>) -> Span {
+        let start = self.items.len();
+        self.items.extend(items);
+        let index = |ix| u32::try_from(ix).expect("Arena holds at most u32::MAX items");
+        Span {
+            start: index(start),
+            end: index(self.items.len()),
+        }
+    }
+
+    /// The slice allocated as `span`.
+    ///
+    /// # Panics
+    ///
+    /// If `span` is from another, larger arena.
+    #[must_use]
+    pub fn get(&self, span: Span) -> &[T] {
+        &self.items[span.range()]
+    }
+
+    pub fn get_mut(&mut self, span: Span) -> &mut [T] {
+        &mut self.items[span.range()]
+    }
+
+    /// Total number of items in all slices.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alloc() {
+        let mut arena = Arena::with_capacity(8);
+        let a = arena.alloc_extend([1, 2, 3]);
+        let empty = arena.alloc_extend([]);
+        let b = arena.alloc_extend(4..6);
+        assert_eq!(arena.get(a), [1, 2, 3]);
+        assert!(arena.get(empty).is_empty());
+        assert!(empty.is_empty());
+        assert_eq!(arena.get(b), [4, 5]);
+        assert_eq!(b.len(), 2);
+        arena.get_mut(a)[0] = 10;
+        assert_eq!(arena.get(a), [10, 2, 3]);
+        assert_eq!(arena.len(), 5);
+    }
+}
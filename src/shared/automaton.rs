@@ -0,0 +1,171 @@
+//! Cellular automata on grids: every tile's next state follows from its own and its
+//! neighbors' by a fixed rule, with all tiles updated at once.
+
+use std::hash::Hash;
+
+use super::cycle::{self, Cycle};
+use super::{Grid, Pos};
+
+/// Which surrounding tiles count as neighbors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// The eight tiles around, diagonals included.
+    Moore,
+    /// The four tiles sharing an edge.
+    VonNeumann,
+}
+
+impl Neighborhood {
+    /// The neighbors of `pos` within `grid`.
+    pub fn of<T>(self, grid: &Grid<T>, pos: Pos) -> impl Iterator<Item = Pos> {
+        grid.neighbors(pos)
+            .filter(move |n| self == Self::Moore || n.row == pos.row || n.col == pos.col)
+    }
+}
+
+/// The neighbors of one tile, as handed to the rule of an [`Automaton`].
+#[derive(Debug, Clone, Copy)]
+pub struct Neighbors<'a, T> {
+    grid: &'a Grid<T>,
+    pos: Pos,
+    neighborhood: Neighborhood,
+}
+
+impl<'a, T> Neighbors<'a, T> {
+    pub fn iter(&self) -> impl Iterator<Item = &'a T> + use<'a, T> {
+        let grid = self.grid;
+        self.neighborhood.of(grid, self.pos).map(move |n| &grid[n])
+    }
+
+    /// How many of the neighbors satisfy `f`.
+    pub fn count(&self, f: impl Fn(&T) -> bool) -> usize {
+        self.iter().filter(|&tile| f(tile)).count()
+    }
+}
+
+/// A cellular automaton: `rule` gives the next state of a tile from its current state and
+/// its neighbors in `neighborhood`.
+#[derive(Debug, Clone, Copy)]
+pub struct Automaton<F> {
+    neighborhood: Neighborhood,
+    rule: F,
+}
+
+impl<F> Automaton<F> {
+    pub const fn new(neighborhood: Neighborhood, rule: F) -> Self {
+        Self { neighborhood, rule }
+    }
+
+    /// The grid one generation later.
+    pub fn step<T>(&self, grid: &Grid<T>) -> Grid<T>
+    where
+        F: Fn(&T, Neighbors<'_, T>) -> T,
+    {
+        let neighborhood = self.neighborhood;
+        let data = grid
+            .all_positions()
+            .map(|pos| {
+                let neighbors = Neighbors {
+                    grid,
+                    pos,
+                    neighborhood,
+                };
+                (self.rule)(&grid[pos], neighbors)
+            })
+            .collect();
+        Grid::new(data, grid.width(), grid.height())
+    }
+
+    /// Steps `grid` until a generation leaves it unchanged, returning the stable grid and
+    /// how many generations changed it. Never returns if the grid keeps oscillating; use
+    /// [`Automaton::find_cycle`] for those.
+    pub fn stabilize<T>(&self, mut grid: Grid<T>) -> (Grid<T>, usize)
+    where
+        F: Fn(&T, Neighbors<'_, T>) -> T,
+        T: PartialEq,
+    {
+        let mut generations = 0;
+        loop {
+            let next = self.step(&grid);
+            if next == grid {
+                return (grid, generations);
+            }
+            grid = next;
+            generations += 1;
+        }
+    }
+
+    /// Steps `grid` until some generation repeats an earlier one.
+    pub fn find_cycle<T>(&self, grid: Grid<T>) -> Cycle<Grid<T>>
+    where
+        F: Fn(&T, Neighbors<'_, T>) -> T,
+        T: Clone + Eq + Hash,
+    {
+        cycle::find_cycle(grid, |grid| self.step(grid))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(text: &str) -> Grid<bool> {
+        Grid::parse_with_legend(text, |ch| Some(ch == b'#')).unwrap()
+    }
+
+    /// Conway's Game of Life.
+    fn life() -> Automaton<impl Fn(&bool, Neighbors<'_, bool>) -> bool> {
+        Automaton::new(
+            Neighborhood::Moore,
+            |&alive: &bool, neighbors: Neighbors<'_, bool>| {
+                matches!((alive, neighbors.count(|&n| n)), (true, 2 | 3) | (false, 3))
+            },
+        )
+    }
+
+    #[test]
+    fn test_neighborhood() {
+        let grid = grid("...\n...\n...");
+        assert_eq!(Neighborhood::Moore.of(&grid, Pos::new(1, 1)).count(), 8);
+        assert_eq!(
+            Neighborhood::VonNeumann.of(&grid, Pos::new(1, 1)).count(),
+            4
+        );
+        assert_eq!(
+            Neighborhood::VonNeumann.of(&grid, Pos::new(0, 0)).count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_step() {
+        let blinker = grid(".....\n..#..\n..#..\n..#..\n.....");
+        let turned = grid(".....\n.....\n.###.\n.....\n.....");
+        assert_eq!(life().step(&blinker), turned);
+        assert_eq!(life().step(&turned), blinker);
+    }
+
+    #[test]
+    fn test_stabilize() {
+        // A pre-block: one generation later it is a block, which stays.
+        let grid = grid("....\n.##.\n.#..\n....");
+        let (stable, generations) = life().stabilize(grid);
+        assert_eq!(generations, 1);
+        assert_eq!(stable, self::grid("....\n.##.\n.##.\n...."));
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        // A blinker next to a lone cell, which dies first.
+        let grid = grid("#.....\n......\n...#..\n...#..\n...#..\n......");
+        let cycle = life().find_cycle(grid.clone());
+        assert_eq!((cycle.start, cycle.period), (1, 2));
+        assert_eq!(cycle.at(0), &grid);
+        assert_eq!(cycle.at(1_000_000_001), cycle.at(1));
+        assert_ne!(cycle.at(1_000_000_000), cycle.at(1));
+
+        let block = self::grid("....\n.##.\n.##.\n....");
+        let cycle = life().find_cycle(block);
+        assert_eq!((cycle.start, cycle.period), (0, 1));
+    }
+}
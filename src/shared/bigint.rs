@@ -0,0 +1,116 @@
+//! Unsigned integers of any size, for counts that outgrow `u128`.
+//!
+//! Only what the puzzles have needed so far: addition and decimal output.
+
+use std::fmt::{self, Display, Write};
+use std::ops::AddAssign;
+
+/// An unsigned integer of any size, stored as little-endian 64-bit limbs without trailing
+/// zero limbs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    limbs: Vec<u64>,
+}
+
+impl BigUint {
+    #[must_use]
+    pub const fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Divides in place by `divisor`, returning the remainder.
+    fn div_rem_small(&mut self, divisor: u64) -> u64 {
+        let mut rem = 0_u128;
+        for limb in self.limbs.iter_mut().rev() {
+            let cur = rem << 64 | u128::from(*limb);
+            *limb = u64::try_from(cur / u128::from(divisor)).expect("quotient fits a limb");
+            rem = cur % u128::from(divisor);
+        }
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+        u64::try_from(rem).expect("remainder is below the divisor")
+    }
+}
+
+impl From<u128> for BigUint {
+    #[expect(clippy::cast_possible_truncation, reason = "splitting into limbs")]
+    fn from(value: u128) -> Self {
+        let mut limbs = vec![value as u64, (value >> 64) as u64];
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        Self { limbs }
+    }
+}
+
+impl AddAssign<&Self> for BigUint {
+    fn add_assign(&mut self, rhs: &Self) {
+        if self.limbs.len() < rhs.limbs.len() {
+            self.limbs.resize(rhs.limbs.len(), 0);
+        }
+        let mut carry = false;
+        for (ix, limb) in self.limbs.iter_mut().enumerate() {
+            let (sum, c1) = limb.overflowing_add(rhs.limbs.get(ix).copied().unwrap_or(0));
+            let (sum, c2) = sum.overflowing_add(u64::from(carry));
+            *limb = sum;
+            carry = c1 || c2;
+            if !carry && ix >= rhs.limbs.len() {
+                break;
+            }
+        }
+        if carry {
+            self.limbs.push(1);
+        }
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        /// The largest power of ten in a limb.
+        const CHUNK: u64 = 10_u64.pow(19);
+        let mut rest = self.clone();
+        let mut chunks = Vec::new();
+        while !rest.is_zero() {
+            chunks.push(rest.div_rem_small(CHUNK));
+        }
+        let Some((first, others)) = chunks.split_last() else {
+            return f.pad("0");
+        };
+        let mut text = first.to_string();
+        for chunk in others.iter().rev() {
+            write!(text, "{chunk:019}")?;
+        }
+        f.pad(&text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_assign() {
+        let mut n = BigUint::from(u128::MAX);
+        n += &BigUint::from(1);
+        assert_eq!(n.limbs, [0, 0, 1]);
+        n += &BigUint::from(u128::MAX);
+        assert_eq!(n.limbs, [u64::MAX, u64::MAX, 1]);
+        let mut small = BigUint::from(5);
+        small += &n;
+        assert_eq!(small.limbs, [4, 0, 2]);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(BigUint::default().to_string(), "0");
+        assert_eq!(BigUint::from(12_345).to_string(), "12345");
+        assert_eq!(BigUint::from(u128::MAX).to_string(), u128::MAX.to_string());
+        let mut n = BigUint::from(u128::MAX);
+        n += &BigUint::from(u128::MAX);
+        assert_eq!(n.to_string(), "680564733841876926926749214863536422910");
+        let mut ten_pow_19 = BigUint::from(10_u128.pow(19));
+        ten_pow_19 += &BigUint::default();
+        assert_eq!(ten_pow_19.to_string(), "10000000000000000000");
+    }
+}
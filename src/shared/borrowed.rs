@@ -0,0 +1,47 @@
+//! Parsed values that borrow from the puzzle input.
+//!
+//! The runner stores the generator output apart from the input, so a generator
+//! can't return data borrowing from its `&str` argument. Taking the input as
+//! `&Arc<str>` instead lets the parsed value keep the input alive next to it,
+//! so names and other slices don't have to be copied into `String`s.
+//!
+//! ```ignore
+//! #[aoc_generator(dayN)]
+//! fn parse(input: &Arc<str>) -> Result<Borrowed<Graph<'static>>, InputError<ParseError>> {
+//!     parse_borrowed(input)
+//! }
+//!
+//! #[aoc(dayN, part1)]
+//! fn part_1(graph: &Borrowed<Graph<'static>>) -> u64 {
+//!     let graph = graph.get();
+//!     // ...
+//! }
+//! ```
+
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use yoke::Yoke;
+pub use yoke::Yokeable;
+
+use super::input::{InputError, ParseInput};
+use super::parse::normalize_input_trimmed;
+
+/// A parsed value together with the input it borrows from.
+pub type Borrowed<T> = Yoke<T, Arc<str>>;
+
+/// Parses `input` into a value borrowing from it, keeping the input alive.
+///
+/// The input is normalized with [`normalize_input_trimmed`] first, which only
+/// copies it if anything had to change.
+pub fn parse_borrowed<T, E>(input: &Arc<str>) -> Result<Borrowed<T>, InputError<E>>
+where
+    T: for<'a> Yokeable<'a>,
+    for<'a> <T as Yokeable<'a>>::Output: ParseInput<'a, Err = E>,
+{
+    let input = match normalize_input_trimmed(input) {
+        Cow::Borrowed(_) => Arc::clone(input),
+        Cow::Owned(normalized) => Arc::from(normalized),
+    };
+    Yoke::try_attach_to_cart(input, |input| <<T as Yokeable>::Output>::parse_input(input))
+}
@@ -0,0 +1,205 @@
+//! Tiny parser combinators operating on `&str` cursors.
+//!
+//! A parser is any `Fn(&mut &str) -> Result<T, Error>`. On success the cursor
+//! is advanced past the consumed text. On failure the cursor is left where it
+//! was, so alternatives can be tried from the same position.
+
+use std::str::FromStr;
+
+use thiserror::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("Expected {expected}")]
+pub struct Error {
+    pub expected: &'static str,
+    /// Length of the unparsed input where the error occurred.
+    pub remaining: usize,
+}
+
+impl Error {
+    const fn new(expected: &'static str, input: &str) -> Self {
+        Self {
+            expected,
+            remaining: input.len(),
+        }
+    }
+
+    /// Byte offset of the error within `source`, the full string given to the parser.
+    #[must_use]
+    pub const fn offset(&self, source: &str) -> usize {
+        source.len() - self.remaining
+    }
+}
+
+pub type PResult<T> = Result<T, Error>;
+
+/// Runs `parser` on the whole of `input`, failing if anything is left unparsed.
+pub fn all_consuming<'a, T>(
+    input: &'a str,
+    parser: impl Fn(&mut &'a str) -> PResult<T>,
+) -> PResult<T> {
+    let mut cursor = input;
+    let value = parser(&mut cursor)?;
+    if cursor.is_empty() {
+        Ok(value)
+    } else {
+        Err(Error::new("end of input", cursor))
+    }
+}
+
+/// Matches the exact text `lit`.
+pub fn literal<'a>(lit: &'static str) -> impl Fn(&mut &'a str) -> PResult<()> {
+    move |input| {
+        *input = input
+            .strip_prefix(lit)
+            .ok_or_else(|| Error::new(lit, input))?;
+        Ok(())
+    }
+}
+
+/// Skips any number of spaces and tabs.
+#[allow(clippy::unnecessary_wraps, reason = "Usable as a parser")]
+pub fn ws(input: &mut &str) -> PResult<()> {
+    *input = input.trim_start_matches([' ', '\t']);
+    Ok(())
+}
+
+/// Consumes the longest non-empty prefix of characters matching `pred`.
+pub fn take_while1<'a>(
+    expected: &'static str,
+    pred: impl Fn(char) -> bool,
+) -> impl Fn(&mut &'a str) -> PResult<&'a str> {
+    move |input| {
+        let len = input.find(|ch| !pred(ch)).unwrap_or(input.len());
+        if len == 0 {
+            return Err(Error::new(expected, input));
+        }
+        let (taken, rest) = input.split_at(len);
+        *input = rest;
+        Ok(taken)
+    }
+}
+
+/// Parses an unsigned integer.
+pub fn uint<T: FromStr>(input: &mut &str) -> PResult<T> {
+    let start = *input;
+    let digits = take_while1("digit", |ch| ch.is_ascii_digit())(input)?;
+    digits.parse().map_err(|_| {
+        *input = start;
+        Error::new("number in range", start)
+    })
+}
+
+/// Parses an integer with an optional leading `-` or `+`.
+pub fn int<T: FromStr>(input: &mut &str) -> PResult<T> {
+    let start = *input;
+    let sign_len = usize::from(input.starts_with(['-', '+']));
+    let digits_len = input[sign_len..]
+        .find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(input.len() - sign_len);
+    if digits_len == 0 {
+        return Err(Error::new("digit", &input[sign_len..]));
+    }
+    let (number, rest) = input.split_at(sign_len + digits_len);
+    let value = number
+        .parse()
+        .map_err(|_| Error::new("number in range", start))?;
+    *input = rest;
+    Ok(value)
+}
+
+/// Parses `open`, then `inner`, then `close`, keeping the value of `inner`.
+pub fn delimited<'a, T>(
+    open: impl Fn(&mut &'a str) -> PResult<()>,
+    inner: impl Fn(&mut &'a str) -> PResult<T>,
+    close: impl Fn(&mut &'a str) -> PResult<()>,
+) -> impl Fn(&mut &'a str) -> PResult<T> {
+    move |input| {
+        let start = *input;
+        let result = (|| {
+            open(input)?;
+            let value = inner(input)?;
+            close(input)?;
+            Ok(value)
+        })();
+        if result.is_err() {
+            *input = start;
+        }
+        result
+    }
+}
+
+/// Parses one or more `item`s separated by `sep`.
+///
+/// A trailing separator that is not followed by an item is left unconsumed.
+/// If an item fails after consuming part of its input, that error is returned
+/// instead, since it is more specific than whatever would fail next.
+pub fn sep_by<'a, T>(
+    item: impl Fn(&mut &'a str) -> PResult<T>,
+    sep: impl Fn(&mut &'a str) -> PResult<()>,
+) -> impl Fn(&mut &'a str) -> PResult<Vec<T>> {
+    move |input| {
+        let start = *input;
+        let mut items = vec![item(input)?];
+        loop {
+            let before_sep = *input;
+            if sep(input).is_err() {
+                break;
+            }
+            let after_sep = input.len();
+            match item(input) {
+                Ok(value) => items.push(value),
+                Err(err) if err.remaining < after_sep => {
+                    *input = start;
+                    return Err(err);
+                }
+                Err(_) => {
+                    *input = before_sep;
+                    break;
+                }
+            }
+        }
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numbers() {
+        let mut input = "123 -45 +6 x";
+        assert_eq!(uint::<u32>(&mut input), Ok(123));
+        ws(&mut input).unwrap();
+        assert_eq!(int::<i32>(&mut input), Ok(-45));
+        ws(&mut input).unwrap();
+        assert_eq!(int::<i32>(&mut input), Ok(6));
+        ws(&mut input).unwrap();
+        assert_eq!(uint::<u32>(&mut input).unwrap_err().expected, "digit");
+        assert_eq!(input, "x");
+        assert_eq!(
+            uint::<u8>(&mut "256").unwrap_err().expected,
+            "number in range"
+        );
+    }
+
+    #[test]
+    fn test_delimited_list() {
+        let list = delimited(literal("("), sep_by(uint::<u8>, literal(",")), literal(")"));
+        let parser = sep_by(list, literal(" "));
+        let mut input = "(1,3) (2) {";
+        assert_eq!(parser(&mut input), Ok(vec![vec![1, 3], vec![2]]));
+        assert_eq!(input, " {");
+    }
+
+    #[test]
+    fn test_error_offset() {
+        let source = "(1,2]";
+        let parser = delimited(literal("("), sep_by(uint::<u8>, literal(",")), literal(")"));
+        let err = all_consuming(source, parser).unwrap_err();
+        assert_eq!(err.expected, ")");
+        assert_eq!(err.offset(source), 4);
+        assert_eq!(err.to_string(), "Expected )");
+    }
+}
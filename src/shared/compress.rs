@@ -0,0 +1,77 @@
+//! Coordinate compression: a sparse set of values mapped to dense indices, for grids over
+//! coordinates too far apart to store every cell.
+
+use std::ops::Sub;
+
+/// Distinct values in increasing order, each standing for the span of values up to the
+/// next one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoordCompressor<T> {
+    values: Vec<T>,
+}
+
+impl<T: Copy + Ord> CoordCompressor<T> {
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The index of `value`, or of the smallest value above it if it wasn't compressed.
+    #[must_use]
+    pub fn index(&self, value: T) -> usize {
+        self.values.partition_point(|&v| v < value)
+    }
+
+    /// The value at `ix`.
+    #[must_use]
+    pub fn value(&self, ix: usize) -> T {
+        self.values[ix]
+    }
+
+    /// How many original values `ix` stands for, or `None` for the last index, which has
+    /// no end.
+    #[must_use]
+    pub fn width(&self, ix: usize) -> Option<T>
+    where
+        T: Sub<Output = T>,
+    {
+        self.values.get(ix + 1).map(|&next| next - self.values[ix])
+    }
+
+    /// The values, in increasing order.
+    #[must_use]
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<T> for CoordCompressor<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut values = iter.into_iter().collect::<Vec<_>>();
+        values.sort_unstable();
+        values.dedup();
+        Self { values }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compress() {
+        let xs: CoordCompressor<u32> = [30, 10, 11, 30, 0].into_iter().collect();
+        assert_eq!(xs.values(), [0, 10, 11, 30]);
+        assert_eq!(xs.index(11), 2);
+        assert_eq!(xs.index(12), 3);
+        assert_eq!(xs.value(3), 30);
+        assert_eq!(xs.width(0), Some(10));
+        assert_eq!(xs.width(1), Some(1));
+        assert_eq!(xs.width(3), None);
+    }
+}
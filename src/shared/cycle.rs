@@ -0,0 +1,131 @@
+//! Simulations that eventually repeat: step until a state comes back, then read off the
+//! state after any number of steps from the cycle instead of simulating them all.
+
+use std::hash::Hash;
+
+use super::FastMap;
+
+/// The states of a simulation up to the first that repeats.
+#[derive(Debug, Clone)]
+pub struct Cycle<S> {
+    /// Every state before the repeat, starting with the initial one.
+    pub states: Vec<S>,
+    /// Step of the first state that comes back.
+    pub start: usize,
+    /// Steps between its returns: 1 for a state that no longer changes.
+    pub period: usize,
+}
+
+impl<S> Cycle<S> {
+    /// The state after `steps` steps, however far past the repeat.
+    #[must_use]
+    pub fn at(&self, steps: usize) -> &S {
+        if steps < self.start {
+            &self.states[steps]
+        } else {
+            &self.states[self.start + (steps - self.start) % self.period]
+        }
+    }
+}
+
+/// Applies `step` to `state` until it gets a state it has seen before.
+pub fn find_cycle<S>(state: S, step: impl FnMut(&S) -> S) -> Cycle<S>
+where
+    S: Clone + Eq + Hash,
+{
+    find_cycle_within(state, usize::MAX, step)
+        .unwrap_or_else(|_| unreachable!("more states than fit in memory"))
+}
+
+/// The state after applying `step` to `state` `steps` times. Once a state repeats, the
+/// rest is read off the cycle, so `steps` can be far more than could be simulated.
+pub fn run_until_cycle<S>(state: S, steps: usize, step: impl FnMut(&S) -> S) -> S
+where
+    S: Clone + Eq + Hash,
+{
+    match find_cycle_within(state, steps, step) {
+        Ok(cycle) => cycle.at(steps).clone(),
+        Err(state) => state,
+    }
+}
+
+/// Like [`find_cycle`], but gives up with the state after `limit` steps if none has
+/// repeated by then.
+fn find_cycle_within<S>(
+    state: S,
+    limit: usize,
+    mut step: impl FnMut(&S) -> S,
+) -> Result<Cycle<S>, S>
+where
+    S: Clone + Eq + Hash,
+{
+    let mut seen = FastMap::default();
+    let mut states = Vec::new();
+    let mut state = state;
+    loop {
+        if let Some(&start) = seen.get(&state) {
+            return Ok(Cycle {
+                period: states.len() - start,
+                start,
+                states,
+            });
+        }
+        if states.len() == limit {
+            return Err(state);
+        }
+        let next = step(&state);
+        seen.insert(state.clone(), states.len());
+        states.push(state);
+        state = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 3, 10, 5, 16, 8, 4, 2, 1, 4, 2, 1, ...
+    #[expect(
+        clippy::trivially_copy_pass_by_ref,
+        reason = "Shaped like a step function"
+    )]
+    fn collatz(&n: &u64) -> u64 {
+        if n % 2 == 0 { n / 2 } else { 3 * n + 1 }
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let cycle = find_cycle(3, collatz);
+        assert_eq!((cycle.start, cycle.period), (5, 3));
+        assert_eq!(cycle.states, [3, 10, 5, 16, 8, 4, 2, 1]);
+        assert_eq!(*cycle.at(0), 3);
+        assert_eq!(*cycle.at(7), 1);
+        assert_eq!(*cycle.at(8), 4);
+        assert_eq!(*cycle.at(1_000_000_000), 1);
+    }
+
+    #[test]
+    fn test_run_until_cycle() {
+        let mut calls = 0;
+        let state = run_until_cycle(3, 1_000_000_000, |n| {
+            calls += 1;
+            collatz(n)
+        });
+        assert_eq!(state, 1);
+        assert!(calls < 20);
+        // Short runs stop before any repeat.
+        assert_eq!(run_until_cycle(3, 0, collatz), 3);
+        assert_eq!(run_until_cycle(3, 4, collatz), 8);
+        for steps in 0..20 {
+            let expected = (0..steps).fold(3, |n, _| collatz(&n));
+            assert_eq!(run_until_cycle(3, steps, collatz), expected);
+        }
+        // A state that never repeats is only stepped as far as asked.
+        let mut calls = 0;
+        let state = run_until_cycle(0_u64, 10, |n| {
+            calls += 1;
+            n + 1
+        });
+        assert_eq!((state, calls), (10, 10));
+    }
+}
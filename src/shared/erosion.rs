@@ -0,0 +1,143 @@
+//! Erosion of grids: solid tiles with too few solid neighbors crumble away, which can expose
+//! more tiles in turn, until the rest is stable.
+
+use super::automaton::Neighborhood;
+use super::{Grid, Pos};
+
+/// The rules of an erosion: a solid tile, as told by `solid`, crumbles when fewer than
+/// `threshold` of its neighbors are solid.
+#[derive(Debug, Clone, Copy)]
+pub struct Erosion<F> {
+    neighborhood: Neighborhood,
+    threshold: usize,
+    solid: F,
+}
+
+impl<F> Erosion<F> {
+    pub const fn new(neighborhood: Neighborhood, threshold: usize, solid: F) -> Self {
+        Self {
+            neighborhood,
+            threshold,
+            solid,
+        }
+    }
+
+    /// Solid neighbors of `pos`.
+    fn solid_neighbors<T>(&self, grid: &Grid<T>, pos: Pos) -> usize
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.neighborhood
+            .of(grid, pos)
+            .filter(|&n| (self.solid)(&grid[n]))
+            .count()
+    }
+
+    /// The solid tiles of `grid` that crumble right away.
+    pub fn exposed<'a, T>(&'a self, grid: &'a Grid<T>) -> impl Iterator<Item = Pos> + 'a
+    where
+        F: Fn(&T) -> bool,
+    {
+        grid.all_positions().filter(move |&pos| {
+            (self.solid)(&grid[pos]) && self.solid_neighbors(grid, pos) < self.threshold
+        })
+    }
+
+    /// Erodes `grid` until it is stable, returning how many tiles crumbled.
+    ///
+    /// Tiles crumble in rounds: the exposed tiles first, then those exposed by their removal,
+    /// and so on. Each round is decided on the grid as it was when the round started.
+    /// `on_wave` is handed the tiles of each round as they are removed.
+    ///
+    /// Standing tiles are tracked in a bitset, next to a count of the standing neighbors of
+    /// every tile. Removing a tile decrements the counts around it, and a tile joins the next
+    /// round when its count drops below the threshold, which happens at most once.
+    pub fn erode<T>(&self, grid: &Grid<T>, mut on_wave: impl FnMut(&[Pos])) -> usize
+    where
+        F: Fn(&T) -> bool,
+    {
+        let index = |pos: Pos| pos.row * grid.width() + pos.col;
+        let mut standing = BitSet::new(grid.width() * grid.height());
+        let mut counts = vec![0; grid.width() * grid.height()];
+        let mut wave = Vec::new();
+        for pos in grid.all_positions() {
+            if (self.solid)(&grid[pos]) {
+                standing.insert(index(pos));
+                counts[index(pos)] = self.solid_neighbors(grid, pos);
+                if counts[index(pos)] < self.threshold {
+                    wave.push(pos);
+                }
+            }
+        }
+        let mut removed = 0;
+        while !wave.is_empty() {
+            on_wave(&wave);
+            removed += wave.len();
+            for &pos in &wave {
+                standing.remove(index(pos));
+            }
+            let mut next_wave = Vec::new();
+            for pos in wave {
+                for next in self.neighborhood.of(grid, pos) {
+                    let ix = index(next);
+                    if standing.contains(ix) {
+                        counts[ix] -= 1;
+                        if counts[ix] + 1 == self.threshold {
+                            next_wave.push(next);
+                        }
+                    }
+                }
+            }
+            wave = next_wave;
+        }
+        removed
+    }
+}
+
+/// A fixed-size set of indices, one bit each.
+struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    fn insert(&mut self, ix: usize) {
+        self.words[ix / 64] |= 1 << (ix % 64);
+    }
+
+    fn remove(&mut self, ix: usize) {
+        self.words[ix / 64] &= !(1 << (ix % 64));
+    }
+
+    fn contains(&self, ix: usize) -> bool {
+        self.words[ix / 64] >> (ix % 64) & 1 == 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(text: &str) -> Grid<bool> {
+        Grid::parse_with_legend(text, |ch| Some(ch == b'#')).unwrap()
+    }
+
+    #[test]
+    fn test_erode() {
+        // A solid block with a tail: the tail crumbles from its tip, the block holds.
+        let grid = grid("###....\n#######\n###....");
+        let moore = Erosion::new(Neighborhood::Moore, 2, |&solid: &bool| solid);
+        assert_eq!(moore.exposed(&grid).collect::<Vec<_>>(), [Pos::new(1, 6)]);
+        let mut waves = Vec::new();
+        assert_eq!(moore.erode(&grid, |wave| waves.push(wave.len())), 3);
+        assert_eq!(waves, [1, 1, 1]);
+
+        let von_neumann = Erosion::new(Neighborhood::VonNeumann, 2, |&solid: &bool| solid);
+        assert_eq!(von_neumann.erode(&grid, |_| ()), 4);
+    }
+}
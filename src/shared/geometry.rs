@@ -0,0 +1,422 @@
+//! Plane geometry on integer coordinates.
+//!
+//! Containment is about lattice points, or tiles: a [`Polygon`] contains the tiles on its
+//! outline and inside it.
+
+use std::fmt::Display;
+
+use thiserror::Error;
+use tracing::debug;
+
+use super::{FastMap, par};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    #[must_use]
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    /// Tiles in the rectangle with corners `self` and `other`, both included.
+    #[must_use]
+    pub const fn rect_tiles(self, other: Self) -> u64 {
+        (self.x.abs_diff(other.x) + 1) * (self.y.abs_diff(other.y) + 1)
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let &Self { x, y } = self;
+        write!(f, "{x},{y}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PolygonError {
+    #[error("Vertex {second} repeats vertex {first}")]
+    DuplicateVertex { first: usize, second: usize },
+    #[error("Edge from vertex {0} is neither horizontal nor vertical")]
+    DiagonalEdge(usize),
+    #[error("The last vertex, {0}, doesn't line up with the first")]
+    NotClosed(usize),
+    #[error("The edges at vertex {0} double back on each other")]
+    DoublesBack(usize),
+    #[error("A polygon needs at least 4 corners, but this has {0}")]
+    TooFewCorners(usize),
+}
+
+impl PolygonError {
+    /// Index of the vertex the error is about, if it is about one.
+    #[must_use]
+    pub const fn vertex(&self) -> Option<usize> {
+        match *self {
+            Self::DuplicateVertex { second: vertex, .. }
+            | Self::DiagonalEdge(vertex)
+            | Self::NotClosed(vertex)
+            | Self::DoublesBack(vertex) => Some(vertex),
+            Self::TooFewCorners(_) => None,
+        }
+    }
+}
+
+/// A rectilinear polygon: a loop of horizontal and vertical edges through its vertices,
+/// in order, back to the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    vertices: Vec<Point>,
+    bands: Vec<Band>,
+}
+
+impl Polygon {
+    /// A polygon through `vertices`.
+    ///
+    /// Vertices may be in the middle of a straight edge, but may not repeat, and edges may
+    /// not double back along the previous one.
+    pub fn new(vertices: Vec<Point>) -> Result<Self, PolygonError> {
+        let mut seen = FastMap::default();
+        for (second, &p) in vertices.iter().enumerate() {
+            if let Some(&first) = seen.get(&p) {
+                return Err(PolygonError::DuplicateVertex { first, second });
+            }
+            seen.insert(p, second);
+        }
+        let last = vertices.len().saturating_sub(1);
+        for (ix, (p1, p2)) in edges(&vertices).enumerate() {
+            if p1.x != p2.x && p1.y != p2.y {
+                return Err(if ix == last {
+                    PolygonError::NotClosed(ix)
+                } else {
+                    PolygonError::DiagonalEdge(ix)
+                });
+            }
+        }
+        let directions = edges(&vertices)
+            .map(|(p1, p2)| ((p2.x - p1.x).signum(), (p2.y - p1.y).signum()))
+            .collect::<Vec<_>>();
+        let mut corners = 0;
+        for (ix, &(dx, dy)) in directions.iter().enumerate() {
+            let before = directions[(ix + directions.len() - 1) % directions.len()];
+            if (dx, dy) == (-before.0, -before.1) {
+                return Err(PolygonError::DoublesBack(ix));
+            }
+            if (dx, dy) != before {
+                corners += 1;
+            }
+        }
+        if corners < 4 {
+            return Err(PolygonError::TooFewCorners(corners));
+        }
+        let bands = bands(&vertices);
+        Ok(Self { vertices, bands })
+    }
+
+    #[must_use]
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    /// The edges, from each vertex to the next.
+    pub fn edges(&self) -> impl Iterator<Item = (Point, Point)> + '_ {
+        edges(&self.vertices)
+    }
+
+    /// The area enclosed by the outline, through the centers of the tiles on it.
+    #[must_use]
+    pub fn area(&self) -> u64 {
+        let twice: i64 = self.edges().map(|(p1, p2)| p1.x * p2.y - p2.x * p1.y).sum();
+        twice.unsigned_abs() / 2
+    }
+
+    /// The length of the outline.
+    #[must_use]
+    pub fn perimeter(&self) -> u64 {
+        self.edges()
+            .map(|(p1, p2)| p1.x.abs_diff(p2.x) + p1.y.abs_diff(p2.y))
+            .sum()
+    }
+
+    /// Whether the tile at `p` is on the outline or inside it.
+    #[must_use]
+    pub fn contains_point(&self, p: Point) -> bool {
+        self.band_at(p.y)
+            .is_some_and(|band| band.span_at(p.x).is_some())
+    }
+
+    /// Whether every tile of the rectangle with corners `a` and `b` is on the outline or
+    /// inside it.
+    #[must_use]
+    pub fn contains_rect(&self, a: Point, b: Point) -> bool {
+        let (left, right) = (a.x.min(b.x), a.x.max(b.x));
+        let (top, bottom) = (a.y.min(b.y), a.y.max(b.y));
+        if self.band_at(top).is_none() || self.band_at(bottom).is_none() {
+            return false;
+        }
+        self.bands[self.band_index(top)..]
+            .iter()
+            .take_while(|band| band.first <= bottom)
+            .all(|band| band.span_at(left).is_some_and(|(_, end)| end >= right))
+    }
+
+    /// The two vertices that are the corners of the rectangle with the most tiles that is
+    /// contained in the polygon, or `None` if there are no vertices.
+    ///
+    /// From each vertex, walks down the bands while the columns covered in every band so
+    /// far, around the vertex's column, narrow; a vertex further down within those columns
+    /// makes a rectangle that is contained. The vertices are spread over threads with the
+    /// `parallel` feature.
+    #[must_use]
+    pub fn largest_inside_rect(&self) -> Option<(Point, Point)> {
+        let mut by_band = vec![Vec::new(); self.bands.len()];
+        for &p in &self.vertices {
+            by_band[self.band_index(p.y)].push(p);
+        }
+        par::max_by_index(self.vertices.len(), |i| {
+            let p1 = self.vertices[i];
+            let first = self.band_index(p1.y);
+            let (mut lo, mut hi) = (i64::MIN, i64::MAX);
+            let mut best = (1, p1, p1);
+            for (band, others) in self.bands[first..].iter().zip(&by_band[first..]) {
+                let Some((start, end)) = band.span_at(p1.x) else {
+                    break;
+                };
+                (lo, hi) = (lo.max(start), hi.min(end));
+                for &p2 in others {
+                    if (lo..=hi).contains(&p2.x) {
+                        best = best.max((p1.rect_tiles(p2), p1, p2));
+                    }
+                }
+            }
+            best
+        })
+        .map(|(_, p1, p2)| (p1, p2))
+    }
+
+    /// The index of the first band that isn't above row `y`.
+    fn band_index(&self, y: i64) -> usize {
+        self.bands.partition_point(|band| band.last < y)
+    }
+
+    /// The band with row `y`.
+    fn band_at(&self, y: i64) -> Option<&Band> {
+        self.bands
+            .get(self.band_index(y))
+            .filter(|band| band.first <= y)
+    }
+}
+
+fn edges(vertices: &[Point]) -> impl Iterator<Item = (Point, Point)> + '_ {
+    vertices
+        .iter()
+        .copied()
+        .zip(vertices.iter().copied().cycle().skip(1))
+}
+
+/// Rows of tiles that are contained in a polygon in the same places.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Band {
+    first: i64,
+    last: i64,
+    /// Disjoint inclusive ranges of columns, in increasing order.
+    spans: Vec<(i64, i64)>,
+}
+
+impl Band {
+    /// The span that covers column `x`.
+    fn span_at(&self, x: i64) -> Option<(i64, i64)> {
+        let ix = self.spans.partition_point(|&(_, end)| end < x);
+        self.spans.get(ix).copied().filter(|&(start, _)| start <= x)
+    }
+}
+
+/// The tiles contained in the polygon through `vertices`, as bands of rows from the top
+/// vertex to the bottom one.
+///
+/// Sweeps down the rows with a vertex, keeping the vertical edges that cross the gap to the
+/// next such row. Between two rows with vertices, the crossing edges pair up into the
+/// spans of the inside; a row with vertices is covered by the spans on either side of it,
+/// and by its horizontal edges.
+fn bands(vertices: &[Point]) -> Vec<Band> {
+    let mut vertical = Vec::new();
+    let mut horizontal = Vec::new();
+    for (p1, p2) in edges(vertices) {
+        if p1.x == p2.x && p1.y != p2.y {
+            vertical.push((p1.y.min(p2.y), p1.y.max(p2.y), p1.x));
+        } else if p1.y == p2.y {
+            horizontal.push((p1.y, p1.x.min(p2.x), p1.x.max(p2.x)));
+        }
+    }
+    vertical.sort_unstable();
+    horizontal.sort_unstable();
+    let mut ys = vertices.iter().map(|p| p.y).collect::<Vec<_>>();
+    ys.sort_unstable();
+    ys.dedup();
+
+    let mut bands = Vec::new();
+    let mut active: Vec<(i64, i64)> = Vec::new();
+    let (mut next_vertical, mut next_horizontal) = (0, 0);
+    let mut above = Vec::new();
+    for (ix, &y) in ys.iter().enumerate() {
+        active.retain(|&(_, end)| end > y);
+        while let Some(&(start, end, x)) = vertical.get(next_vertical)
+            && start == y
+        {
+            active.push((x, end));
+            next_vertical += 1;
+        }
+        active.sort_unstable();
+        let below = active
+            .chunks_exact(2)
+            .map(|pair| (pair[0].0, pair[1].0))
+            .collect::<Vec<_>>();
+        let mut row = above.clone();
+        row.extend_from_slice(&below);
+        while let Some(&(edge_y, start, end)) = horizontal.get(next_horizontal)
+            && edge_y == y
+        {
+            row.push((start, end));
+            next_horizontal += 1;
+        }
+        bands.push(Band {
+            first: y,
+            last: y,
+            spans: merge_spans(row),
+        });
+        if let Some(&next_y) = ys.get(ix + 1)
+            && next_y > y + 1
+        {
+            bands.push(Band {
+                first: y + 1,
+                last: next_y - 1,
+                spans: merge_spans(below.clone()),
+            });
+        }
+        above = below;
+    }
+    debug!(bands = bands.len(), "swept the polygon");
+    bands
+}
+
+/// Sorts `spans` and joins those that overlap or are next to each other.
+fn merge_spans(mut spans: Vec<(i64, i64)>) -> Vec<(i64, i64)> {
+    spans.sort_unstable();
+    let mut merged: Vec<(i64, i64)> = Vec::with_capacity(spans.len());
+    for (start, end) in spans {
+        match merged.last_mut() {
+            Some((_, last_end)) if start <= *last_end + 1 => *last_end = end.max(*last_end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(vertices: &[(i64, i64)]) -> Polygon {
+        Polygon::new(vertices.iter().map(|&(x, y)| Point::new(x, y)).collect()).unwrap()
+    }
+
+    /// An L: a 5 by 2 bar along the top, with a 2 by 4 leg down the left.
+    fn ell() -> Polygon {
+        polygon(&[(0, 0), (4, 0), (4, 1), (1, 1), (1, 3), (0, 3)])
+    }
+
+    #[test]
+    fn test_bands() {
+        let band = |first, last, spans: &[(i64, i64)]| Band {
+            first,
+            last,
+            spans: spans.to_vec(),
+        };
+        assert_eq!(
+            ell().bands,
+            [
+                band(0, 0, &[(0, 4)]),
+                band(1, 1, &[(0, 4)]),
+                band(2, 2, &[(0, 1)]),
+                band(3, 3, &[(0, 1)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_area_and_perimeter() {
+        let ell = ell();
+        assert_eq!(ell.area(), 4 + 2);
+        assert_eq!(ell.perimeter(), 14);
+        // Pick's theorem: the tiles are the area plus half the outline, plus one.
+        assert_eq!(ell.area() + ell.perimeter() / 2 + 1, 14);
+    }
+
+    #[test]
+    fn test_contains() {
+        let ell = ell();
+        assert!(ell.contains_point(Point::new(3, 1)));
+        assert!(!ell.contains_point(Point::new(3, 2)));
+        assert!(!ell.contains_point(Point::new(0, 4)));
+        assert!(ell.contains_rect(Point::new(0, 0), Point::new(4, 1)));
+        assert!(ell.contains_rect(Point::new(1, 3), Point::new(0, 0)));
+        assert!(!ell.contains_rect(Point::new(0, 0), Point::new(2, 2)));
+        assert!(!ell.contains_rect(Point::new(0, 2), Point::new(1, 4)));
+    }
+
+    #[test]
+    fn test_largest_inside_rect() {
+        let (a, b) = ell().largest_inside_rect().unwrap();
+        assert_eq!(a.rect_tiles(b), 10);
+    }
+
+    #[test]
+    fn test_collinear_vertices() {
+        let straight = polygon(&[
+            (0, 0),
+            (2, 0),
+            (4, 0),
+            (4, 1),
+            (1, 1),
+            (1, 2),
+            (1, 3),
+            (0, 3),
+        ]);
+        let ell = ell();
+        assert_eq!(straight.bands, ell.bands);
+        assert_eq!(straight.area(), ell.area());
+        assert_eq!(straight.perimeter(), ell.perimeter());
+        let (a, b) = straight.largest_inside_rect().unwrap();
+        assert_eq!(a.rect_tiles(b), 10);
+    }
+
+    #[test]
+    fn test_degenerate() {
+        let new = |vertices: &[(i64, i64)]| {
+            Polygon::new(vertices.iter().map(|&(x, y)| Point::new(x, y)).collect())
+        };
+        assert_eq!(
+            new(&[(0, 0), (2, 0), (2, 2), (0, 2), (2, 2)]),
+            Err(PolygonError::DuplicateVertex {
+                first: 2,
+                second: 4
+            })
+        );
+        assert_eq!(
+            new(&[(0, 0), (2, 0), (3, 2), (0, 2)]),
+            Err(PolygonError::DiagonalEdge(1))
+        );
+        assert_eq!(
+            new(&[(0, 0), (2, 0), (2, 2), (1, 2), (1, 3)]),
+            Err(PolygonError::NotClosed(4))
+        );
+        assert_eq!(
+            new(&[(0, 0), (3, 0), (1, 0), (1, 2), (0, 2)]),
+            Err(PolygonError::DoublesBack(1))
+        );
+        assert_eq!(new(&[]), Err(PolygonError::TooFewCorners(0)));
+        assert_eq!(new(&[(0, 0), (2, 0)]), Err(PolygonError::DoublesBack(0)));
+    }
+}
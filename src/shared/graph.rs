@@ -0,0 +1,408 @@
+//! Directed graphs with dense node ids, for puzzles that name their nodes.
+//!
+//! An [`Interner`] hands out ids for names while parsing, and a [`GraphBuilder`] collects the
+//! edges. How the adjacency lists are stored is up to the graph's [`Storage`] parameter:
+//! by default each node keeps up to four neighbors inline ([`Inline`]), which suits the tiny
+//! degrees of most puzzles, and [`Packed`] lays out every list in one [`Arena`] instead.
+
+use std::collections::hash_map::Entry;
+
+use smallvec::SmallVec;
+
+use super::FastMap;
+use super::arena::{Arena, Span};
+
+/// Assigns consecutive ids to distinct names, borrowing the names from the input.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Interner<'a> {
+    ids: FastMap<&'a str, usize>,
+    names: Vec<&'a str>,
+}
+
+impl<'a> Interner<'a> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The id of `name`, assigning the next free id if it is new.
+    pub fn intern(&mut self, name: &'a str) -> usize {
+        match self.ids.entry(name) {
+            Entry::Occupied(entry) => *entry.get(),
+            Entry::Vacant(entry) => {
+                self.names.push(name);
+                *entry.insert(self.names.len() - 1)
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    /// The name with id `id`.
+    #[must_use]
+    pub fn name(&self, id: usize) -> &'a str {
+        self.names[id]
+    }
+
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// The names, indexed by id.
+    #[must_use]
+    pub fn into_names(self) -> Vec<&'a str> {
+        self.names
+    }
+}
+
+/// Collects edges, then lays out the adjacency lists of a [`Graph`].
+#[derive(Debug, Clone, Default)]
+pub struct GraphBuilder {
+    edges: Vec<(usize, usize)>,
+    len: usize,
+}
+
+impl GraphBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes sure the graph has at least `len` nodes, even ones without edges.
+    pub fn reserve_nodes(&mut self, len: usize) {
+        self.len = self.len.max(len);
+    }
+
+    pub fn add_edge(&mut self, from: usize, to: usize) {
+        self.len = self.len.max(from.max(to) + 1);
+        self.edges.push((from, to));
+    }
+
+    /// Builds the graph. Neighbors keep the order their edges were added in.
+    #[must_use]
+    pub fn build<S: Storage>(mut self) -> Graph<S> {
+        self.edges.sort_by_key(|&(from, _)| from);
+        let mut edges = self.edges.as_slice();
+        let lists = (0..self.len).map(|node| {
+            let count = edges.partition_point(|&(from, _)| from == node);
+            let (own, rest) = edges.split_at(count);
+            edges = rest;
+            own.iter().map(|&(_, to)| to)
+        });
+        Graph {
+            storage: S::from_lists(self.len, self.edges.len(), lists),
+        }
+    }
+}
+
+/// How a [`Graph`] stores its adjacency lists.
+pub trait Storage {
+    /// Stores the neighbors of each of `nodes` nodes, `edges` in total.
+    fn from_lists<L>(nodes: usize, edges: usize, lists: impl Iterator<Item = L>) -> Self
+    where
+        L: Iterator<Item = usize>;
+
+    fn node_count(&self) -> usize;
+
+    fn neighbors(&self, node: usize) -> &[usize];
+}
+
+/// Each node's neighbors in a small vector, inline up to four.
+pub type Inline = Vec<SmallVec<[usize; 4]>>;
+
+impl<const N: usize> Storage for Vec<SmallVec<[usize; N]>> {
+    fn from_lists<L>(nodes: usize, _edges: usize, lists: impl Iterator<Item = L>) -> Self
+    where
+        L: Iterator<Item = usize>,
+    {
+        let mut storage = Self::with_capacity(nodes);
+        storage.extend(lists.map(SmallVec::from_iter));
+        storage
+    }
+
+    fn node_count(&self) -> usize {
+        self.len()
+    }
+
+    fn neighbors(&self, node: usize) -> &[usize] {
+        &self[node]
+    }
+}
+
+/// All adjacency lists back to back in one arena.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packed {
+    neighbors: Arena<usize>,
+    spans: Vec<Span>,
+}
+
+impl Storage for Packed {
+    fn from_lists<L>(nodes: usize, edges: usize, lists: impl Iterator<Item = L>) -> Self
+    where
+        L: Iterator<Item = usize>,
+    {
+        let mut neighbors = Arena::with_capacity(edges);
+        let mut spans = Vec::with_capacity(nodes);
+        spans.extend(lists.map(|list| neighbors.alloc_extend(list)));
+        Self { neighbors, spans }
+    }
+
+    fn node_count(&self) -> usize {
+        self.spans.len()
+    }
+
+    fn neighbors(&self, node: usize) -> &[usize] {
+        self.neighbors.get(self.spans[node])
+    }
+}
+
+/// A directed graph over the nodes `0..len()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Graph<S = Inline> {
+    storage: S,
+}
+
+impl<S: Storage> Graph<S> {
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.storage.node_count()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The nodes `node` has edges to.
+    #[must_use]
+    pub fn neighbors(&self, node: usize) -> &[usize] {
+        self.storage.neighbors(node)
+    }
+
+    /// The number of edges into each node.
+    #[must_use]
+    pub fn in_degrees(&self) -> Vec<usize> {
+        let mut degrees = vec![0; self.len()];
+        for node in 0..self.len() {
+            for &next in self.neighbors(node) {
+                degrees[next] += 1;
+            }
+        }
+        degrees
+    }
+
+    /// The nodes ordered so that every edge points forward, or `None` if the graph has a
+    /// cycle.
+    #[must_use]
+    pub fn topological_order(&self) -> Option<Vec<usize>> {
+        let mut in_degrees = self.in_degrees();
+        let mut order = (0..self.len())
+            .filter(|&node| in_degrees[node] == 0)
+            .collect::<Vec<_>>();
+        let mut done = 0;
+        while let Some(&node) = order.get(done) {
+            done += 1;
+            for &next in self.neighbors(node) {
+                in_degrees[next] -= 1;
+                if in_degrees[next] == 0 {
+                    order.push(next);
+                }
+            }
+        }
+        (order.len() == self.len()).then_some(order)
+    }
+
+    /// The strongly connected components, by Tarjan's algorithm. Components come in reverse
+    /// topological order: every edge between two components points to an earlier one.
+    #[must_use]
+    pub fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        const UNVISITED: usize = usize::MAX;
+        let mut index = vec![UNVISITED; self.len()];
+        let mut low = vec![0; self.len()];
+        let mut on_stack = vec![false; self.len()];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut next_index = 0;
+        for root in 0..self.len() {
+            if index[root] != UNVISITED {
+                continue;
+            }
+            // Each node being visited, with how many of its neighbors have been tried.
+            let mut work = vec![(root, 0)];
+            index[root] = next_index;
+            low[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+            while let Some((node, tried)) = work.last_mut() {
+                let node = *node;
+                if let Some(&next) = self.neighbors(node).get(*tried) {
+                    *tried += 1;
+                    if index[next] == UNVISITED {
+                        index[next] = next_index;
+                        low[next] = next_index;
+                        next_index += 1;
+                        stack.push(next);
+                        on_stack[next] = true;
+                        work.push((next, 0));
+                    } else if on_stack[next] {
+                        low[node] = low[node].min(index[next]);
+                    }
+                    continue;
+                }
+                work.pop();
+                if let Some(&(parent, _)) = work.last() {
+                    low[parent] = low[parent].min(low[node]);
+                }
+                if low[node] == index[node] {
+                    let start = stack.iter().rposition(|&n| n == node).unwrap_or(0);
+                    let component = stack.split_off(start);
+                    for &member in &component {
+                        on_stack[member] = false;
+                    }
+                    components.push(component);
+                }
+            }
+        }
+        components
+    }
+
+    /// Contracts each strongly connected component to a single node.
+    #[must_use]
+    pub fn condense<T: Storage>(&self) -> Condensation<T> {
+        let mut components = self.strongly_connected_components();
+        components.reverse();
+        let mut component_of = vec![0; self.len()];
+        for (ix, component) in components.iter().enumerate() {
+            for &node in component {
+                component_of[node] = ix;
+            }
+        }
+        let mut cyclic = vec![false; components.len()];
+        let mut builder = GraphBuilder::new();
+        builder.reserve_nodes(components.len());
+        for node in 0..self.len() {
+            let from = component_of[node];
+            for &next in self.neighbors(node) {
+                let to = component_of[next];
+                if from == to {
+                    cyclic[from] = true;
+                } else {
+                    builder.add_edge(from, to);
+                }
+            }
+        }
+        Condensation {
+            components,
+            component_of,
+            cyclic,
+            dag: builder.build(),
+        }
+    }
+}
+
+/// A graph with each strongly connected component contracted to a single node, which leaves
+/// a directed acyclic graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Condensation<S = Inline> {
+    /// The nodes of each component, with the components in topological order.
+    pub components: Vec<Vec<usize>>,
+    /// The component of each node.
+    pub component_of: Vec<usize>,
+    /// Whether each component has a cycle: more than one node, or a node with an edge to
+    /// itself.
+    pub cyclic: Vec<bool>,
+    /// One edge for every edge between two different components.
+    pub dag: Graph<S>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interner() {
+        let mut interner = Interner::new();
+        assert_eq!(interner.intern("aaa"), 0);
+        assert_eq!(interner.intern("bbb"), 1);
+        assert_eq!(interner.intern("aaa"), 0);
+        assert_eq!(interner.get("bbb"), Some(1));
+        assert_eq!(interner.get("ccc"), None);
+        assert_eq!(interner.name(1), "bbb");
+        assert_eq!(interner.into_names(), ["aaa", "bbb"]);
+    }
+
+    fn example() -> GraphBuilder {
+        let mut builder = GraphBuilder::new();
+        builder.add_edge(2, 0);
+        builder.add_edge(0, 1);
+        builder.add_edge(2, 1);
+        builder.add_edge(0, 2);
+        for to in 0..6 {
+            builder.add_edge(1, to);
+        }
+        builder.reserve_nodes(4);
+        builder
+    }
+
+    fn check<S: Storage>(graph: &Graph<S>) {
+        assert_eq!(graph.len(), 6);
+        assert_eq!(graph.neighbors(0), [1, 2]);
+        assert_eq!(graph.neighbors(1), [0, 1, 2, 3, 4, 5]);
+        assert_eq!(graph.neighbors(2), [0, 1]);
+        assert!(graph.neighbors(3).is_empty());
+        assert_eq!(graph.in_degrees(), [2, 3, 2, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_topological_order() {
+        assert_eq!(example().build::<Inline>().topological_order(), None);
+
+        let mut builder = GraphBuilder::new();
+        for (from, to) in [(3, 1), (1, 0), (3, 0), (2, 3), (4, 4)] {
+            builder.add_edge(from, to);
+        }
+        assert_eq!(builder.clone().build::<Inline>().topological_order(), None);
+        builder.edges.pop();
+        let graph = builder.build::<Inline>();
+        let order = graph.topological_order().unwrap();
+        assert_eq!(order, [2, 4, 3, 1, 0]);
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        let graph = example().build::<Inline>();
+        let mut components = graph.strongly_connected_components();
+        for component in &mut components {
+            component.sort_unstable();
+        }
+        assert_eq!(components, [vec![3], vec![4], vec![5], vec![0, 1, 2]]);
+
+        let condensed = graph.condense::<Inline>();
+        let cycle = condensed.component_of[0];
+        assert_eq!(cycle, 0);
+        assert_eq!(condensed.component_of[1], cycle);
+        assert_eq!(condensed.cyclic, [true, false, false, false]);
+        assert_eq!(condensed.dag.in_degrees(), [0, 1, 1, 1]);
+        assert!(condensed.dag.topological_order().is_some());
+    }
+
+    #[test]
+    fn test_build_inline() {
+        check::<Inline>(&example().build());
+    }
+
+    #[test]
+    fn test_build_packed() {
+        check::<Packed>(&example().build());
+    }
+}
@@ -0,0 +1,175 @@
+//! Parsing of whole puzzle inputs with located errors.
+//!
+//! Types implement [`ParseInput`] to parse the full input at once, or
+//! [`ParseLine`] to be parsed one line at a time into a `Vec`. Either way,
+//! errors are reported as an [`InputError`] pointing at the offending line,
+//! and column when the inner error can tell where it happened.
+
+use std::fmt::Display;
+use std::num::ParseIntError;
+
+use thiserror::Error;
+
+use super::combinators;
+use super::parse::ParseArrayError;
+
+/// Parsing of a complete puzzle input.
+pub trait ParseInput<'a>: Sized {
+    type Err;
+
+    fn parse_input(input: &'a str) -> Result<Self, InputError<Self::Err>>;
+}
+
+/// Parsing of a single line of puzzle input.
+pub trait ParseLine<'a>: Sized {
+    type Err: Locate;
+
+    fn parse_line(line: &'a str) -> Result<Self, Self::Err>;
+}
+
+impl<'a, T: ParseLine<'a>> ParseInput<'a> for Vec<T> {
+    type Err = T::Err;
+
+    fn parse_input(input: &'a str) -> Result<Self, InputError<Self::Err>> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(row, line)| {
+                T::parse_line(line).map_err(|err| InputError::in_line(row, line, err))
+            })
+            .collect()
+    }
+}
+
+/// Errors that know where in the parsed text they occurred.
+pub trait Locate {
+    /// Byte offset of the error within `text`, the string that was parsed.
+    fn offset(&self, text: &str) -> Option<usize>;
+}
+
+impl Locate for combinators::Error {
+    fn offset(&self, text: &str) -> Option<usize> {
+        text.len().checked_sub(self.remaining)
+    }
+}
+
+impl Locate for ParseIntError {
+    fn offset(&self, _text: &str) -> Option<usize> {
+        None
+    }
+}
+
+impl<E> Locate for ParseArrayError<E> {
+    fn offset(&self, _text: &str) -> Option<usize> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub struct InputError<E> {
+    /// One-based line number.
+    pub line: usize,
+    /// One-based column number, if known.
+    pub col: Option<usize>,
+    pub error: E,
+}
+
+impl<E> InputError<E> {
+    /// An error in the line with zero-based index `row`, whose text is `line`.
+    pub fn in_line(row: usize, line: &str, error: E) -> Self
+    where
+        E: Locate,
+    {
+        let col = error
+            .offset(line)
+            .map(|offset| line[..offset].chars().count() + 1);
+        Self {
+            line: row + 1,
+            col,
+            error,
+        }
+    }
+
+    /// An error at byte offset `offset` in `input`.
+    pub fn at_offset(input: &str, offset: usize, error: E) -> Self {
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |ix| ix + 1);
+        Self {
+            line: before.matches('\n').count() + 1,
+            col: Some(before[line_start..].chars().count() + 1),
+            error,
+        }
+    }
+
+    /// Moves the error down by `lines`, for errors from parsing a later part of the input.
+    #[must_use]
+    pub const fn below(mut self, lines: usize) -> Self {
+        self.line += lines;
+        self
+    }
+
+    /// Converts the inner error.
+    pub fn map<F>(self, f: impl FnOnce(E) -> F) -> InputError<F> {
+        InputError {
+            line: self.line,
+            col: self.col,
+            error: f(self.error),
+        }
+    }
+}
+
+impl<E: Display> Display for InputError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at line {}", self.error, self.line)?;
+        if let Some(col) = self.col {
+            write!(f, ", col {col}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Byte offset of `part` within `input`, which it must be a slice of.
+#[must_use]
+pub fn offset_in(input: &str, part: &str) -> usize {
+    let offset = part.as_ptr().addr().wrapping_sub(input.as_ptr().addr());
+    assert!(offset <= input.len(), "part is not a slice of input");
+    offset
+}
+
+/// Number of lines in `input` before `part`, which must be a slice of `input`.
+#[must_use]
+pub fn lines_before(input: &str, part: &str) -> usize {
+    input[..offset_in(input, part)].matches('\n').count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::combinators::{all_consuming, delimited, literal, uint};
+
+    struct Pair(u8);
+
+    impl ParseLine<'_> for Pair {
+        type Err = combinators::Error;
+
+        fn parse_line(line: &str) -> Result<Self, Self::Err> {
+            all_consuming(line, delimited(literal("("), uint, literal(")"))).map(Pair)
+        }
+    }
+
+    #[test]
+    fn test_per_line() {
+        let result = Vec::<Pair>::parse_input("(1)\n(2)\n(3]");
+        let err = result.err().unwrap();
+        assert_eq!((err.line, err.col), (3, Some(3)));
+        assert_eq!(err.to_string(), "Expected ) at line 3, col 3");
+    }
+
+    #[test]
+    fn test_at_offset() {
+        let err = InputError::at_offset("ab\ncde", 5, "oops");
+        assert_eq!((err.line, err.col), (2, Some(3)));
+        let input = "a\nb\nc";
+        assert_eq!(lines_before(input, &input[4..]), 2);
+    }
+}
@@ -0,0 +1,223 @@
+/// Number of decimal digits in `n`. Zero has one digit.
+#[must_use]
+pub const fn num_digits(n: u64) -> u32 {
+    match n.checked_ilog10() {
+        Some(log) => log + 1,
+        None => 1,
+    }
+}
+
+/// The decimal digits of `n`, most significant first.
+#[must_use]
+pub fn digits(n: u64) -> impl DoubleEndedIterator<Item = u8> + ExactSizeIterator {
+    (0..num_digits(n))
+        .rev()
+        .map(move |exp| u8::try_from(n / 10_u64.pow(exp) % 10).expect("single digit"))
+}
+
+/// Builds a number from decimal digits, most significant first.
+pub fn from_digits(digits: impl IntoIterator<Item = u8>) -> u64 {
+    digits
+        .into_iter()
+        .fold(0, |value, digit| value * 10 + u64::from(digit))
+}
+
+/// The number with the decimal digits of `n` in reverse order. Trailing zeros are dropped.
+#[must_use]
+pub fn reverse_digits(n: u64) -> u64 {
+    from_digits(digits(n).rev())
+}
+
+/// Whether the decimal representation of `n` reads the same in both directions.
+#[must_use]
+pub fn is_palindrome(n: u64) -> bool {
+    let mut forward = digits(n);
+    let mut backward = digits(n).rev();
+    (0..num_digits(n) / 2).all(|_| forward.next() == backward.next())
+}
+
+/// The digits of `n` in the given base, most significant first. Zero has one digit.
+#[must_use]
+pub fn to_base(mut n: u64, base: u8) -> Vec<u8> {
+    assert!(base >= 2, "base must be at least 2");
+    let base = u64::from(base);
+    let mut digits = Vec::new();
+    loop {
+        digits.push(u8::try_from(n % base).expect("digit less than base"));
+        n /= base;
+        if n == 0 {
+            break;
+        }
+    }
+    digits.reverse();
+    digits
+}
+
+/// Builds a number from digits in the given base, most significant first.
+pub fn from_base(digits: impl IntoIterator<Item = u8>, base: u8) -> u64 {
+    digits
+        .into_iter()
+        .fold(0, |value, digit| value * u64::from(base) + u64::from(digit))
+}
+
+/// The distinct prime factors of `n`, in increasing order.
+#[must_use]
+pub fn prime_factors(mut n: u64) -> Vec<u64> {
+    let mut factors = Vec::new();
+    let mut p = 2;
+    while p * p <= n {
+        if n.is_multiple_of(p) {
+            factors.push(p);
+            while n.is_multiple_of(p) {
+                n /= p;
+            }
+        }
+        p += 1;
+    }
+    if n > 1 {
+        factors.push(n);
+    }
+    factors
+}
+
+/// A numeral system given by its digit symbols, in order of increasing value.
+///
+/// The first symbol has the value `offset`, so balanced systems where digits
+/// can be negative are supported. SNAFU-style numerals use `Alphabet::new(b"=-012", -2)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Alphabet<'a> {
+    symbols: &'a [u8],
+    offset: i64,
+}
+
+impl<'a> Alphabet<'a> {
+    pub const BINARY: Alphabet<'static> = Alphabet::new(b"01", 0);
+    pub const DECIMAL: Alphabet<'static> = Alphabet::new(b"0123456789", 0);
+    pub const HEX: Alphabet<'static> = Alphabet::new(b"0123456789abcdef", 0);
+
+    #[must_use]
+    pub const fn new(symbols: &'a [u8], offset: i64) -> Self {
+        assert!(symbols.len() >= 2, "at least two symbols required");
+        Self { symbols, offset }
+    }
+
+    #[expect(clippy::cast_possible_wrap, reason = "Alphabets are short")]
+    #[must_use]
+    pub const fn base(&self) -> i64 {
+        self.symbols.len() as i64
+    }
+
+    fn value(&self, symbol: u8) -> Option<i64> {
+        let index = self.symbols.iter().position(|&s| s == symbol)?;
+        Some(i64::try_from(index).ok()? + self.offset)
+    }
+
+    /// Formats `n` with this alphabet, or `None` if it is not representable
+    /// (like negative numbers without negative digits).
+    #[must_use]
+    pub fn encode(&self, mut n: i64) -> Option<String> {
+        let base = self.base();
+        let mut symbols = Vec::new();
+        loop {
+            let digit = (n - self.offset).rem_euclid(base) + self.offset;
+            let index = usize::try_from(digit - self.offset).ok()?;
+            symbols.push(self.symbols[index]);
+            let next = (n - digit) / base;
+            if next == n && digit != n {
+                return None;
+            }
+            n = next;
+            if n == 0 {
+                break;
+            }
+        }
+        symbols.reverse();
+        String::from_utf8(symbols).ok()
+    }
+
+    /// Parses a numeral in this alphabet, or `None` on unknown symbols or overflow.
+    #[must_use]
+    pub fn decode(&self, numeral: &str) -> Option<i64> {
+        if numeral.is_empty() {
+            return None;
+        }
+        numeral.bytes().try_fold(0_i64, |value, symbol| {
+            value
+                .checked_mul(self.base())?
+                .checked_add(self.value(symbol)?)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case(0 => 1)]
+    #[test_case(9 => 1)]
+    #[test_case(10 => 2)]
+    #[test_case(999_999 => 6)]
+    #[test_case(u64::MAX => 20)]
+    fn test_num_digits(n: u64) -> u32 {
+        num_digits(n)
+    }
+
+    #[test]
+    fn test_digits() {
+        assert_eq!(digits(0).collect::<Vec<_>>(), [0]);
+        assert_eq!(digits(1_203).collect::<Vec<_>>(), [1, 2, 0, 3]);
+        assert_eq!(from_digits([1, 2, 0, 3]), 1_203);
+        assert_eq!(reverse_digits(1_230), 321);
+    }
+
+    #[test_case(0 => true)]
+    #[test_case(11 => true)]
+    #[test_case(12_321 => true)]
+    #[test_case(1_221 => true)]
+    #[test_case(1_231 => false)]
+    #[test_case(10 => false)]
+    fn test_is_palindrome(n: u64) -> bool {
+        is_palindrome(n)
+    }
+
+    #[test]
+    fn test_prime_factors() {
+        assert!(prime_factors(1).is_empty());
+        assert_eq!(prime_factors(12), [2, 3]);
+        assert_eq!(prime_factors(97), [97]);
+        assert_eq!(prime_factors(1_000_000), [2, 5]);
+    }
+
+    #[test]
+    fn test_base() {
+        assert_eq!(to_base(0, 2), [0]);
+        assert_eq!(to_base(6, 2), [1, 1, 0]);
+        assert_eq!(to_base(255, 16), [15, 15]);
+        assert_eq!(from_base([1, 1, 0], 2), 6);
+        assert_eq!(from_base([15, 15], 16), 255);
+    }
+
+    #[test_case(1 => "1")]
+    #[test_case(3 => "1=")]
+    #[test_case(2022 => "1=11-2")]
+    #[test_case(314_159_265 => "1121-1110-1=0")]
+    #[test_case(-3 => "-2")]
+    fn test_snafu(n: i64) -> String {
+        let snafu = Alphabet::new(b"=-012", -2);
+        let encoded = snafu.encode(n).unwrap();
+        assert_eq!(snafu.decode(&encoded), Some(n));
+        encoded
+    }
+
+    #[test]
+    fn test_alphabet() {
+        assert_eq!(Alphabet::HEX.encode(255).as_deref(), Some("ff"));
+        assert_eq!(Alphabet::HEX.decode("ff"), Some(255));
+        assert_eq!(Alphabet::BINARY.encode(0).as_deref(), Some("0"));
+        assert_eq!(Alphabet::DECIMAL.encode(-1), None);
+        assert_eq!(Alphabet::DECIMAL.decode("12a"), None);
+        assert_eq!(Alphabet::DECIMAL.decode(""), None);
+    }
+}
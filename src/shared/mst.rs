@@ -0,0 +1,97 @@
+//! Minimum spanning forests by Kruskal's algorithm, one merge at a time.
+
+use super::UnionFind;
+
+/// An edge that joined two components.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Merge<W> {
+    pub weight: W,
+    pub a: usize,
+    pub b: usize,
+    /// Edges considered so far, this one included.
+    pub edges: usize,
+}
+
+/// Runs Kruskal's algorithm over edges `(weight, a, b)` given in increasing order of weight,
+/// yielding each edge that joins two components.
+///
+/// Stops once every node is in one component; the components so far are available through
+/// [`Kruskal::components`].
+#[derive(Debug, Clone)]
+pub struct Kruskal<I> {
+    edges: I,
+    components: UnionFind,
+    considered: usize,
+}
+
+impl<I> Kruskal<I> {
+    pub fn new(len: usize, edges: impl IntoIterator<IntoIter = I>) -> Self {
+        Self {
+            edges: edges.into_iter(),
+            components: UnionFind::new(len),
+            considered: 0,
+        }
+    }
+
+    #[must_use]
+    pub const fn components(&self) -> &UnionFind {
+        &self.components
+    }
+
+    /// Whether every node is in one component.
+    #[must_use]
+    pub const fn is_spanning(&self) -> bool {
+        self.components.num_roots() <= 1
+    }
+}
+
+impl<W, I: Iterator<Item = (W, usize, usize)>> Iterator for Kruskal<I> {
+    type Item = Merge<W>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_spanning() {
+            return None;
+        }
+        for (weight, a, b) in self.edges.by_ref() {
+            self.considered += 1;
+            if self.components.union(a, b) {
+                return Some(Merge {
+                    weight,
+                    a,
+                    b,
+                    edges: self.considered,
+                });
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kruskal() {
+        let edges = [
+            (1, 0, 1),
+            (2, 1, 2),
+            (3, 0, 2),
+            (4, 3, 4),
+            (5, 2, 3),
+            (6, 0, 4),
+        ];
+        let mut kruskal = Kruskal::new(5, edges);
+        let merges = kruskal
+            .by_ref()
+            .map(|m| (m.weight, m.edges))
+            .collect::<Vec<_>>();
+        assert_eq!(merges, [(1, 1), (2, 2), (4, 4), (5, 5)]);
+        assert!(kruskal.is_spanning());
+
+        let mut forest = Kruskal::new(4, [(1, 0, 1), (2, 2, 3)]);
+        assert_eq!(forest.by_ref().count(), 2);
+        assert!(!forest.is_spanning());
+        assert_eq!(forest.components().num_roots(), 2);
+    }
+}
@@ -0,0 +1,72 @@
+//! Data-parallel helpers that use rayon with the `parallel` feature, and plain iterators
+//! otherwise.
+
+use std::iter::Sum;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Applies `f` to every item, keeping the order.
+pub fn map<T, U, F>(items: &[T], f: F) -> Vec<U>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(&T) -> U + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    return items.par_iter().map(f).collect();
+    #[cfg(not(feature = "parallel"))]
+    return items.iter().map(f).collect();
+}
+
+/// The sum of `f` over every item.
+pub fn sum<T, S, F>(items: &[T], f: F) -> S
+where
+    T: Sync,
+    S: Send + Sum,
+    F: Fn(&T) -> S + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    return items.par_iter().map(f).sum();
+    #[cfg(not(feature = "parallel"))]
+    return items.iter().map(f).sum();
+}
+
+/// The largest value of `f` over the indices `0..len`, or `None` if there are none.
+pub fn max_by_index<S, F>(len: usize, f: F) -> Option<S>
+where
+    S: Send + Ord,
+    F: Fn(usize) -> S + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    return (0..len).into_par_iter().map(f).max();
+    #[cfg(not(feature = "parallel"))]
+    return (0..len).map(f).max();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map() {
+        let items = (0..1000).collect::<Vec<u64>>();
+        let squares = map(&items, |&x| x * x);
+        let expected = items.iter().map(|&x| x * x).collect::<Vec<_>>();
+        assert_eq!(squares, expected);
+    }
+
+    #[test]
+    fn test_sum() {
+        let items = (1..=100).collect::<Vec<u64>>();
+        assert_eq!(sum(&items, |&x| x), 5050);
+        assert_eq!(sum(&[] as &[u64], |&x| x), 0);
+    }
+
+    #[test]
+    fn test_max_by_index() {
+        let items = [3, 9, 2, 9, 4];
+        assert_eq!(max_by_index(items.len(), |ix| items[ix]), Some(9));
+        assert_eq!(max_by_index(0, |ix| items[ix]), None);
+    }
+}
@@ -0,0 +1,207 @@
+use std::borrow::Cow;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use super::scan::parse_digits;
+
+/// Every integer embedded in `text`, in order of appearance.
+///
+/// A `-` directly before the digits is treated as a sign, unless it follows a
+/// letter or digit (so `"11-22"` yields `11` and `22`). If `T` can not hold
+/// a negative value, the sign is ignored. Numbers that don't fit in `T` are
+/// skipped.
+pub fn extract_integers<T: TryFrom<i128>>(text: &str) -> impl Iterator<Item = T> {
+    let bytes = text.as_bytes();
+    let mut pos = 0;
+    std::iter::from_fn(move || {
+        while pos < bytes.len() {
+            let start = pos;
+            while pos < bytes.len() && bytes[pos].is_ascii_digit() {
+                pos += 1;
+            }
+            if start == pos {
+                pos += 1;
+                continue;
+            }
+            let Some(magnitude) = parse_digits(&bytes[start..pos]).map(i128::from) else {
+                continue;
+            };
+            let negative = start > 0
+                && bytes[start - 1] == b'-'
+                && (start < 2 || !bytes[start - 2].is_ascii_alphanumeric());
+            if negative && let Ok(value) = T::try_from(-magnitude) {
+                return Some(value);
+            }
+            if let Ok(value) = T::try_from(magnitude) {
+                return Some(value);
+            }
+        }
+        None
+    })
+}
+
+/// Splits `input` into blocks separated by one or more blank lines.
+///
+/// Accepts both `\n` and `\r\n` line endings. Leading and trailing line
+/// breaks are removed from each block, but indentation is kept.
+pub fn input_blocks(input: &str) -> impl Iterator<Item = &str> {
+    let mut rest = input;
+    std::iter::from_fn(move || {
+        let mut offset = 0;
+        let mut start = None;
+        for line in rest.split_inclusive('\n') {
+            let is_blank = line.trim().is_empty();
+            match start {
+                None if !is_blank => start = Some(offset),
+                Some(start) if is_blank => {
+                    let block = &rest[start..offset];
+                    rest = &rest[offset..];
+                    return Some(block.trim_end_matches(['\r', '\n']));
+                }
+                _ => {}
+            }
+            offset += line.len();
+        }
+        let block = &rest[start?..];
+        rest = "";
+        Some(block.trim_end_matches(['\r', '\n']))
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ParseArrayError<E> {
+    #[error("Expected {expected} items, found {found}")]
+    WrongLength { expected: usize, found: usize },
+    #[error(transparent)]
+    InvalidItem(E),
+}
+
+/// Parses exactly `N` items from `items` into an array.
+pub fn parse_array<'a, T: FromStr, const N: usize>(
+    items: impl IntoIterator<Item = &'a str>,
+) -> Result<[T; N], ParseArrayError<T::Err>> {
+    let mut items = items.into_iter();
+    let values = items
+        .by_ref()
+        .take(N)
+        .map(str::parse)
+        .collect::<Result<Vec<T>, _>>()
+        .map_err(ParseArrayError::InvalidItem)?;
+    let found = values.len() + items.count();
+    values
+        .try_into()
+        .ok()
+        .filter(|_| found == N)
+        .ok_or(ParseArrayError::WrongLength { expected: N, found })
+}
+
+/// Normalizes raw puzzle input before parsing.
+///
+/// Strips a leading byte order mark, converts `\r\n` line endings to `\n`, and
+/// drops trailing blank lines and the final line break. Trailing spaces on
+/// each line are kept, since some grids depend on them.
+#[must_use]
+pub fn normalize_input(input: &str) -> Cow<'_, str> {
+    normalize(input, false)
+}
+
+/// Like [`normalize_input`], but also removes trailing whitespace from every line.
+#[must_use]
+pub fn normalize_input_trimmed(input: &str) -> Cow<'_, str> {
+    normalize(input, true)
+}
+
+fn normalize(input: &str, trim_lines: bool) -> Cow<'_, str> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let is_normalized = !input.contains('\r')
+        && !input.ends_with('\n')
+        && input
+            .lines()
+            .next_back()
+            .is_none_or(|last| !last.trim().is_empty())
+        && (!trim_lines
+            || input
+                .lines()
+                .all(|line| !line.ends_with(char::is_whitespace)));
+    if is_normalized {
+        return Cow::Borrowed(input);
+    }
+    let mut lines = input
+        .lines()
+        .map(|line| if trim_lines { line.trim_end() } else { line })
+        .collect::<Vec<_>>();
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+    Cow::Owned(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_integers() {
+        let line = "Sensor at x=2, y=-18: closest beacon is at x=-2, y=15";
+        assert_eq!(
+            extract_integers::<i32>(line).collect::<Vec<_>>(),
+            [2, -18, -2, 15]
+        );
+        assert_eq!(
+            extract_integers::<u32>(line).collect::<Vec<_>>(),
+            [2, 18, 2, 15]
+        );
+        assert_eq!(
+            extract_integers::<i64>("11-22,95-115").collect::<Vec<_>>(),
+            [11, 22, 95, 115]
+        );
+        assert_eq!(
+            extract_integers::<u8>("12x5: 1 300 +2").collect::<Vec<_>>(),
+            [12, 5, 1, 2]
+        );
+        assert_eq!(extract_integers::<u8>("").count(), 0);
+    }
+
+    #[test]
+    fn test_normalize_input() {
+        assert!(matches!(normalize_input("a \nb"), Cow::Borrowed("a \nb")));
+        assert_eq!(normalize_input("\u{feff}a \r\nb\r\n\r\n  \n"), "a \nb");
+        assert_eq!(normalize_input_trimmed("a \r\nb\t\n"), "a\nb");
+        assert_eq!(normalize_input("\n\n"), "");
+    }
+
+    #[test]
+    fn test_parse_array() {
+        assert_eq!(parse_array::<u32, 3>("1,2,3".split(',')), Ok([1, 2, 3]));
+        assert_eq!(
+            parse_array::<u32, 3>("1,2".split(',')),
+            Err(ParseArrayError::WrongLength {
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            parse_array::<u32, 3>("1,2,3,4,5".split(',')),
+            Err(ParseArrayError::WrongLength {
+                expected: 3,
+                found: 5
+            })
+        );
+        assert!(matches!(
+            parse_array::<u32, 3>("1,x,3".split(',')),
+            Err(ParseArrayError::InvalidItem(_))
+        ));
+    }
+
+    #[test]
+    fn test_input_blocks() {
+        let input = "\r\na\r\n b\r\n\r\n\r\nc\nd\n\n  \ne\n\n";
+        assert_eq!(
+            input_blocks(input).collect::<Vec<_>>(),
+            ["a\r\n b", "c\nd", "e"]
+        );
+        assert_eq!(input_blocks("a").collect::<Vec<_>>(), ["a"]);
+        assert_eq!(input_blocks("\n\n").count(), 0);
+    }
+}
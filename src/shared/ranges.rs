@@ -0,0 +1,190 @@
+//! Sets of values stored as sorted, disjoint inclusive ranges.
+
+use std::iter::Sum;
+use std::ops::{Add, Deref, Sub};
+
+/// A union of inclusive ranges, kept sorted with overlapping ranges merged.
+///
+/// Ranges that merely touch, like `1..=3` and `4..=6`, are kept apart; they cover no value
+/// twice.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeSet<T> {
+    ranges: Vec<(T, T)>,
+}
+
+impl<T> Default for RangeSet<T> {
+    fn default() -> Self {
+        Self { ranges: Vec::new() }
+    }
+}
+
+impl<T: Copy + Ord> RangeSet<T> {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    /// Adds `start..=end`, merging it with the ranges it overlaps. Empty ranges are ignored.
+    pub fn insert(&mut self, start: T, end: T) {
+        if start > end {
+            return;
+        }
+        let first = self.ranges.partition_point(|&(_, e)| e < start);
+        let last = self.ranges.partition_point(|&(s, _)| s <= end);
+        let merged = self.ranges[first..last]
+            .iter()
+            .fold((start, end), |(lo, hi), &(s, e)| (lo.min(s), hi.max(e)));
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    /// Whether `value` is in any of the ranges.
+    #[must_use]
+    pub fn contains(&self, value: T) -> bool {
+        let ix = self.ranges.partition_point(|&(_, e)| e < value);
+        self.ranges.get(ix).is_some_and(|&(s, _)| s <= value)
+    }
+
+    /// Number of values in all the ranges together.
+    #[must_use]
+    pub fn total_len(&self) -> T
+    where
+        T: Add<Output = T> + Sub<Output = T> + From<u8> + Sum,
+    {
+        self.ranges
+            .iter()
+            .map(|&(start, end)| end - start + T::from(1))
+            .sum()
+    }
+
+    /// The values between the ranges that none of them cover, as ranges in increasing order.
+    pub fn gaps(&self) -> impl Iterator<Item = (T, T)> + '_
+    where
+        T: Add<Output = T> + Sub<Output = T> + From<u8>,
+    {
+        self.ranges
+            .windows(2)
+            .filter(|pair| pair[1].0 - pair[0].1 > T::from(1))
+            .map(|pair| (pair[0].1 + T::from(1), pair[1].0 - T::from(1)))
+    }
+
+    /// How far `value` is from the closest range, 0 if it is in one, or `None` if the set is
+    /// empty.
+    #[must_use]
+    pub fn distance(&self, value: T) -> Option<T>
+    where
+        T: Sub<Output = T>,
+    {
+        let ix = self.ranges.partition_point(|&(_, e)| e < value);
+        // Zero when the range contains `value`.
+        let after = self.ranges.get(ix).map(|&(s, _)| s - s.min(value));
+        let before = ix.checked_sub(1).map(|ix| value - self.ranges[ix].1);
+        match (before, after) {
+            (Some(before), Some(after)) => Some(before.min(after)),
+            (before, after) => before.or(after),
+        }
+    }
+
+    /// The disjoint ranges, in increasing order.
+    #[must_use]
+    pub fn as_slice(&self) -> &[(T, T)] {
+        &self.ranges
+    }
+}
+
+impl<T> Deref for RangeSet<T> {
+    type Target = [(T, T)];
+
+    fn deref(&self) -> &Self::Target {
+        &self.ranges
+    }
+}
+
+impl<T: Copy + Ord> Extend<(T, T)> for RangeSet<T> {
+    fn extend<I: IntoIterator<Item = (T, T)>>(&mut self, iter: I) {
+        for (start, end) in iter {
+            self.insert(start, end);
+        }
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<(T, T)> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = (T, T)>>(iter: I) -> Self {
+        let mut sorted: Vec<_> = iter.into_iter().filter(|&(s, e)| s <= e).collect();
+        sorted.sort_unstable();
+        let mut ranges: Vec<(T, T)> = Vec::with_capacity(sorted.len());
+        for (start, end) in sorted {
+            match ranges.last_mut() {
+                Some((_, last_end)) if start <= *last_end => *last_end = end.max(*last_end),
+                _ => ranges.push((start, end)),
+            }
+        }
+        Self { ranges }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_iter() {
+        let set: RangeSet<u32> = [(10, 14), (3, 5), (16, 20), (12, 18), (3, 5), (7, 6)]
+            .into_iter()
+            .collect();
+        assert_eq!(set.as_slice(), [(3, 5), (10, 20)]);
+        let touching: RangeSet<u32> = [(1, 3), (4, 6)].into_iter().collect();
+        assert_eq!(touching.len(), 2);
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut set = RangeSet::new();
+        set.insert(10, 14);
+        set.insert(3, 5);
+        set.insert(16, 20);
+        assert_eq!(set.as_slice(), [(3, 5), (10, 14), (16, 20)]);
+        set.insert(12, 18);
+        assert_eq!(set.as_slice(), [(3, 5), (10, 20)]);
+        set.insert(1, 30);
+        assert_eq!(set.as_slice(), [(1, 30)]);
+        set.insert(5, 4);
+        set.extend([(40, 50), (45, 60)]);
+        assert_eq!(set.as_slice(), [(1, 30), (40, 60)]);
+    }
+
+    #[test]
+    fn test_contains() {
+        let set: RangeSet<i32> = [(-5, -1), (3, 5)].into_iter().collect();
+        assert!(set.contains(-5));
+        assert!(set.contains(4));
+        assert!(!set.contains(0));
+        assert!(!set.contains(6));
+        assert!(!RangeSet::new().contains(0));
+    }
+
+    #[test]
+    fn test_gaps() {
+        let set: RangeSet<u64> = [(3, 5), (6, 8), (10, 14), (20, 20)].into_iter().collect();
+        assert!(set.gaps().eq([(9, 9), (15, 19)]));
+        assert_eq!(RangeSet::<u64>::new().gaps().count(), 0);
+    }
+
+    #[test]
+    fn test_distance() {
+        let set: RangeSet<u64> = [(3, 5), (10, 14)].into_iter().collect();
+        assert_eq!(set.distance(0), Some(3));
+        assert_eq!(set.distance(4), Some(0));
+        assert_eq!(set.distance(7), Some(2));
+        assert_eq!(set.distance(8), Some(2));
+        assert_eq!(set.distance(14), Some(0));
+        assert_eq!(set.distance(20), Some(6));
+        assert_eq!(RangeSet::new().distance(1), None);
+    }
+
+    #[test]
+    fn test_total_len() {
+        let set: RangeSet<u64> = [(3, 5), (10, 14), (16, 20), (12, 18)].into_iter().collect();
+        assert_eq!(set.total_len(), 14);
+        assert_eq!(RangeSet::<u64>::new().total_len(), 0);
+    }
+}
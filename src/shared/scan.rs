@@ -0,0 +1,105 @@
+//! Byte-level number scanning for large inputs.
+//!
+//! Digits are accumulated eight at a time with SWAR arithmetic, and field
+//! delimiters are located with `memchr`, avoiding the per-token overhead of
+//! `str::parse`.
+
+use memchr::memchr_iter;
+
+const ASCII_ZEROS: u64 = u64::from_le_bytes([b'0'; 8]);
+
+/// Whether all eight bytes of `chunk` are ASCII digits.
+const fn all_digits(chunk: u64) -> bool {
+    let low = chunk.wrapping_sub(ASCII_ZEROS);
+    let high = chunk.wrapping_add(u64::from_le_bytes([0x46; 8]));
+    (low | high) & u64::from_le_bytes([0x80; 8]) == 0
+}
+
+/// The value of eight ASCII digits loaded little-endian (first digit in the lowest byte).
+const fn eight_digits(chunk: u64) -> u64 {
+    let chunk = chunk.wrapping_sub(ASCII_ZEROS);
+    let chunk = (chunk.wrapping_mul(10) + (chunk >> 8)) & 0x00ff_00ff_00ff_00ff;
+    let chunk = (chunk.wrapping_mul(100) + (chunk >> 16)) & 0x0000_ffff_0000_ffff;
+    (chunk.wrapping_mul(10000) + (chunk >> 32)) & 0xffff_ffff
+}
+
+/// Parses a non-empty run of ASCII digits, or `None` on other bytes or overflow.
+#[must_use]
+pub fn parse_digits(mut digits: &[u8]) -> Option<u64> {
+    if digits.is_empty() {
+        return None;
+    }
+    let mut value = 0_u64;
+    while let Some((chunk, rest)) = digits.split_first_chunk::<8>() {
+        let chunk = u64::from_le_bytes(*chunk);
+        if !all_digits(chunk) {
+            return None;
+        }
+        value = value
+            .checked_mul(100_000_000)?
+            .checked_add(eight_digits(chunk))?;
+        digits = rest;
+    }
+    for &ch in digits {
+        if !ch.is_ascii_digit() {
+            return None;
+        }
+        value = value.checked_mul(10)?.checked_add(u64::from(ch - b'0'))?;
+    }
+    Some(value)
+}
+
+/// Splits `input` on `delim`. A trailing delimiter does not produce an empty last field.
+pub fn fields(input: &[u8], delim: u8) -> impl Iterator<Item = &[u8]> {
+    let mut start = 0;
+    memchr_iter(delim, input)
+        .chain((!input.is_empty() && input.last() != Some(&delim)).then_some(input.len()))
+        .map(move |end| {
+            let field = &input[start..end];
+            start = end + 1;
+            field
+        })
+}
+
+/// Parses each `delim`-separated field of `input` as an unsigned number.
+/// Fields that are not numbers yield `None`.
+pub fn uints(input: &[u8], delim: u8) -> impl Iterator<Item = Option<u64>> {
+    fields(input, delim).map(|field| parse_digits(field.trim_ascii()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_digits() {
+        for n in [
+            0,
+            7,
+            12_345_678,
+            123_456_789,
+            98_765_432_123_456_789,
+            u64::MAX,
+        ] {
+            assert_eq!(parse_digits(n.to_string().as_bytes()), Some(n));
+        }
+        assert_eq!(parse_digits(b"0000000000000042"), Some(42));
+        assert_eq!(parse_digits(b""), None);
+        assert_eq!(parse_digits(b"1234567:"), None);
+        assert_eq!(parse_digits(b"12345/78"), None);
+        assert_eq!(parse_digits(b"18446744073709551616"), None);
+    }
+
+    #[test]
+    fn test_uints() {
+        assert_eq!(
+            uints(b"1,22,333\n", b',').collect::<Vec<_>>(),
+            [Some(1), Some(22), Some(333)]
+        );
+        assert_eq!(
+            uints(b"1,,x", b',').collect::<Vec<_>>(),
+            [Some(1), None, None]
+        );
+        assert_eq!(fields(b"", b',').count(), 0);
+    }
+}
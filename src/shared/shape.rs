@@ -0,0 +1,238 @@
+//! Small shapes as bitmasks, one `u64` per row with bit `x` for column `x`: turned, flipped,
+//! trimmed, and placed on grids stored as the same kind of rows.
+
+/// Most rows or columns a [`Shape`] can have.
+pub const MAX_SIZE: u8 = 64;
+
+/// A set of cells within a `width` × `height` box: bit `x` of row `y` is the cell at column
+/// `x`. Blank rows and columns at the edges are kept until [`Shape::trim`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Shape {
+    width: u8,
+    rows: Vec<u64>,
+}
+
+impl Shape {
+    /// A `width` wide shape with the cells of `rows`, which must not reach past `width`.
+    #[must_use]
+    pub fn new(width: u8, rows: Vec<u64>) -> Self {
+        assert!(width <= MAX_SIZE && rows.len() <= usize::from(MAX_SIZE));
+        assert!(
+            rows.iter()
+                .all(|row| row.checked_shr(width.into()).unwrap_or(0) == 0),
+            "cells past the width"
+        );
+        Self { width, rows }
+    }
+
+    #[must_use]
+    pub const fn width(&self) -> u8 {
+        self.width
+    }
+
+    #[must_use]
+    #[expect(clippy::cast_possible_truncation, reason = "At most MAX_SIZE rows")]
+    pub const fn height(&self) -> u8 {
+        self.rows.len() as u8
+    }
+
+    #[must_use]
+    pub fn rows(&self) -> &[u64] {
+        &self.rows
+    }
+
+    /// Number of cells.
+    #[must_use]
+    pub fn area(&self) -> u32 {
+        self.rows.iter().map(|row| row.count_ones()).sum()
+    }
+
+    /// Column of the first cell of the top row: the cell a search filling a grid in
+    /// reading order places the shape by, once it is trimmed.
+    #[must_use]
+    #[expect(clippy::cast_possible_truncation, reason = "At most 64 columns")]
+    pub fn anchor(&self) -> u8 {
+        self.rows
+            .first()
+            .map_or(0, |row| row.trailing_zeros() as u8)
+    }
+
+    /// The shape turned a quarter clockwise.
+    #[must_use]
+    pub fn rotate(&self) -> Self {
+        let rows = (0..self.width)
+            .map(|y| {
+                // The new row `y` is the old column `y`, read from the bottom up.
+                self.rows
+                    .iter()
+                    .rev()
+                    .enumerate()
+                    .fold(0, |row, (x, old)| row | (old >> y & 1) << x)
+            })
+            .collect();
+        Self {
+            width: self.height(),
+            rows,
+        }
+    }
+
+    /// The shape flipped left to right.
+    #[must_use]
+    pub fn mirror(&self) -> Self {
+        let shift = u64::BITS - u32::from(self.width);
+        Self {
+            width: self.width,
+            rows: self
+                .rows
+                .iter()
+                .map(|row| row.reverse_bits().checked_shr(shift).unwrap_or(0))
+                .collect(),
+        }
+    }
+
+    /// The shape with its blank rows and columns at the edges removed.
+    #[must_use]
+    pub fn trim(&self) -> Self {
+        let mut rows = self
+            .rows
+            .iter()
+            .copied()
+            .skip_while(|&row| row == 0)
+            .collect::<Vec<_>>();
+        while rows.last() == Some(&0) {
+            rows.pop();
+        }
+        let columns = rows.iter().fold(0, |acc, row| acc | row);
+        if columns == 0 {
+            return Self {
+                width: 0,
+                rows: Vec::new(),
+            };
+        }
+        let left = columns.trailing_zeros();
+        for row in &mut rows {
+            *row >>= left;
+        }
+        #[expect(clippy::cast_possible_truncation, reason = "At most 64 columns")]
+        Self {
+            width: (columns >> left).ilog2() as u8 + 1,
+            rows,
+        }
+    }
+
+    /// Every distinct rotation and reflection of the shape, trimmed, starting with the shape
+    /// itself.
+    #[must_use]
+    pub fn orientations(&self) -> Vec<Self> {
+        let mut shapes = Vec::new();
+        let mut shape = self.trim();
+        for _ in 0..2 {
+            for _ in 0..4 {
+                if !shapes.contains(&shape) {
+                    shapes.push(shape.clone());
+                }
+                shape = shape.rotate();
+            }
+            shape = shape.mirror();
+        }
+        shapes
+    }
+
+    /// Whether the shape, with its top left corner at column `x` of row `y`, lies within a
+    /// `width` wide grid of `grid` rows without covering any of their set cells.
+    #[must_use]
+    pub fn fits_at(&self, grid: &[u64], width: usize, x: usize, y: usize) -> bool {
+        self.within(grid, width, x, y)
+            && (self.rows.iter())
+                .zip(&grid[y..])
+                .all(|(&row, taken)| row << x & taken == 0)
+    }
+
+    /// Whether every cell of the shape, with its top left corner at column `x` of row `y`, is
+    /// set in a `width` wide grid of `grid` rows: where a pattern shows up in a picture.
+    #[must_use]
+    pub fn found_at(&self, grid: &[u64], width: usize, x: usize, y: usize) -> bool {
+        self.within(grid, width, x, y)
+            && (self.rows.iter())
+                .zip(&grid[y..])
+                .all(|(&row, set)| row << x & !set == 0)
+    }
+
+    /// Flips the cells of `grid` under the shape with its top left corner at column `x` of
+    /// row `y`: placing it where it [fits](Self::fits_at), or taking it back off.
+    pub fn toggle_at(&self, grid: &mut [u64], x: usize, y: usize) {
+        for (taken, &row) in grid[y..].iter_mut().zip(&self.rows) {
+            *taken ^= row << x;
+        }
+    }
+
+    fn within(&self, grid: &[u64], width: usize, x: usize, y: usize) -> bool {
+        x + usize::from(self.width) <= width && y + self.rows.len() <= grid.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ```text
+    /// ###.
+    /// #...
+    /// ```
+    fn ell() -> Shape {
+        Shape::new(4, vec![0b0111, 0b0001])
+    }
+
+    #[test]
+    fn test_rotate() {
+        // ##
+        // .#
+        // .#
+        // ..
+        assert_eq!(ell().rotate(), Shape::new(2, vec![0b11, 0b10, 0b10, 0b00]));
+        assert_eq!((0..4).fold(ell(), |shape, _| shape.rotate()), ell());
+    }
+
+    #[test]
+    fn test_mirror() {
+        assert_eq!(ell().mirror(), Shape::new(4, vec![0b1110, 0b1000]));
+        assert_eq!(ell().mirror().mirror(), ell());
+    }
+
+    #[test]
+    fn test_trim() {
+        let shape = Shape::new(3, vec![0b000, 0b110, 0b100]);
+        let trimmed = shape.trim();
+        assert_eq!(trimmed, Shape::new(2, vec![0b11, 0b10]));
+        assert_eq!((trimmed.anchor(), trimmed.area()), (0, 3));
+        assert_eq!(Shape::new(2, vec![0, 0]).trim(), Shape::new(0, vec![]));
+    }
+
+    #[test]
+    fn test_orientations() {
+        assert_eq!(ell().orientations().len(), 8);
+        assert_eq!(ell().orientations()[0], ell().trim());
+        let square = Shape::new(2, vec![0b11, 0b11]);
+        assert_eq!(square.orientations(), [square]);
+        let tee = Shape::new(3, vec![0b111, 0b010]);
+        assert_eq!(tee.orientations().len(), 4);
+        let ess = Shape::new(3, vec![0b110, 0b011]);
+        assert_eq!(ess.orientations().len(), 4);
+    }
+
+    #[test]
+    fn test_place() {
+        let mut grid = vec![0b00000, 0b00010, 0b00000];
+        assert!(ell().fits_at(&grid, 5, 0, 0));
+        // Over the taken cell, past the right edge, and past the bottom.
+        assert!(!ell().fits_at(&grid, 5, 1, 0));
+        assert!(!ell().fits_at(&grid, 5, 2, 0));
+        assert!(!ell().fits_at(&grid, 5, 0, 2));
+        ell().toggle_at(&mut grid, 0, 0);
+        assert_eq!(grid, [0b00111, 0b00011, 0b00000]);
+        assert!(ell().found_at(&grid, 5, 0, 0));
+        assert!(!ell().found_at(&grid, 5, 1, 0));
+        ell().toggle_at(&mut grid, 0, 0);
+        assert_eq!(grid, [0b00000, 0b00010, 0b00000]);
+    }
+}
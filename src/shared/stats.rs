@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// The lower median of `values`. Reorders the slice.
+pub fn median<T: Ord + Copy>(values: &mut [T]) -> Option<T> {
+    if values.is_empty() {
+        return None;
+    }
+    let mid = (values.len() - 1) / 2;
+    let (_, &mut median, _) = values.select_nth_unstable(mid);
+    Some(median)
+}
+
+/// Arithmetic mean of `values`.
+pub fn mean<T: Copy + Into<f64>>(values: &[T]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let sum = values.iter().map(|&v| v.into()).sum::<f64>();
+    #[expect(clippy::cast_precision_loss, reason = "Slices are far below 2^52")]
+    Some(sum / values.len() as f64)
+}
+
+/// Number of occurrences of each distinct value.
+pub fn counts<T: Hash + Eq>(values: impl IntoIterator<Item = T>) -> HashMap<T, usize> {
+    let mut counts = HashMap::new();
+    for value in values {
+        *counts.entry(value).or_default() += 1;
+    }
+    counts
+}
+
+/// The most common value, with its count. Ties are broken by the smallest value.
+pub fn max_by_count<T: Hash + Ord>(values: impl IntoIterator<Item = T>) -> Option<(T, usize)> {
+    counts(values)
+        .into_iter()
+        .max_by(|(v1, c1), (v2, c2)| c1.cmp(c2).then_with(|| v2.cmp(v1)))
+}
+
+/// The least common value, with its count. Ties are broken by the smallest value.
+pub fn min_by_count<T: Hash + Ord>(values: impl IntoIterator<Item = T>) -> Option<(T, usize)> {
+    counts(values)
+        .into_iter()
+        .min_by(|(v1, c1), (v2, c2)| c1.cmp(c2).then_with(|| v1.cmp(v2)))
+}
+
+/// The most common value.
+pub fn mode<T: Hash + Ord>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    max_by_count(values).map(|(value, _)| value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median::<u32>(&mut []), None);
+        assert_eq!(median(&mut [16, 1, 2, 0, 4, 2, 7, 1, 2, 14]), Some(2));
+        assert_eq!(median(&mut [3, 1, 2]), Some(2));
+    }
+
+    #[test]
+    fn test_mean() {
+        assert_eq!(mean::<u32>(&[]), None);
+        assert_eq!(mean(&[16, 1, 2, 0, 4, 2, 7, 1, 2, 14]), Some(4.9));
+    }
+
+    #[test]
+    fn test_counts() {
+        let values = [3, 1, 3, 2, 1, 3];
+        assert_eq!(mode(values), Some(3));
+        assert_eq!(max_by_count(values), Some((3, 3)));
+        assert_eq!(min_by_count(values), Some((2, 1)));
+        assert_eq!(max_by_count([2, 1]), Some((1, 1)));
+    }
+}
@@ -0,0 +1,547 @@
+//! Animating grid simulations.
+//!
+//! Simulations take an [`Animator`] and hand it a frame after each step. Solutions pass `()`,
+//! which never builds the frames; `aoc animate` passes a [`Terminal`] to play them back.
+//!
+//! Point clouds can be exported with [`export_points3d`] for inspection in a 3D viewer.
+//!
+//! With the `image` feature, grids can also be exported as PNG images and animated GIFs, which
+//! scale to full-size inputs where the terminal doesn't.
+//!
+//! With the `svg` feature, shapes can be drawn as an [`Svg`], for geometry too spread out for
+//! a grid of pixels.
+
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use super::Grid;
+
+/// ANSI escape: clear the screen and move the cursor to the top left.
+const CLEAR: &str = "\x1b[2J\x1b[H";
+
+/// Receives the frames of a simulation.
+pub trait Animator<T> {
+    /// Whether frames are wanted at all. Simulations may skip bookkeeping that only feeds frames.
+    fn enabled(&self) -> bool {
+        true
+    }
+
+    /// Records a frame. `frame` is only called if the frame is actually kept.
+    fn frame(&mut self, frame: impl FnOnce() -> Grid<T>);
+}
+
+impl<T> Animator<T> for () {
+    fn enabled(&self) -> bool {
+        false
+    }
+
+    fn frame(&mut self, _frame: impl FnOnce() -> Grid<T>) {}
+}
+
+/// Collects every frame.
+impl<T> Animator<T> for Vec<Grid<T>> {
+    fn frame(&mut self, frame: impl FnOnce() -> Grid<T>) {
+        self.push(frame());
+    }
+}
+
+/// Redraws each frame in place on a terminal, pausing `delay` between frames.
+#[derive(Debug)]
+pub struct Terminal<W> {
+    out: W,
+    delay: Duration,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> Terminal<W> {
+    pub const fn new(out: W, delay: Duration) -> Self {
+        Self {
+            out,
+            delay,
+            error: None,
+        }
+    }
+
+    /// Returns the first error encountered while drawing, if any.
+    pub fn finish(self) -> io::Result<()> {
+        self.error.map_or(Ok(()), Err)
+    }
+}
+
+impl<W: Write, T: Display> Animator<T> for Terminal<W> {
+    fn enabled(&self) -> bool {
+        self.error.is_none()
+    }
+
+    fn frame(&mut self, frame: impl FnOnce() -> Grid<T>) {
+        if self.error.is_some() {
+            return;
+        }
+        let result = write!(self.out, "{CLEAR}{}", frame()).and_then(|()| self.out.flush());
+        if let Err(err) = result {
+            self.error = Some(err);
+            return;
+        }
+        thread::sleep(self.delay);
+    }
+}
+
+/// A color as red, green and blue components.
+pub type Rgb = [u8; 3];
+
+/// A distinct color for each of a number of categories, such as the components of a graph.
+/// Consecutive indices get hues far apart.
+#[must_use]
+#[expect(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    reason = "The hue is within 0..6 and the components within 0..=1"
+)]
+pub fn category_color(index: usize) -> Rgb {
+    const GOLDEN_RATIO_CONJUGATE: f64 = 0.618_033_988_749_895;
+    #[expect(clippy::cast_precision_loss, reason = "Only the fraction matters")]
+    let hue = (index as f64 * GOLDEN_RATIO_CONJUGATE).fract() * 6.0;
+    let x = 1.0 - (hue % 2.0 - 1.0).abs();
+    let rgb = match hue as u8 {
+        0 => [1.0, x, 0.0],
+        1 => [x, 1.0, 0.0],
+        2 => [0.0, 1.0, x],
+        3 => [0.0, x, 1.0],
+        4 => [x, 0.0, 1.0],
+        _ => [1.0, 0.0, x],
+    };
+    rgb.map(|c: f64| (c * 255.0).round() as u8)
+}
+
+/// Writes colored points as an ASCII PLY point cloud.
+pub fn write_ply(mut out: impl Write, points: &[([f64; 3], Rgb)]) -> io::Result<()> {
+    writeln!(
+        out,
+        "ply\nformat ascii 1.0\nelement vertex {}",
+        points.len()
+    )?;
+    for axis in ["x", "y", "z"] {
+        writeln!(out, "property double {axis}")?;
+    }
+    for channel in ["red", "green", "blue"] {
+        writeln!(out, "property uchar {channel}")?;
+    }
+    writeln!(out, "end_header")?;
+    for ([x, y, z], [r, g, b]) in points {
+        writeln!(out, "{x} {y} {z} {r} {g} {b}")?;
+    }
+    out.flush()
+}
+
+/// Writes colored points as OBJ vertices, with colors in the common `v x y z r g b` extension.
+pub fn write_obj(mut out: impl Write, points: &[([f64; 3], Rgb)]) -> io::Result<()> {
+    for ([x, y, z], color) in points {
+        let [r, g, b] = color.map(|c| f64::from(c) / 255.0);
+        writeln!(out, "v {x} {y} {z} {r:.4} {g:.4} {b:.4}")?;
+    }
+    out.flush()
+}
+
+/// Writes colored points to `path`, as PLY or OBJ depending on the extension.
+pub fn export_points3d(path: &Path, points: &[([f64; 3], Rgb)]) -> io::Result<()> {
+    let extension = path.extension().and_then(|ext| ext.to_str());
+    let write = match extension.map(str::to_ascii_lowercase).as_deref() {
+        Some("ply") => write_ply,
+        Some("obj") => write_obj,
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Unknown point cloud format {}", path.display()),
+            ));
+        }
+    };
+    write(BufWriter::new(File::create(path)?), points)
+}
+
+/// A color on a black-red-yellow-white heat scale, for `heat` in `0.0..=1.0`.
+#[must_use]
+#[expect(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    reason = "Components are clamped to 0..=255"
+)]
+pub fn heatmap(heat: f64) -> Rgb {
+    let heat = heat.clamp(0.0, 1.0) * 3.0;
+    [heat, heat - 1.0, heat - 2.0].map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+}
+
+/// The average of each `scale`×`scale` block of colors, as rows.
+fn downsample(colors: &[Rgb], width: usize, scale: usize) -> Vec<Vec<Rgb>> {
+    colors
+        .chunks(width)
+        .collect::<Vec<_>>()
+        .chunks(scale)
+        .map(|rows| {
+            (0..width.div_ceil(scale))
+                .map(|block| {
+                    let cols = block * scale..width.min((block + 1) * scale);
+                    let mut sum = [0_usize; 3];
+                    let mut count = 0;
+                    for color in rows.iter().flat_map(|row| &row[cols.clone()]) {
+                        for (s, &c) in sum.iter_mut().zip(color) {
+                            *s += usize::from(c);
+                        }
+                        count += 1;
+                    }
+                    sum.map(|s| u8::try_from(s / count).expect("Average of u8"))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Draws `grid` with 24-bit ANSI colors, two rows of tiles per line of text.
+///
+/// Grids wider than `max_width` are downsampled by averaging square blocks of tiles.
+pub fn write_colored<T>(
+    mut out: impl Write,
+    grid: &Grid<T>,
+    color: impl Fn(&T) -> Rgb,
+    max_width: usize,
+) -> io::Result<()> {
+    let colors = grid.data.iter().map(color).collect::<Vec<_>>();
+    let scale = grid.width.div_ceil(max_width.max(1)).max(1);
+    let rows = downsample(&colors, grid.width.max(1), scale);
+    for pair in rows.chunks(2) {
+        for (col, [r, g, b]) in pair[0].iter().enumerate() {
+            write!(out, "\x1b[38;2;{r};{g};{b}m")?;
+            match pair.get(1) {
+                Some(bottom) => {
+                    let [r, g, b] = bottom[col];
+                    write!(out, "\x1b[48;2;{r};{g};{b}m▀")?;
+                }
+                None => write!(out, "\x1b[49m▀")?,
+            }
+        }
+        writeln!(out, "\x1b[0m")?;
+    }
+    out.flush()
+}
+
+/// Prints `grid` in color to stdout, downsampled to fit the terminal width.
+///
+/// The width is taken from `COLUMNS`, defaulting to 80.
+pub fn print_colored<T>(grid: &Grid<T>, color: impl Fn(&T) -> Rgb) -> io::Result<()> {
+    let columns = std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .unwrap_or(80);
+    write_colored(io::stdout().lock(), grid, color, columns)
+}
+
+/// A default palette for tiles drawn as characters: `.` and space are black, and every other
+/// character gets a bright color of its own.
+#[cfg(feature = "image")]
+pub fn glyph_palette<T: Display>(tile: &T) -> Rgb {
+    const COLORS: [Rgb; 6] = [
+        [0xff, 0xff, 0xff],
+        [0xff, 0xd7, 0x00],
+        [0x00, 0xbf, 0xff],
+        [0xff, 0x45, 0x00],
+        [0x32, 0xcd, 0x32],
+        [0xda, 0x70, 0xd6],
+    ];
+    let glyph = tile.to_string();
+    if matches!(glyph.as_str(), "." | " ") {
+        return [0, 0, 0];
+    }
+    let hash = glyph.chars().fold(0, |acc, ch| acc ^ u32::from(ch));
+    COLORS[hash as usize % COLORS.len()]
+}
+
+/// Draws `grid` as an image with one pixel per tile.
+#[cfg(feature = "image")]
+fn to_image<T>(grid: &Grid<T>, palette: impl Fn(&T) -> Rgb) -> image::RgbImage {
+    let width = u32::try_from(grid.width()).expect("Grid fits in an image");
+    let height = u32::try_from(grid.height()).expect("Grid fits in an image");
+    image::RgbImage::from_fn(width, height, |x, y| {
+        image::Rgb(palette(&grid[super::Pos::new(y as usize, x as usize)]))
+    })
+}
+
+/// Writes `grid` to `path` as a PNG with one pixel per tile, colored by `palette`.
+#[cfg(feature = "image")]
+pub fn render_png<T>(
+    grid: &Grid<T>,
+    palette: impl Fn(&T) -> Rgb,
+    path: &Path,
+) -> image::ImageResult<()> {
+    to_image(grid, palette).save_with_format(path, image::ImageFormat::Png)
+}
+
+/// Encodes frames into a looping animated GIF, showing each for `delay`.
+#[cfg(feature = "image")]
+pub struct Gif<W: Write, P> {
+    encoder: image::codecs::gif::GifEncoder<W>,
+    palette: P,
+    delay: image::Delay,
+    error: Option<image::ImageError>,
+}
+
+#[cfg(feature = "image")]
+impl<W: Write, P> Gif<W, P> {
+    pub fn new(out: W, palette: P, delay: Duration) -> Self {
+        let mut encoder = image::codecs::gif::GifEncoder::new(out);
+        let error = encoder
+            .set_repeat(image::codecs::gif::Repeat::Infinite)
+            .err();
+        Self {
+            encoder,
+            palette,
+            delay: image::Delay::from_saturating_duration(delay),
+            error,
+        }
+    }
+
+    /// Returns the first error encountered while encoding, if any.
+    pub fn finish(self) -> image::ImageResult<()> {
+        self.error.map_or(Ok(()), Err)
+    }
+}
+
+#[cfg(feature = "image")]
+impl<W: Write, T, P: Fn(&T) -> Rgb> Animator<T> for Gif<W, P> {
+    fn enabled(&self) -> bool {
+        self.error.is_none()
+    }
+
+    fn frame(&mut self, frame: impl FnOnce() -> Grid<T>) {
+        if self.error.is_some() {
+            return;
+        }
+        let image = image::DynamicImage::from(to_image(&frame(), &self.palette)).into_rgba8();
+        let frame = image::Frame::from_parts(image, 0, 0, self.delay);
+        self.error = self.encoder.encode_frame(frame).err();
+    }
+}
+
+/// A vector drawing of filled and outlined shapes, written as SVG.
+///
+/// Coordinates are in the drawing's own units, with y pointing down; outlines stay the same
+/// width however far the drawing is zoomed.
+#[cfg(feature = "svg")]
+#[derive(Debug, Clone)]
+pub struct Svg {
+    view: [f64; 4],
+    body: String,
+}
+
+#[cfg(feature = "svg")]
+impl Svg {
+    /// An empty drawing showing the area from `min` spanning `size`.
+    #[must_use]
+    pub const fn new(min: [f64; 2], size: [f64; 2]) -> Self {
+        Self {
+            view: [min[0], min[1], size[0], size[1]],
+            body: String::new(),
+        }
+    }
+
+    /// Adds a rectangle from `min` spanning `size`.
+    pub fn rect(&mut self, min: [f64; 2], size: [f64; 2], fill: Option<Rgb>, stroke: Option<Rgb>) {
+        use std::fmt::Write;
+        let ([x, y], [width, height]) = (min, size);
+        let style = Self::style(fill, stroke);
+        writeln!(
+            self.body,
+            r#"<rect x="{x}" y="{y}" width="{width}" height="{height}" {style}/>"#
+        )
+        .expect("Writing to a String can't fail");
+    }
+
+    /// Adds a closed polygon through `points`.
+    pub fn polygon(&mut self, points: &[[f64; 2]], fill: Option<Rgb>, stroke: Option<Rgb>) {
+        use std::fmt::Write;
+        let points = points
+            .iter()
+            .map(|[x, y]| format!("{x},{y}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let style = Self::style(fill, stroke);
+        writeln!(self.body, r#"<polygon points="{points}" {style}/>"#)
+            .expect("Writing to a String can't fail");
+    }
+
+    fn style(fill: Option<Rgb>, stroke: Option<Rgb>) -> String {
+        let color = |color: Option<Rgb>| {
+            color.map_or_else(
+                || "none".to_string(),
+                |[r, g, b]| format!("#{r:02x}{g:02x}{b:02x}"),
+            )
+        };
+        format!(
+            r#"fill="{}" stroke="{}" stroke-width="1.5" vector-effect="non-scaling-stroke""#,
+            color(fill),
+            color(stroke)
+        )
+    }
+
+    /// Writes the drawing as a standalone SVG document.
+    pub fn write(&self, mut out: impl Write) -> io::Result<()> {
+        let [x, y, width, height] = self.view;
+        writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{x} {y} {width} {height}">"#
+        )?;
+        out.write_all(self.body.as_bytes())?;
+        writeln!(out, "</svg>")?;
+        out.flush()
+    }
+
+    /// Writes the drawing to `path`.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        self.write(BufWriter::new(File::create(path)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_collect() {
+        let mut frames = Vec::new();
+        frames.frame(|| Grid::new(vec![1, 2], 2, 1));
+        frames.frame(|| Grid::new(vec![3, 4], 2, 1));
+        let rendered = frames.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(rendered, ["12\n", "34\n"]);
+    }
+
+    #[test]
+    fn test_terminal() {
+        let mut terminal = Terminal::new(Vec::new(), Duration::ZERO);
+        terminal.frame(|| Grid::new(vec!['a', 'b'], 1, 2));
+        terminal.frame(|| Grid::new(vec!['c', 'd'], 1, 2));
+        assert_eq!(
+            String::from_utf8(terminal.out).unwrap(),
+            format!("{CLEAR}a\nb\n{CLEAR}c\nd\n")
+        );
+    }
+
+    #[test]
+    fn test_disabled() {
+        let mut animator = ();
+        assert!(!Animator::<u8>::enabled(&animator));
+        animator.frame(|| -> Grid<u8> { unreachable!() });
+    }
+
+    #[test]
+    fn test_category_color() {
+        assert_eq!(category_color(0), [255, 0, 0]);
+        let colors = (0..8).map(category_color).collect::<HashSet<_>>();
+        assert_eq!(colors.len(), 8);
+    }
+
+    #[test]
+    fn test_heatmap() {
+        assert_eq!(heatmap(0.0), [0, 0, 0]);
+        assert_eq!(heatmap(0.5), [255, 128, 0]);
+        assert_eq!(heatmap(1.0), [255, 255, 255]);
+        assert_eq!(heatmap(7.0), [255, 255, 255]);
+    }
+
+    #[test]
+    fn test_write_colored() {
+        let grid = Grid::new(vec![0_u8, 255, 255, 0, 0, 0], 2, 3);
+        let mut out = Vec::new();
+        write_colored(&mut out, &grid, |&v| [v, 0, 0], 80).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1b[38;2;0;0;0m\x1b[48;2;255;0;0m▀\x1b[38;2;255;0;0m\x1b[48;2;0;0;0m▀\x1b[0m\n\
+             \x1b[38;2;0;0;0m\x1b[49m▀\x1b[38;2;0;0;0m\x1b[49m▀\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_write_colored_downsampled() {
+        let grid = Grid::new(vec![0_u8, 100, 200, 50, 10, 20, 30, 40], 4, 2);
+        let mut out = Vec::new();
+        write_colored(&mut out, &grid, |&v| [v, v, v], 2).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1b[38;2;32;32;32m\x1b[49m▀\x1b[38;2;80;80;80m\x1b[49m▀\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_write_points() {
+        let points = [
+            ([1.0, 2.0, 3.0], [255, 0, 0]),
+            ([4.0, 5.5, 6.0], [0, 0, 255]),
+        ];
+        let mut ply = Vec::new();
+        write_ply(&mut ply, &points).unwrap();
+        let ply = String::from_utf8(ply).unwrap();
+        assert!(ply.starts_with("ply\nformat ascii 1.0\nelement vertex 2\n"));
+        assert!(ply.ends_with("end_header\n1 2 3 255 0 0\n4 5.5 6 0 0 255\n"));
+        let mut obj = Vec::new();
+        write_obj(&mut obj, &points).unwrap();
+        assert_eq!(
+            String::from_utf8(obj).unwrap(),
+            "v 1 2 3 1.0000 0.0000 0.0000\nv 4 5.5 6 0.0000 0.0000 1.0000\n"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "image")]
+    fn test_glyph_palette() {
+        assert_eq!(glyph_palette(&'.'), [0, 0, 0]);
+        assert_ne!(glyph_palette(&'#'), [0, 0, 0]);
+        assert_ne!(glyph_palette(&'#'), glyph_palette(&'@'));
+    }
+
+    #[test]
+    #[cfg(feature = "image")]
+    fn test_render_png() {
+        let path = std::env::temp_dir().join("aoc-viz-test.png");
+        let grid = Grid::new(vec![0_u8, 1, 2, 3], 2, 2);
+        render_png(&grid, |&v| [v * 80, 0, 0], &path).unwrap();
+        let image = image::open(&path).unwrap().into_rgb8();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(image.dimensions(), (2, 2));
+        assert_eq!(image.get_pixel(1, 1).0, [240, 0, 0]);
+    }
+
+    #[test]
+    #[cfg(feature = "image")]
+    fn test_gif() {
+        let mut out = Vec::new();
+        let mut gif = Gif::new(&mut out, glyph_palette, Duration::from_millis(50));
+        gif.frame(|| Grid::new(vec!['.', '#'], 2, 1));
+        gif.frame(|| Grid::new(vec!['#', '.'], 2, 1));
+        gif.finish().unwrap();
+        assert!(out.starts_with(b"GIF89a"));
+    }
+
+    #[test]
+    #[cfg(feature = "svg")]
+    fn test_svg() {
+        let mut svg = Svg::new([0.0, -1.0], [10.0, 5.5]);
+        svg.rect([1.0, 2.0], [3.0, 4.0], Some([255, 0, 16]), None);
+        svg.polygon(&[[0.0, 0.0], [2.5, 0.0], [0.0, 1.0]], None, Some([0, 0, 0]));
+        let mut out = Vec::new();
+        svg.write(&mut out).unwrap();
+        let style = r#"stroke-width="1.5" vector-effect="non-scaling-stroke""#;
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!(
+                "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 -1 10 5.5\">\n\
+                 <rect x=\"1\" y=\"2\" width=\"3\" height=\"4\" \
+                 fill=\"#ff0010\" stroke=\"none\" {style}/>\n\
+                 <polygon points=\"0,0 2.5,0 0,1\" fill=\"none\" stroke=\"#000000\" {style}/>\n\
+                 </svg>\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{Answer, AocError};
+
+/// A day's puzzle: a parser for its input and a solver for each part.
+///
+/// Every day implements this on a unit struct such as [`Day01`](crate::days::Day01),
+/// alongside the `#[aoc]` functions it delegates to.
+pub trait DaySolution {
+    /// Day of the month.
+    const DAY: u8;
+
+    type Parsed;
+
+    /// Parses the puzzle input.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AocError::InvalidInput`] if the input is malformed.
+    fn parse(input: &str) -> Result<Self::Parsed, AocError>;
+
+    /// Solves part 1.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AocError::SolverFailed`] if the input has no answer.
+    fn part1(parsed: &Self::Parsed) -> Result<Answer, AocError>;
+
+    /// Solves part 2.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AocError::SolverFailed`] if the input has no answer.
+    fn part2(parsed: &Self::Parsed) -> Result<Answer, AocError>;
+
+    /// Parses `input` and solves `part`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `part` is not 1 or 2, if `input` is malformed, or if it has no answer.
+    fn solve(part: u8, input: &str) -> Result<Answer, AocError> {
+        let solve = match part {
+            1 => Self::part1,
+            2 => Self::part2,
+            _ => {
+                return Err(AocError::NoSolution {
+                    day: Self::DAY,
+                    part,
+                });
+            }
+        };
+        solve(&Self::parse(input)?)
+    }
+}
+
+#[cfg(all(test, feature = "day01"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        use crate::days::Day01;
+
+        let input = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82";
+        assert_eq!(Day01::solve(1, input), Ok(Answer::from(3_u64)));
+        assert_eq!(Day01::solve(2, input), Ok(Answer::from(6_u64)));
+        assert_eq!(
+            Day01::solve(3, input),
+            Err(AocError::NoSolution { day: 1, part: 3 })
+        );
+        assert!(matches!(
+            Day01::solve(1, "X1"),
+            Err(AocError::InvalidInput(_))
+        ));
+    }
+}
@@ -0,0 +1,232 @@
+//! Checks every solution against the confirmed answers recorded in `answers.toml`.
+//!
+//! The file has a table per day with a value per part:
+//!
+//! ```toml
+//! [day1]
+//! part1 = 1234
+//! part2 = "text answers are quoted"
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use std::{fs, io};
+
+use thiserror::Error;
+
+use crate::Answer;
+use crate::runner::{self, Solution};
+
+/// Where the confirmed answers are stored.
+pub const ANSWERS_PATH: &str = "answers.toml";
+
+/// Where the confirmed answers of `year` are stored: [`ANSWERS_PATH`] for the current year,
+/// `answers-YYYY.toml` for earlier ones.
+#[must_use]
+pub fn answers_path(year: u16) -> PathBuf {
+    if year == runner::YEAR {
+        PathBuf::from(ANSWERS_PATH)
+    } else {
+        PathBuf::from(format!("answers-{year}.toml"))
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum AnswersError {
+    #[error("Failed to read answers: {0}")]
+    Io(#[from] io::Error),
+    #[error("Invalid answers file: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("Invalid key {0:?}, expected dayN.partM")]
+    InvalidKey(String),
+    #[error("Invalid answer for {0}, expected a string or an integer")]
+    InvalidValue(String),
+}
+
+/// Confirmed answers, by day and part.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers(BTreeMap<(u8, u8), Answer>);
+
+impl Answers {
+    /// Reads the answers from `path`, or returns no answers if it doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file can't be read or is malformed.
+    pub fn load(path: &Path) -> Result<Self, AnswersError> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Parses answers from TOML text.
+    ///
+    /// # Errors
+    ///
+    /// Fails on invalid TOML, or keys and values not of the expected form.
+    pub fn parse(text: &str) -> Result<Self, AnswersError> {
+        let table: toml::Table = toml::from_str(text)?;
+        let mut answers = BTreeMap::new();
+        for (day_key, parts) in table {
+            let day = day_key
+                .strip_prefix("day")
+                .and_then(|d| d.parse().ok())
+                .ok_or_else(|| AnswersError::InvalidKey(day_key.clone()))?;
+            let parts = parts
+                .as_table()
+                .ok_or_else(|| AnswersError::InvalidKey(day_key.clone()))?;
+            for (part_key, value) in parts {
+                let key = format!("{day_key}.{part_key}");
+                let part = part_key
+                    .strip_prefix("part")
+                    .and_then(|p| p.parse().ok())
+                    .ok_or_else(|| AnswersError::InvalidKey(key.clone()))?;
+                let answer = match value {
+                    toml::Value::String(text) => Answer::Text(text.clone()),
+                    toml::Value::Integer(n) => Answer::I64(*n),
+                    _ => return Err(AnswersError::InvalidValue(key)),
+                };
+                answers.insert((day, part), answer);
+            }
+        }
+        Ok(Self(answers))
+    }
+
+    #[must_use]
+    pub fn get(&self, day: u8, part: u8) -> Option<&Answer> {
+        self.0.get(&(day, part))
+    }
+}
+
+/// The result of checking one solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Correct,
+    Wrong {
+        expected: Answer,
+        actual: Answer,
+    },
+    Failed(String),
+    /// No answer is recorded yet; the solution's answer is kept for reference.
+    Unconfirmed(Answer),
+    NoInput,
+}
+
+impl Status {
+    /// Whether this indicates a regression.
+    #[must_use]
+    pub const fn is_failure(&self) -> bool {
+        matches!(self, Self::Wrong { .. } | Self::Failed(_))
+    }
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Correct => write!(f, "ok"),
+            Self::Wrong { expected, actual } => {
+                write!(f, "WRONG: expected {expected}, got {actual}")
+            }
+            Self::Failed(err) => write!(f, "FAILED: {err}"),
+            Self::Unconfirmed(answer) => write!(f, "{answer} (unconfirmed)"),
+            Self::NoInput => write!(f, "no input"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub solution: &'static Solution,
+    pub status: Status,
+}
+
+/// Runs `solution` on `input` and compares with the recorded answer.
+#[must_use]
+pub fn check(
+    solution: &'static Solution,
+    input: &Arc<str>,
+    answers: &Answers,
+    timeout: Option<Duration>,
+) -> Status {
+    match solution.run_limited(input, timeout) {
+        Err(err) => Status::Failed(err.to_string()),
+        Ok(outcome) => match answers.get(solution.day, solution.part) {
+            None => Status::Unconfirmed(outcome.answer),
+            Some(expected) if *expected == outcome.answer => Status::Correct,
+            Some(expected) => Status::Wrong {
+                expected: expected.clone(),
+                actual: outcome.answer,
+            },
+        },
+    }
+}
+
+/// Checks every registered solution, including alternates, on its cached input, failing any
+/// that takes longer than `timeout`.
+#[must_use]
+pub fn verify_all(year: u16, answers: &Answers, timeout: Option<Duration>) -> Vec<Check> {
+    let mut checks = Vec::new();
+    for solutions in runner::solutions_for(year).chunk_by(|a, b| a.day == b.day) {
+        let input = runner::read_input(&runner::input_path(year, solutions[0].day)).map(Arc::from);
+        for solution in solutions {
+            let status = input.as_ref().map_or(Status::NoInput, |input| {
+                check(solution, input, answers, timeout)
+            });
+            checks.push(Check { solution, status });
+        }
+    }
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let answers =
+            Answers::parse("[day1]\npart1 = 3\npart2 = \"6\"\n\n[day12]\npart1 = 2\n").unwrap();
+        assert_eq!(answers.get(1, 1), Some(&Answer::I64(3)));
+        assert_eq!(answers.get(1, 2), Some(&Answer::Text("6".to_string())));
+        assert_eq!(answers.get(12, 1), Some(&Answer::U64(2)));
+        assert_eq!(answers.get(12, 2), None);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(matches!(
+            Answers::parse("[dayone]\npart1 = 3\n"),
+            Err(AnswersError::InvalidKey(key)) if key == "dayone"
+        ));
+        assert!(matches!(
+            Answers::parse("[day1]\npart1 = 1.5\n"),
+            Err(AnswersError::InvalidValue(key)) if key == "day1.part1"
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "day01")]
+    fn test_check() {
+        let solution = runner::find(runner::YEAR, 1, 1, None).unwrap();
+        let input = Arc::from("L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82");
+        let answers = Answers::parse("[day1]\npart1 = 3\n").unwrap();
+        assert_eq!(check(solution, &input, &answers, None), Status::Correct);
+        let answers = Answers::parse("[day1]\npart1 = 4\n").unwrap();
+        assert_eq!(
+            check(solution, &input, &answers, None),
+            Status::Wrong {
+                expected: Answer::I64(4),
+                actual: Answer::U64(3)
+            }
+        );
+        assert!(matches!(
+            check(solution, &input, &Answers::default(), None),
+            Status::Unconfirmed(Answer::U64(3))
+        ));
+    }
+}
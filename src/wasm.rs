@@ -0,0 +1,33 @@
+//! JavaScript bindings, enabled with the `wasm` feature, for running the solutions in a browser.
+//!
+//! Build with `cargo rustc --lib --release --target wasm32-unknown-unknown
+//! --no-default-features --features all-days,wasm --crate-type cdylib` and generate the JS glue
+//! with `wasm-bindgen --target web`.
+
+use wasm_bindgen::prelude::*;
+
+use crate::runner;
+
+/// Solves `part` of `day` for `input`, throwing the parse or solver error as a JS `Error`.
+///
+/// # Errors
+///
+/// Fails if there is no such solution, or if it fails on `input`.
+#[wasm_bindgen]
+pub fn solve(day: u8, part: u8, input: &str) -> Result<String, JsError> {
+    crate::solve_day(day, part, input)
+        .map(|answer| answer.to_string())
+        .map_err(|err| JsError::new(&err.to_string()))
+}
+
+/// The days that have at least one solution.
+#[wasm_bindgen]
+#[must_use]
+pub fn days() -> Vec<u8> {
+    let mut days: Vec<u8> = runner::solutions_for(runner::YEAR)
+        .iter()
+        .map(|s| s.day)
+        .collect();
+    days.dedup();
+    days
+}
@@ -0,0 +1,107 @@
+use std::num::ParseIntError;
+
+use thiserror::Error;
+
+use crate::shared::FastMap;
+use crate::shared::parse::normalize_input_trimmed;
+use crate::{Answer, AocError, DaySolution};
+
+#[derive(Debug, Error)]
+enum ParseError {
+    #[error("Syntax error")]
+    SyntaxError,
+    #[error(transparent)]
+    InvalidNumber(#[from] ParseIntError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lists {
+    left: Vec<u32>,
+    right: Vec<u32>,
+}
+
+fn parse(input: &str) -> Result<Lists, ParseError> {
+    let input = normalize_input_trimmed(input);
+    let mut left = Vec::new();
+    let mut right = Vec::new();
+    for line in input.lines() {
+        let (a, b) = line
+            .split_once(char::is_whitespace)
+            .ok_or(ParseError::SyntaxError)?;
+        left.push(a.parse()?);
+        right.push(b.trim_start().parse()?);
+    }
+    Ok(Lists { left, right })
+}
+
+fn part_1(input: &Lists) -> u64 {
+    let mut left = input.left.clone();
+    let mut right = input.right.clone();
+    left.sort_unstable();
+    right.sort_unstable();
+    left.iter()
+        .zip(&right)
+        .map(|(&a, &b)| u64::from(a.abs_diff(b)))
+        .sum()
+}
+
+fn part_2(input: &Lists) -> u64 {
+    let mut counts = FastMap::default();
+    for &b in &input.right {
+        *counts.entry(b).or_insert(0_u64) += 1;
+    }
+    input
+        .left
+        .iter()
+        .map(|&a| u64::from(a) * counts.get(&a).copied().unwrap_or_default())
+        .sum()
+}
+
+pub struct Day01;
+
+impl DaySolution for Day01 {
+    const DAY: u8 = 1;
+
+    type Parsed = Lists;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        parse(input).map_err(AocError::invalid_input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part_1(parsed).into())
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part_2(parsed).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE1: &str = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3";
+
+    #[test]
+    fn test_parse() {
+        let result = parse(EXAMPLE1).unwrap();
+        assert_eq!(result.left, [3, 4, 2, 1, 3, 3]);
+        assert_eq!(result.right, [4, 3, 5, 3, 9, 3]);
+        assert!(matches!(parse("3"), Err(ParseError::SyntaxError)));
+    }
+
+    #[test]
+    fn test_part_1() {
+        let input = parse(EXAMPLE1).unwrap();
+        let result = part_1(&input);
+        assert_eq!(result, 11);
+    }
+
+    #[test]
+    fn test_part_2() {
+        let input = parse(EXAMPLE1).unwrap();
+        let result = part_2(&input);
+        assert_eq!(result, 31);
+    }
+}
@@ -0,0 +1,84 @@
+use thiserror::Error;
+
+use crate::shared::parse::normalize_input_trimmed;
+use crate::{Answer, AocError, DaySolution};
+
+#[derive(Debug, Error)]
+enum ParseError {
+    #[error("Empty line")]
+    EmptyLine,
+}
+
+#[aoc_generator(day@DAY@)]
+fn parse(input: &str) -> Result<Vec<String>, ParseError> {
+    let input = normalize_input_trimmed(input);
+    input
+        .lines()
+        .map(|line| {
+            if line.is_empty() {
+                return Err(ParseError::EmptyLine);
+            }
+            Ok(line.to_owned())
+        })
+        .collect()
+}
+
+#[aoc(day@DAY@, part1)]
+const fn part_1(input: &[String]) -> usize {
+    input.len()
+}
+
+#[aoc(day@DAY@, part2)]
+const fn part_2(input: &[String]) -> usize {
+    input.len()
+}
+
+pub struct Day@DAY2@;
+
+impl DaySolution for Day@DAY2@ {
+    const DAY: u8 = @DAY@;
+
+    type Parsed = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Parsed, AocError> {
+        parse(input).map_err(AocError::invalid_input)
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part_1(parsed).into())
+    }
+
+    fn part2(parsed: &Self::Parsed) -> Result<Answer, AocError> {
+        Ok(part_2(parsed).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE1: &str = "\
+";
+
+    #[test]
+    fn test_parse() {
+        let result = parse(EXAMPLE1).unwrap();
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    #[ignore = "example answer not filled in yet"]
+    fn test_part_1() {
+        let input = parse(EXAMPLE1).unwrap();
+        let result = part_1(&input);
+        assert_eq!(result, 0);
+    }
+
+    #[test]
+    #[ignore = "example answer not filled in yet"]
+    fn test_part_2() {
+        let input = parse(EXAMPLE1).unwrap();
+        let result = part_2(&input);
+        assert_eq!(result, 0);
+    }
+}
@@ -0,0 +1,24 @@
+//! Runs every solution on the cached real inputs and compares with `answers.toml`, or
+//! `answers-<YEAR>.toml` for earlier years. Days without an input are skipped.
+
+use std::time::Duration;
+
+use advent_of_code_2025::runner;
+use advent_of_code_2025::verify::{Answers, answers_path, verify_all};
+
+#[test]
+fn test_recorded_answers() {
+    let mut years: Vec<u16> = runner::solutions().iter().map(|s| s.year).collect();
+    years.dedup();
+    let mut failures = Vec::new();
+    for year in years {
+        let answers = Answers::load(&answers_path(year)).unwrap();
+        failures.extend(
+            verify_all(year, &answers, Some(Duration::from_secs(60)))
+                .into_iter()
+                .filter(|check| check.status.is_failure())
+                .map(|check| format!("{}: {}", check.solution, check.status)),
+        );
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}